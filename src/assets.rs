@@ -0,0 +1,250 @@
+use crate::config::Config;
+use crate::error::ReleaseError;
+use crate::scm::Release;
+use crate::scm::ScmClient;
+use log::info;
+use sha2::{Digest, Sha256};
+use std::fs::File;
+use std::path::{Path, PathBuf};
+
+pub const CHECKSUMS_FILE_NAME: &str = "SHA256SUMS";
+
+/// Compute the SHA-256 of a file, streaming it instead of loading it into memory
+pub fn sha256_file(path: &Path) -> std::result::Result<String, ReleaseError> {
+    let mut file =
+        File::open(path).map_err(|e| format!("Failed to open asset {:?}: {}", path, e))?;
+    let mut hasher = Sha256::new();
+    std::io::copy(&mut file, &mut hasher)
+        .map_err(|e| format!("Failed to read asset {:?}: {}", path, e))?;
+
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+/// Render a `sha256sum`-compatible listing (`<hash>  <file name>`) for the given files
+pub fn render_checksums(paths: &[PathBuf]) -> std::result::Result<String, ReleaseError> {
+    let mut content = String::new();
+
+    for path in paths {
+        let file_name = asset_name(path)?;
+        content.push_str(&format!("{}  {}\n", sha256_file(path)?, file_name));
+    }
+
+    Ok(content)
+}
+
+fn asset_name(path: &Path) -> std::result::Result<&str, ReleaseError> {
+    path.file_name()
+        .and_then(|name| name.to_str())
+        .ok_or_else(|| format!("Asset path {:?} has no valid file name", path).into())
+}
+
+/// Expand the configured asset glob patterns into the files to upload, in a stable order.
+///
+/// A pattern matching no file fails the release rather than silently leaving an artifact out.
+pub fn resolve_asset_paths(
+    base: &Path,
+    patterns: &[String],
+) -> std::result::Result<Vec<PathBuf>, ReleaseError> {
+    let mut paths = Vec::new();
+
+    for pattern in patterns {
+        // Relative patterns are matched from `base`, whose own name is taken literally
+        let full_pattern = if Path::new(pattern).is_absolute() {
+            pattern.clone()
+        } else {
+            format!(
+                "{}/{}",
+                glob::Pattern::escape(&base.to_string_lossy()),
+                pattern
+            )
+        };
+        let mut matched: Vec<PathBuf> = glob::glob(&full_pattern)
+            .map_err(|e| format!("Invalid asset pattern {:?}: {}", pattern, e))?
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| format!("Failed to read assets matching {:?}: {}", pattern, e))?
+            .into_iter()
+            .filter(|path| path.is_file())
+            .collect();
+        if matched.is_empty() {
+            return Err(format!("Asset pattern {:?} matched no files", pattern).into());
+        }
+        matched.sort();
+        paths.extend(matched);
+    }
+
+    // Overlapping patterns must not upload the same file twice
+    let mut seen = std::collections::HashSet::new();
+    paths.retain(|path| seen.insert(path.clone()));
+
+    Ok(paths)
+}
+
+/// The files to attach to a release, resolved before anything is published so a missing
+/// artifact fails the run while it can still be retried from scratch
+#[derive(Debug, Default, PartialEq)]
+pub struct ReleaseAssets {
+    pub paths: Vec<PathBuf>,
+    /// The `SHA256SUMS` listing of `paths`, when `asset_checksums` is set
+    pub checksums: Option<String>,
+}
+
+impl ReleaseAssets {
+    /// Expand the configured `assets` patterns from `base` and checksum the files
+    pub fn resolve(base: &Path, config: &Config) -> std::result::Result<Self, ReleaseError> {
+        let paths =
+            resolve_asset_paths(base, config.release.assets.as_deref().unwrap_or_default())?;
+        let checksums = if config.release.asset_checksums && !paths.is_empty() {
+            Some(render_checksums(&paths)?)
+        } else {
+            None
+        };
+
+        Ok(Self { paths, checksums })
+    }
+}
+
+/// Upload the resolved assets (and their checksums, when enabled) to a release
+pub async fn upload_release_assets(
+    github_client: &dyn ScmClient,
+    release: &Release,
+    assets: &ReleaseAssets,
+) -> std::result::Result<(), ReleaseError> {
+    for path in &assets.paths {
+        github_client
+            .upload_release_asset(release, path, asset_name(path)?)
+            .await?;
+        info!("📎 Uploaded asset {}", path.display());
+    }
+
+    if let Some(checksums) = &assets.checksums {
+        let checksums_path =
+            std::env::temp_dir().join(format!("{}-{}", CHECKSUMS_FILE_NAME, release.id));
+        std::fs::write(&checksums_path, checksums)
+            .map_err(|e| format!("Failed to write {}: {}", CHECKSUMS_FILE_NAME, e))?;
+
+        let result = github_client
+            .upload_release_asset(release, &checksums_path, CHECKSUMS_FILE_NAME)
+            .await;
+        let _ = std::fs::remove_file(&checksums_path);
+        result?;
+
+        info!("🔐 Uploaded {}", CHECKSUMS_FILE_NAME);
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scm::github::GitHubClient;
+    use tempfile::TempDir;
+
+    #[tokio::test]
+    async fn test_upload_release_assets_with_checksums() {
+        let temp_dir = TempDir::new().expect("Failed to create temp directory");
+        let asset = temp_dir.path().join("app.bin");
+        std::fs::write(&asset, "hello").unwrap();
+
+        let mut server = mockito::Server::new_async().await;
+        let asset_mock = server
+            .mock("POST", "/upload")
+            .match_query(mockito::Matcher::UrlEncoded(
+                "name".to_string(),
+                "app.bin".to_string(),
+            ))
+            .match_body("hello")
+            .with_status(201)
+            .create_async()
+            .await;
+        let checksums_mock = server
+            .mock("POST", "/upload")
+            .match_query(mockito::Matcher::UrlEncoded(
+                "name".to_string(),
+                CHECKSUMS_FILE_NAME.to_string(),
+            ))
+            .match_body(
+                "2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824  app.bin\n",
+            )
+            .with_status(201)
+            .create_async()
+            .await;
+
+        let client = GitHubClient::new("test-token".to_string()).unwrap();
+        let release = Release {
+            id: 1,
+            tag_name: "v1.0.0".to_string(),
+            name: Some("Release v1.0.0".to_string()),
+            body: None,
+            draft: false,
+            prerelease: false,
+            url: "https://github.com/owner/repo/releases/tag/v1.0.0".to_string(),
+            upload_url: format!("{}/upload{{?name,label}}", server.url()),
+        };
+        let mut config = Config::default();
+        config.release.assets = Some(vec![asset.to_string_lossy().to_string()]);
+        config.release.asset_checksums = true;
+
+        let assets = ReleaseAssets::resolve(Path::new("."), &config).unwrap();
+        upload_release_assets(&client, &release, &assets)
+            .await
+            .unwrap();
+
+        asset_mock.assert_async().await;
+        checksums_mock.assert_async().await;
+    }
+
+    #[test]
+    fn test_resolve_asset_paths_expands_globs() {
+        let temp_dir = TempDir::new().expect("Failed to create temp directory");
+        let dist = temp_dir.path().join("dist");
+        std::fs::create_dir_all(dist.join("nested.tar.gz")).unwrap();
+        for name in ["app-macos.tar.gz", "app-linux.tar.gz", "notes.txt"] {
+            std::fs::write(dist.join(name), "").unwrap();
+        }
+        let pattern = |suffix: &str| format!("{}/{}", dist.display(), suffix);
+
+        let paths = resolve_asset_paths(
+            Path::new("."),
+            &[pattern("*.tar.gz"), pattern("app-linux.tar.gz")],
+        )
+        .unwrap();
+        assert_eq!(
+            paths,
+            vec![dist.join("app-linux.tar.gz"), dist.join("app-macos.tar.gz")]
+        );
+
+        let error = resolve_asset_paths(Path::new("."), &[pattern("*.zip")]).unwrap_err();
+        assert!(error.to_string().contains("matched no files"));
+        assert!(resolve_asset_paths(Path::new("."), &[pattern("[")]).is_err());
+    }
+
+    #[test]
+    fn test_sha256_file() {
+        let temp_dir = TempDir::new().expect("Failed to create temp directory");
+        let path = temp_dir.path().join("hello.txt");
+        std::fs::write(&path, "hello").unwrap();
+
+        assert_eq!(
+            sha256_file(&path).unwrap(),
+            "2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824"
+        );
+    }
+
+    #[test]
+    fn test_render_checksums_matches_asset_bytes() {
+        let temp_dir = TempDir::new().expect("Failed to create temp directory");
+        let first = temp_dir.path().join("app-linux.tar.gz");
+        let second = temp_dir.path().join("app-macos.tar.gz");
+        std::fs::write(&first, "hello").unwrap();
+        std::fs::write(&second, "").unwrap();
+
+        let content = render_checksums(&[first, second]).unwrap();
+
+        assert_eq!(
+            content,
+            "2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824  app-linux.tar.gz\n\
+             e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855  app-macos.tar.gz\n"
+        );
+    }
+}
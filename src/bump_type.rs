@@ -1,3 +1,7 @@
+use crate::conventional_commit::ConventionalCommit;
+use log::warn;
+use semver::Version;
+
 #[derive(Debug, Clone, PartialEq)]
 pub enum BumpType {
     Major,
@@ -7,17 +11,147 @@ pub enum BumpType {
 }
 
 impl BumpType {
-    pub fn from_conventional_commit(message: &str) -> Self {
-        let message = message.to_lowercase();
+    /// Lowercase name as accepted by [`BumpType::from_name`], e.g. `"minor"`
+    pub fn name(&self) -> &'static str {
+        match self {
+            BumpType::Major => "major",
+            BumpType::Minor => "minor",
+            BumpType::Patch => "patch",
+            BumpType::None => "none",
+        }
+    }
 
-        if message.starts_with("feat!") || message.contains("!") {
+    /// Parse a bump name such as `"minor"` (case-insensitive)
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.trim().to_lowercase().as_str() {
+            "major" => Some(BumpType::Major),
+            "minor" => Some(BumpType::Minor),
+            "patch" => Some(BumpType::Patch),
+            "none" => Some(BumpType::None),
+            _ => None,
+        }
+    }
+
+    fn rank(&self) -> u8 {
+        match self {
+            BumpType::None => 0,
+            BumpType::Patch => 1,
+            BumpType::Minor => 2,
+            BumpType::Major => 3,
+        }
+    }
+
+    /// The bump that leads from `from` to the higher version `to`
+    pub fn between(from: &Version, to: &Version) -> Self {
+        if to.major != from.major {
             BumpType::Major
-        } else if message.starts_with("feat") {
+        } else if to.minor != from.minor {
             BumpType::Minor
-        } else if message.starts_with("fix") || message.starts_with("perf") {
+        } else if to > from {
             BumpType::Patch
         } else {
             BumpType::None
         }
     }
+
+    /// The larger of two bumps
+    pub fn max(self, other: BumpType) -> Self {
+        if other.rank() > self.rank() {
+            other
+        } else {
+            self
+        }
+    }
+
+    /// Enforce `max` as the largest allowed bump: either clamp down to it or fail
+    pub fn capped_at(self, max: &BumpType, clamp: bool) -> Result<Self, String> {
+        if self.rank() <= max.rank() {
+            return Ok(self);
+        }
+
+        if clamp {
+            warn!(
+                "⚠️  {:?} bump exceeds the configured maximum, clamping to {:?}",
+                self, max
+            );
+            Ok(max.clone())
+        } else {
+            Err(format!(
+                "Computed {:?} bump exceeds the configured max_bump ({:?}); refusing to release",
+                self, max
+            ))
+        }
+    }
+
+    /// Bump for a commit message with default parsing options; unparseable messages don't bump
+    pub fn from_conventional_commit(message: &str) -> Self {
+        ConventionalCommit::parse(message)
+            .map(|commit| commit.bump_type())
+            .unwrap_or(BumpType::None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_capped_at_within_limit() {
+        assert_eq!(
+            BumpType::Patch.capped_at(&BumpType::Minor, false),
+            Ok(BumpType::Patch)
+        );
+        assert_eq!(
+            BumpType::Minor.capped_at(&BumpType::Minor, false),
+            Ok(BumpType::Minor)
+        );
+    }
+
+    #[test]
+    fn test_capped_at_clamps() {
+        assert_eq!(
+            BumpType::Major.capped_at(&BumpType::Minor, true),
+            Ok(BumpType::Minor)
+        );
+    }
+
+    #[test]
+    fn test_capped_at_errors_when_exceeded() {
+        let result = BumpType::Major.capped_at(&BumpType::Minor, false);
+        assert!(result
+            .unwrap_err()
+            .contains("exceeds the configured max_bump"));
+    }
+
+    #[test]
+    fn test_from_conventional_commit() {
+        assert_eq!(
+            BumpType::from_conventional_commit("feat: add login"),
+            BumpType::Minor
+        );
+        assert_eq!(
+            BumpType::from_conventional_commit("fix(api)!: drop v1"),
+            BumpType::Major
+        );
+        assert_eq!(
+            BumpType::from_conventional_commit("not a conventional commit"),
+            BumpType::None
+        );
+    }
+
+    #[test]
+    fn test_exclamation_in_description_is_not_breaking() {
+        assert_eq!(
+            BumpType::from_conventional_commit("fix: handle edge case when value != 0"),
+            BumpType::Patch
+        );
+        assert_eq!(
+            BumpType::from_conventional_commit("feat(ui): say hello!"),
+            BumpType::Minor
+        );
+        assert_eq!(
+            BumpType::from_conventional_commit("chore: wow!"),
+            BumpType::None
+        );
+    }
 }
@@ -0,0 +1,191 @@
+use crate::commit::Commit;
+use crate::config::Config;
+use crate::error::ReleaseError;
+use crate::release_notes::{render_changes_with_heading, PullRequestLinks, ReleaseLinks};
+use semver::Version;
+use std::path::Path;
+
+/// Preamble of a freshly created changelog
+const CHANGELOG_HEADER: &str = "# Changelog
+
+All notable changes to this project will be documented in this file.
+
+The format is based on [Keep a Changelog](https://keepachangelog.com/en/1.1.0/),
+and this project adheres to [Semantic Versioning](https://semver.org/spec/v2.0.0.html).
+";
+
+/// Render the changelog section of a release: a `## [version] - date` heading followed by
+/// the same grouped entries as the release notes, one heading level down. With `links` the
+/// version links to the comparison with the previous release.
+pub fn render_changelog_section(
+    version: &Version,
+    date: &str,
+    commits: &[Commit],
+    config: &Config,
+    pull_requests: &PullRequestLinks,
+    links: Option<&ReleaseLinks>,
+) -> String {
+    let changes = render_changes_with_heading(commits, config, pull_requests, links, "###");
+    let mut section = match links.and_then(ReleaseLinks::compare_url) {
+        Some(compare_url) => format!("## [{}]({}) - {}\n", version, compare_url, date),
+        None => format!("## [{}] - {}\n", version, date),
+    };
+    if !changes.is_empty() {
+        section.push('\n');
+        section.push_str(&changes);
+    }
+    section
+}
+
+/// Insert `section` above the newest release in `existing`, below the preamble and any
+/// `## [Unreleased]` section, leaving earlier entries untouched
+pub fn prepend_section(existing: &str, section: &str) -> String {
+    if existing.trim().is_empty() {
+        return format!("{}\n{}", CHANGELOG_HEADER, section);
+    }
+
+    let mut offset = 0;
+    for line in existing.split_inclusive('\n') {
+        if line.starts_with("## ") && !line.starts_with("## [Unreleased]") {
+            return format!(
+                "{}{}\n{}",
+                &existing[..offset],
+                section,
+                &existing[offset..]
+            );
+        }
+        offset += line.len();
+    }
+
+    // No release yet: append after the preamble
+    let separator = if existing.ends_with("\n\n") {
+        ""
+    } else if existing.ends_with('\n') {
+        "\n"
+    } else {
+        "\n\n"
+    };
+    format!("{}{}{}", existing, separator, section)
+}
+
+/// Prepend `section` to the changelog at `path`, creating the file when it doesn't exist
+pub fn update_changelog(path: &Path, section: &str) -> std::result::Result<(), ReleaseError> {
+    let existing = if path.exists() {
+        std::fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read changelog {:?}: {}", path, e))?
+    } else {
+        String::new()
+    };
+
+    std::fs::write(path, prepend_section(&existing, section))
+        .map_err(|e| format!("Failed to write changelog {:?}: {}", path, e))?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn commit(sha: &str, message: &str) -> Commit {
+        Commit {
+            sha: sha.to_string(),
+            message: message.to_string(),
+            author_name: "Test User".to_string(),
+            author_email: "test@example.com".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_render_changelog_section() {
+        let commits = vec![
+            commit("aaaaaaa1111111", "feat(api): add tokens"),
+            commit("bbbbbbb2222222", "fix: small fix"),
+            commit("ccccccc3333333", "chore: bump deps"),
+        ];
+        let section = render_changelog_section(
+            &Version::parse("1.2.0").unwrap(),
+            "2024-05-01",
+            &commits,
+            &Config::default(),
+            &PullRequestLinks::new(),
+            None,
+        );
+
+        assert_eq!(
+            section,
+            "## [1.2.0] - 2024-05-01\n\
+             \n### Features\n\n- **api:** add tokens (aaaaaaa)\n\
+             \n### Bug Fixes\n\n- small fix (bbbbbbb)\n"
+        );
+    }
+
+    #[test]
+    fn test_changelog_section_links_to_compare() {
+        let links = ReleaseLinks::new(
+            "https://github.com",
+            "owner/repo",
+            Some("v1.1.0".to_string()),
+            "v1.2.0".to_string(),
+        );
+        let section = render_changelog_section(
+            &Version::parse("1.2.0").unwrap(),
+            "2024-05-01",
+            &[commit("aaaaaaa1111111", "fix: small fix")],
+            &Config::default(),
+            &PullRequestLinks::new(),
+            Some(&links),
+        );
+
+        assert!(section.starts_with(
+            "## [1.2.0](https://github.com/owner/repo/compare/v1.1.0...v1.2.0) - 2024-05-01\n"
+        ));
+        assert!(section.contains(
+            "- small fix ([aaaaaaa](https://github.com/owner/repo/commit/aaaaaaa1111111))\n"
+        ));
+    }
+
+    #[test]
+    fn test_prepend_keeps_previous_entries() {
+        let existing = format!(
+            "{}\n## [Unreleased]\n\n## [1.1.0] - 2024-04-01\n\n### Features\n\n- old feature (0000000)\n",
+            CHANGELOG_HEADER
+        );
+        let section = "## [1.2.0] - 2024-05-01\n\n### Bug Fixes\n\n- small fix (bbbbbbb)\n";
+
+        let updated = prepend_section(&existing, section);
+
+        assert_eq!(
+            updated,
+            format!(
+                "{}\n## [Unreleased]\n\n{}\n## [1.1.0] - 2024-04-01\n\n### Features\n\n- old feature (0000000)\n",
+                CHANGELOG_HEADER, section
+            )
+        );
+    }
+
+    #[test]
+    fn test_update_changelog_creates_and_extends_file() {
+        let temp_dir = TempDir::new().expect("Failed to create temp directory");
+        let path = temp_dir.path().join("CHANGELOG.md");
+
+        update_changelog(&path, "## [1.0.0] - 2024-04-01\n").unwrap();
+        update_changelog(&path, "## [1.1.0] - 2024-05-01\n").unwrap();
+
+        let content = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(
+            content,
+            format!(
+                "{}\n## [1.1.0] - 2024-05-01\n\n## [1.0.0] - 2024-04-01\n",
+                CHANGELOG_HEADER
+            )
+        );
+    }
+
+    #[test]
+    fn test_prepend_to_changelog_without_releases() {
+        let updated = prepend_section("# Changelog\n", "## [0.1.0] - 2024-05-01\n");
+        assert_eq!(updated, "# Changelog\n\n## [0.1.0] - 2024-05-01\n");
+    }
+}
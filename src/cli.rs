@@ -1,62 +1,88 @@
+use crate::error::ReleaseError;
+use crate::scm::git::SafeDirectoryScope;
+use clap::error::ErrorKind;
+use clap::Parser;
 use std::env;
+use std::ffi::OsString;
 use std::path::PathBuf;
 
-#[derive(Debug)]
+/// A flexible, config-driven release flow that scales from a single package to large monorepos
+#[derive(Debug, Parser)]
+#[command(name = "conventional-release-action", version)]
 pub struct Args {
+    /// Path to the configuration file
+    #[arg(long, value_name = "FILE", default_value = ".release-config.toml")]
     pub config_file: PathBuf,
+    /// Run in dry-run mode without creating releases
+    #[arg(long)]
     pub dry_run: bool,
+    /// Working directory
+    #[arg(long, value_name = "DIR", default_value = ".")]
     pub working_directory: PathBuf,
+    /// Check configuration, git and GitHub access without releasing
+    #[arg(long)]
+    pub self_test: bool,
+    /// Only check that the configuration file is well-formed and valid
+    #[arg(long)]
+    pub validate_config: bool,
+    /// Analyze commits after <REF> instead of the previous release
+    #[arg(long, value_name = "REF")]
+    pub since: Option<String>,
+    /// Like --since; a bare version (1.2.0) gets the tag prefix/suffix
+    #[arg(long, value_name = "TAG")]
+    pub since_tag: Option<String>,
+    /// Repository to release [default: $GITHUB_REPOSITORY, then the origin remote]
+    #[arg(long, value_name = "OWNER/NAME")]
+    pub repository: Option<String>,
+    /// Git remote to push the release to [default: `remote` from the config, then origin]
+    #[arg(long, value_name = "NAME")]
+    pub remote: Option<String>,
+    /// Read the GitHub token from <FILE> instead of $GITHUB_TOKEN [default: $GITHUB_TOKEN_FILE]
+    #[arg(long, value_name = "FILE")]
+    pub token_file: Option<PathBuf>,
+    /// Compute the bump for a commit message file
+    #[arg(
+        long,
+        value_name = "FILE",
+        num_args = 0..=1,
+        default_missing_value = ".git/COMMIT_EDITMSG"
+    )]
+    pub message_file: Option<PathBuf>,
+    /// Report each analyzed commit's type, scope and bump, and the bump they add up to
+    #[arg(long)]
+    pub explain: bool,
+    /// Use this as the current version instead of reading tags
+    #[arg(long, value_name = "X.Y.Z")]
+    pub current_version: Option<String>,
+    /// Also report details such as the parents of the release commit
+    #[arg(long, short, conflicts_with = "quiet")]
+    pub verbose: bool,
+    /// Only report warnings and errors
+    #[arg(long, short)]
+    pub quiet: bool,
+    /// Git config the repository is trusted in: global, system or none [default: $SAFE_DIRECTORY_SCOPE, then global]
+    #[arg(long, value_name = "SCOPE", value_parser = SafeDirectoryScope::parse)]
+    pub safe_directory_scope: Option<SafeDirectoryScope>,
 }
 
 impl Args {
-    pub fn parse() -> Self {
-        let args: Vec<String> = env::args().collect();
-        let mut config_file = PathBuf::from(".release-config.toml");
-        let mut dry_run = false;
-        let mut working_directory = PathBuf::from(".");
-
-        let mut i = 1;
-        while i < args.len() {
-            match args[i].as_str() {
-                "--config-file" => {
-                    if i + 1 < args.len() {
-                        config_file = PathBuf::from(&args[i + 1]);
-                        i += 2;
-                    } else {
-                        eprintln!("Error: --config-file requires a value");
-                        std::process::exit(1);
-                    }
-                }
-                "--dry-run" => {
-                    dry_run = true;
-                    i += 1;
-                }
-                "--working-directory" => {
-                    if i + 1 < args.len() {
-                        working_directory = PathBuf::from(&args[i + 1]);
-                        i += 2;
-                    } else {
-                        eprintln!("Error: --working-directory requires a value");
-                        std::process::exit(1);
-                    }
-                }
-                "--help" | "-h" => {
-                    Self::print_help();
-                    std::process::exit(0);
-                }
-                _ => {
-                    eprintln!("Error: Unknown argument: {}", args[i]);
-                    eprintln!("Use --help for usage information");
-                    std::process::exit(1);
-                }
+    /// Parse a command line, returning usage errors instead of exiting. `--help` and
+    /// `--version` still print their output and exit.
+    pub fn try_parse_args<I, T>(args: I) -> Result<Self, ReleaseError>
+    where
+        I: IntoIterator<Item = T>,
+        T: Into<OsString> + Clone,
+    {
+        Self::try_parse_from(args).map_err(|e| match e.kind() {
+            ErrorKind::DisplayHelp
+            | ErrorKind::DisplayVersion
+            | ErrorKind::DisplayHelpOnMissingArgumentOrSubcommand => e.exit(),
+            _ => {
+                let message = e.render().to_string();
+                let message = message.strip_prefix("error: ").unwrap_or(&message);
+                ReleaseError::Config(message.trim_end().to_string())
             }
-        }
-
-        Self {
-            config_file,
-            dry_run,
-            working_directory,
-        }
+        })
     }
 
     pub fn from_env() -> Self {
@@ -71,17 +97,136 @@ impl Args {
             working_directory: env::var("WORKING_DIRECTORY")
                 .unwrap_or_else(|_| ".".to_string())
                 .into(),
+            self_test: env::var("SELF_TEST")
+                .unwrap_or_else(|_| "false".to_string())
+                .parse()
+                .unwrap_or(false),
+            validate_config: env::var("VALIDATE_CONFIG")
+                .unwrap_or_else(|_| "false".to_string())
+                .parse()
+                .unwrap_or(false),
+            since: env::var("SINCE").ok().filter(|value| !value.is_empty()),
+            since_tag: env::var("SINCE_TAG").ok().filter(|value| !value.is_empty()),
+            repository: None,
+            remote: env::var("REMOTE").ok().filter(|value| !value.is_empty()),
+            token_file: env::var("GITHUB_TOKEN_FILE")
+                .ok()
+                .filter(|value| !value.is_empty())
+                .map(PathBuf::from),
+            message_file: None,
+            explain: env::var("EXPLAIN")
+                .unwrap_or_else(|_| "false".to_string())
+                .parse()
+                .unwrap_or(false),
+            current_version: env::var("CURRENT_VERSION")
+                .ok()
+                .filter(|value| !value.is_empty()),
+            verbose: env::var("VERBOSE")
+                .unwrap_or_else(|_| "false".to_string())
+                .parse()
+                .unwrap_or(false),
+            quiet: env::var("QUIET")
+                .unwrap_or_else(|_| "false".to_string())
+                .parse()
+                .unwrap_or(false),
+            // Read from SAFE_DIRECTORY_SCOPE when the repository is opened, so a bad value fails the run
+            safe_directory_scope: None,
         }
     }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_valid_arguments() {
+        let args = Args::try_parse_from([
+            "conventional-release-action",
+            "--config-file",
+            "release.yaml",
+            "--dry-run",
+            "--working-directory",
+            "packages/app",
+            "--since-tag",
+            "1.2.0",
+            "--explain",
+            "--token-file",
+            "/run/secrets/github_token",
+        ])
+        .unwrap();
+
+        assert_eq!(args.config_file, PathBuf::from("release.yaml"));
+        assert!(args.dry_run);
+        assert_eq!(args.working_directory, PathBuf::from("packages/app"));
+        assert_eq!(args.since_tag.as_deref(), Some("1.2.0"));
+        assert!(!args.self_test);
+        assert!(args.message_file.is_none());
+        assert!(args.remote.is_none());
+        assert!(args.explain);
+        assert_eq!(
+            args.token_file,
+            Some(PathBuf::from("/run/secrets/github_token"))
+        );
+    }
+
+    #[test]
+    fn test_parse_defaults() {
+        let args = Args::try_parse_from(["conventional-release-action"]).unwrap();
+
+        assert_eq!(args.config_file, PathBuf::from(".release-config.toml"));
+        assert_eq!(args.working_directory, PathBuf::from("."));
+        assert!(!args.dry_run);
+        assert!(args.safe_directory_scope.is_none());
+
+        let args = Args::try_parse_from(["conventional-release-action", "--message-file"]).unwrap();
+        assert_eq!(
+            args.message_file,
+            Some(PathBuf::from(".git/COMMIT_EDITMSG"))
+        );
+    }
+
+    #[test]
+    fn test_parse_invalid_arguments() {
+        let error = Args::try_parse_from(["conventional-release-action", "--unknown"]).unwrap_err();
+        assert_eq!(error.kind(), clap::error::ErrorKind::UnknownArgument);
+
+        let error =
+            Args::try_parse_from(["conventional-release-action", "--config-file"]).unwrap_err();
+        assert_eq!(error.kind(), clap::error::ErrorKind::InvalidValue);
+
+        let error = Args::try_parse_from(["conventional-release-action", "--version"]).unwrap_err();
+        assert_eq!(error.kind(), clap::error::ErrorKind::DisplayVersion);
+    }
+
+    #[test]
+    fn test_parse_safe_directory_scope() {
+        let args = Args::try_parse_from([
+            "conventional-release-action",
+            "--safe-directory-scope",
+            "none",
+        ])
+        .unwrap();
+        assert_eq!(args.safe_directory_scope, Some(SafeDirectoryScope::None));
+
+        let error = Args::try_parse_args([
+            "conventional-release-action",
+            "--safe-directory-scope",
+            "local",
+        ])
+        .unwrap_err();
+        assert_eq!(error.exit_code(), 2);
+        assert!(error.to_string().contains("local"), "{}", error);
+    }
+
+    #[test]
+    fn test_usage_errors_are_config_errors() {
+        let error = Args::try_parse_args(["conventional-release-action", "--unknown"]).unwrap_err();
+        assert!(matches!(error, ReleaseError::Config(_)));
+        assert_eq!(error.exit_code(), 2);
+        assert!(error.to_string().contains("--unknown"), "{}", error);
 
-    fn print_help() {
-        println!("conventional-release-action");
-        println!("A flexible, config-driven release flow that scales from a single package to large monorepos");
-        println!();
-        println!("OPTIONS:");
-        println!("    --config-file <FILE>           Path to the configuration file [default: .release-config.toml]");
-        println!("    --dry-run                      Run in dry-run mode without creating releases");
-        println!("    --working-directory <DIR>      Working directory [default: .]");
-        println!("    --help, -h                     Print help information");
+        let args = Args::try_parse_args(["conventional-release-action", "--dry-run"]).unwrap();
+        assert!(args.dry_run);
     }
 }
@@ -2,5 +2,6 @@
 pub struct Commit {
     pub sha: String,
     pub message: String,
+    pub author_name: String,
+    pub author_email: String,
 }
-
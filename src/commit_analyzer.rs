@@ -1,17 +1,232 @@
+use crate::error::ReleaseError;
 use crate::scm::git::open_repository;
-use git2::Commit as GitCommit;
+use git2::{Commit as GitCommit, DiffOptions, Repository};
+use log::info;
 
 use crate::bump_type::BumpType;
 use crate::commit::Commit;
-use std::error::Error;
+use crate::config::Config;
+use crate::conventional_commit::{ConventionalCommit, ParseOptions};
+use crate::release::{find_previous_release_commit, find_release_commits};
+use crate::scm::RepositoryInfo;
+use crate::scm::ScmClient;
+use regex::Regex;
+use std::path::Path;
+
+pub async fn get_impact_from_latest_commit(
+    base: &Path,
+    config: &Config,
+) -> Result<BumpType, ReleaseError> {
+    let commit = get_last_commit(base).await?;
+    Ok(bump_for_message(&commit.message, config))
+}
+
+/// Determine the bump of a single commit message, honoring the commit parsing config
+pub fn bump_for_message(message: &str, config: &Config) -> BumpType {
+    let options = ParseOptions::from_config(config);
+    ConventionalCommit::parse_with(message, &options)
+        .map(|commit| commit.bump_type_with(&options))
+        .unwrap_or(BumpType::None)
+}
+
+/// Read the bump from a checked release-type box in the body of the PR that produced `commit_sha`.
+///
+/// Returns `None` when the commit has no associated PR or no box is checked.
+pub async fn get_impact_from_pull_request(
+    github_client: &dyn ScmClient,
+    repo_info: &RepositoryInfo,
+    commit_sha: &str,
+    config: &Config,
+) -> Result<Option<BumpType>, ReleaseError> {
+    let pattern = Regex::new(config.version.pr_body_pattern())
+        .map_err(|e| format!("Invalid pr_body_pattern: {}", e))?;
+
+    let pull_requests = github_client
+        .get_pull_requests_for_commit(repo_info, commit_sha)
+        .await?;
+
+    for pull_request in pull_requests {
+        let body = pull_request.body.as_deref().unwrap_or_default();
+        if let Some(bump) = bump_from_pr_body(body, &pattern) {
+            info!(
+                "☑️  Using release type from PR #{}: {:?}",
+                pull_request.number, bump
+            );
+            return Ok(Some(bump));
+        }
+    }
+
+    Ok(None)
+}
+
+fn bump_from_pr_body(body: &str, pattern: &Regex) -> Option<BumpType> {
+    pattern
+        .captures(body)
+        .and_then(|captures| captures.get(1))
+        .and_then(|name| BumpType::from_name(name.as_str()))
+}
+
+/// Determine the bump a not-yet-created commit would cause from its message file
+/// (e.g. `.git/COMMIT_EDITMSG` in a `commit-msg` hook). Comment lines are ignored.
+pub fn get_impact_from_message_file(
+    path: &std::path::Path,
+    config: &Config,
+) -> Result<BumpType, ReleaseError> {
+    let content = std::fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read commit message file {:?}: {}", path, e))?;
+    let message: Vec<&str> = content
+        .lines()
+        .filter(|line| !line.starts_with('#'))
+        .collect();
+    let message = message.join("\n");
+
+    if has_skip_release_marker(&message) {
+        return Ok(BumpType::None);
+    }
+    Ok(bump_for_message(message.trim(), config))
+}
+
+/// The largest bump of any commit in `commits`, e.g. everything since the previous release
+pub fn highest_bump(commits: &[Commit], config: &Config) -> BumpType {
+    commits
+        .iter()
+        .map(|commit| bump_for_message(&commit.message, config))
+        .fold(BumpType::None, BumpType::max)
+}
+
+/// One line per commit with its short SHA, parsed type and scope and the bump it calls for,
+/// followed by the bump they add up to, e.g. `a1b2c3d feat(api) → minor`
+pub fn explain_bump(commits: &[Commit], config: &Config) -> String {
+    let options = ParseOptions::from_config(config);
+    let mut explanation = String::new();
+
+    for commit in commits {
+        let short_sha = &commit.sha[..commit.sha.len().min(7)];
+        let (kind, bump) = match ConventionalCommit::parse_with(&commit.message, &options) {
+            Ok(parsed) => {
+                let scope = parsed
+                    .scope
+                    .as_ref()
+                    .map(|scope| format!("({})", scope))
+                    .unwrap_or_default();
+                let breaking = if parsed.breaking_change { "!" } else { "" };
+                (
+                    format!("{}{}{}", parsed.commit_type, scope, breaking),
+                    parsed.bump_type_with(&options),
+                )
+            }
+            Err(_) => ("(not conventional)".to_string(), BumpType::None),
+        };
+        explanation.push_str(&format!("{} {} → {}\n", short_sha, kind, bump.name()));
+    }
+
+    explanation.push_str(&format!(
+        "Commits call for: {} bump from {} commit(s)",
+        highest_bump(commits, config).name(),
+        commits.len()
+    ));
+    explanation
+}
+
+/// The bump that is actually released, with each rule that changed it on its own line
+pub fn explain_result(bump: &BumpType, adjustments: &[String]) -> String {
+    let mut explanation = format!("Result: {} bump", bump.name());
+    for adjustment in adjustments {
+        explanation.push_str(&format!("\n  ↳ {}", adjustment));
+    }
+    explanation
+}
+
+/// Markers that hold back a release even when commits would otherwise trigger one
+const SKIP_RELEASE_MARKERS: [&str; 2] = ["[skip release]", "[no release]"];
+
+/// Whether `message` asks for no release with `[skip release]` or `[no release]`
+pub fn has_skip_release_marker(message: &str) -> bool {
+    let message = message.to_lowercase();
+    SKIP_RELEASE_MARKERS
+        .iter()
+        .any(|marker| message.contains(marker))
+}
+
+/// Split `commits` into those that count towards a release and those marked with
+/// `[skip release]` / `[no release]`, which never do
+pub fn split_skip_release_commits(commits: Vec<Commit>) -> (Vec<Commit>, Vec<Commit>) {
+    commits
+        .into_iter()
+        .partition(|commit| !has_skip_release_marker(&commit.message))
+}
 
-pub async fn get_impact_from_latest_commit() -> Result<BumpType, Box<dyn Error>> {
-    let commit = get_last_commit().await?;
-    Ok(BumpType::from_conventional_commit(&commit.message))
+/// Version forced by the newest `Release-As:` footer among `commits`, which must be newer
+/// than `current`
+pub fn release_as_version(
+    commits: &[Commit],
+    current: &semver::Version,
+) -> Result<Option<semver::Version>, ReleaseError> {
+    let Some(requested) = commits.iter().find_map(|commit| {
+        ConventionalCommit::parse(&commit.message)
+            .ok()?
+            .release_as()
+            .map(str::to_string)
+    }) else {
+        return Ok(None);
+    };
+
+    let version = semver::Version::parse(&requested).map_err(|e| {
+        ReleaseError::InvalidVersion(format!("Invalid Release-As version {:?}: {}", requested, e))
+    })?;
+    if version <= *current {
+        return Err(ReleaseError::InvalidVersion(format!(
+            "Release-As version {} must be greater than the current version {}",
+            version, current
+        )));
+    }
+
+    Ok(Some(version))
+}
+
+/// Count the commits whose messages would trigger a release on their own
+pub fn count_releasable_commits(commits: &[Commit], config: &Config) -> usize {
+    commits
+        .iter()
+        .filter(|commit| bump_for_message(&commit.message, config) != BumpType::None)
+        .count()
+}
+
+/// Turn a `--since-tag` value into a tag name, adding the configured prefix/separator/suffix to bare versions
+pub fn resolve_since_tag(name: &str, config: &Config) -> String {
+    if semver::Version::parse(name).is_ok() {
+        config.version.tag_name(&name)
+    } else {
+        name.to_string()
+    }
 }
 
-async fn get_last_commit() -> Result<Commit, Box<dyn Error>> {
-    let repo = open_repository(".")?;
+/// Tag of the most recent stable (non-prerelease, published) GitHub release.
+///
+/// A stable release's notes start here, so they also cover what earlier prereleases shipped.
+pub async fn last_stable_release_tag(
+    github_client: &dyn ScmClient,
+    repo_info: &RepositoryInfo,
+    config: &Config,
+) -> Result<Option<String>, ReleaseError> {
+    let releases = github_client.list_releases(repo_info).await?;
+
+    let latest = releases
+        .into_iter()
+        .filter(|release| !release.prerelease && !release.draft)
+        .filter_map(|release| {
+            let version =
+                semver::Version::parse(config.version.version_part(&release.tag_name)).ok()?;
+            Some((version, release.tag_name))
+        })
+        .max_by(|a, b| a.0.cmp(&b.0))
+        .map(|(_, tag_name)| tag_name);
+
+    Ok(latest)
+}
+
+pub async fn get_last_commit(base: &Path) -> Result<Commit, ReleaseError> {
+    let repo = open_repository(base)?;
 
     // Get only the HEAD commit (last commit)
     let head_commit = repo
@@ -24,20 +239,127 @@ async fn get_last_commit() -> Result<Commit, Box<dyn Error>> {
     Ok(commit)
 }
 
-fn parse_commit(git_commit: &GitCommit) -> Result<Commit, Box<dyn Error>> {
+/// Collect the commits reachable from HEAD that are not part of the previous release
+pub async fn get_commits_since_previous_release(
+    base: &Path,
+    config: &Config,
+    since: Option<&str>,
+) -> Result<Vec<Commit>, ReleaseError> {
+    let repo = open_repository(base)?;
+    collect_release_window(&repo, config, since).await
+}
+
+/// Walk from HEAD back to the previous release, newest commit first.
+///
+/// `since` overrides the start of the window with any revision (tag, branch or SHA).
+pub async fn collect_release_window(
+    repo: &Repository,
+    config: &Config,
+    since: Option<&str>,
+) -> Result<Vec<Commit>, ReleaseError> {
+    let mut revwalk = repo.revwalk()?;
+    revwalk.push_head()?;
+
+    let base_oid = match since {
+        Some(revision) => Some(
+            repo.revparse_single(revision)
+                .map_err(|e| format!("Failed to resolve --since revision {:?}: {}", revision, e))?
+                .id(),
+        ),
+        None => find_previous_release_commit(repo, config).await?,
+    };
+
+    if let Some(oid) = base_oid {
+        // Tags may point at annotated tag objects, so peel to the commit
+        let release_commit = repo.find_object(oid, None)?.peel_to_commit()?;
+        // Hiding the release hides all of its history, so commits that reached it through
+        // other branches are excluded too
+        revwalk.hide(release_commit.id())?;
+    }
+
+    // Tagged release commits inside the window are our own and must not trigger a bump
+    let release_commits = find_release_commits(repo, config)?;
+
+    let mut commits = Vec::new();
+    for oid in revwalk {
+        let oid = oid?;
+        if release_commits.contains(&oid) {
+            continue;
+        }
+        let git_commit = repo.find_commit(oid)?;
+        commits.push(parse_commit(&git_commit)?);
+    }
+
+    Ok(commits)
+}
+
+/// Keep the commits whose changes touch `path`, e.g. a monorepo package directory.
+///
+/// Like `git log -- <path>`, a merge only counts when it differs from every parent there.
+pub fn commits_touching_path(
+    repo: &Repository,
+    commits: Vec<Commit>,
+    path: &str,
+) -> Result<Vec<Commit>, ReleaseError> {
+    let mut diff_options = DiffOptions::new();
+    diff_options.pathspec(path);
+
+    let mut touching = Vec::new();
+    for commit in commits {
+        let git_commit = repo.find_commit(git2::Oid::from_str(&commit.sha)?)?;
+        let tree = git_commit.tree()?;
+
+        let parent_trees = git_commit
+            .parents()
+            .map(|parent| parent.tree().map(Some))
+            .collect::<Result<Vec<_>, _>>()?;
+        // A root commit is compared against the empty tree
+        let parent_trees = if parent_trees.is_empty() {
+            vec![None]
+        } else {
+            parent_trees
+        };
+
+        let mut touches = true;
+        for parent_tree in &parent_trees {
+            let diff =
+                repo.diff_tree_to_tree(parent_tree.as_ref(), Some(&tree), Some(&mut diff_options))?;
+            if diff.deltas().len() == 0 {
+                touches = false;
+                break;
+            }
+        }
+        if touches {
+            touching.push(commit);
+        }
+    }
+
+    Ok(touching)
+}
+
+fn parse_commit(git_commit: &GitCommit) -> Result<Commit, ReleaseError> {
     let sha = git_commit.id().to_string();
     let message = git_commit
         .message()
         .ok_or("Commit message is not valid UTF-8")?
         .to_string();
-
-    Ok(Commit { sha, message })
+    let author = git_commit.author();
+    let author_name = author.name().unwrap_or_default().to_string();
+    let author_email = author.email().unwrap_or_default().to_string();
+
+    Ok(Commit {
+        sha,
+        message,
+        author_name,
+        author_email,
+    })
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::scm::git::open_repository;
+    use crate::scm::github::GitHubClient;
     use git2::{Repository, Signature};
     use tempfile::TempDir;
     use tokio;
@@ -63,16 +385,7 @@ mod tests {
     #[tokio::test]
     async fn test_get_impact_from_latest_commit_feat() {
         let temp_dir = create_test_repo_with_commit("feat: add new feature");
-        let original_dir = std::env::current_dir().unwrap();
-
-        // Change to the test repository directory
-        std::env::set_current_dir(temp_dir.path()).unwrap();
-
-        // Test that the function correctly identifies a feature commit
-        let result = get_impact_from_latest_commit().await;
-
-        // Restore original directory
-        std::env::set_current_dir(original_dir).unwrap();
+        let result = get_impact_from_latest_commit(temp_dir.path(), &Config::default()).await;
 
         assert!(result.is_ok());
         assert_eq!(result.unwrap(), BumpType::Minor);
@@ -81,13 +394,7 @@ mod tests {
     #[tokio::test]
     async fn test_get_impact_from_latest_commit_fix() {
         let temp_dir = create_test_repo_with_commit("fix: resolve bug");
-        let original_dir = std::env::current_dir().unwrap();
-
-        std::env::set_current_dir(temp_dir.path()).unwrap();
-
-        let result = get_impact_from_latest_commit().await;
-
-        std::env::set_current_dir(original_dir).unwrap();
+        let result = get_impact_from_latest_commit(temp_dir.path(), &Config::default()).await;
 
         assert!(result.is_ok());
         assert_eq!(result.unwrap(), BumpType::Patch);
@@ -96,13 +403,7 @@ mod tests {
     #[tokio::test]
     async fn test_get_impact_from_latest_commit_breaking() {
         let temp_dir = create_test_repo_with_commit("feat!: breaking change");
-        let original_dir = std::env::current_dir().unwrap();
-
-        std::env::set_current_dir(temp_dir.path()).unwrap();
-
-        let result = get_impact_from_latest_commit().await;
-
-        std::env::set_current_dir(original_dir).unwrap();
+        let result = get_impact_from_latest_commit(temp_dir.path(), &Config::default()).await;
 
         assert!(result.is_ok());
         assert_eq!(result.unwrap(), BumpType::Major);
@@ -111,16 +412,580 @@ mod tests {
     #[tokio::test]
     async fn test_get_impact_from_latest_commit_none() {
         let temp_dir = create_test_repo_with_commit("chore: update dependencies");
-        let original_dir = std::env::current_dir().unwrap();
+        let result = get_impact_from_latest_commit(temp_dir.path(), &Config::default()).await;
 
-        std::env::set_current_dir(temp_dir.path()).unwrap();
+        assert!(result.is_ok());
+        assert_eq!(result.unwrap(), BumpType::None);
+    }
 
-        let result = get_impact_from_latest_commit().await;
+    fn commit_on_head(repo: &Repository, message: &str) -> git2::Oid {
+        let sig = Signature::now("Test User", "test@example.com").unwrap();
+        let tree_id = {
+            let mut index = repo.index().unwrap();
+            index.write_tree().unwrap()
+        };
+        let tree = repo.find_tree(tree_id).unwrap();
+        let parent = repo.head().ok().and_then(|head| head.peel_to_commit().ok());
+        let parents: Vec<&git2::Commit> = parent.iter().collect();
 
-        std::env::set_current_dir(original_dir).unwrap();
+        repo.commit(Some("HEAD"), &sig, &sig, message, &tree, &parents)
+            .unwrap()
+    }
 
-        assert!(result.is_ok());
-        assert_eq!(result.unwrap(), BumpType::None);
+    fn commit_file_on_head(repo: &Repository, path: &str, message: &str) -> git2::Oid {
+        let workdir = repo.workdir().unwrap();
+        let file = workdir.join(path);
+        std::fs::create_dir_all(file.parent().unwrap()).unwrap();
+        std::fs::write(&file, message).unwrap();
+        let mut index = repo.index().unwrap();
+        index.add_path(std::path::Path::new(path)).unwrap();
+        index.write().unwrap();
+        commit_on_head(repo, message)
+    }
+
+    #[tokio::test]
+    async fn test_commits_touching_path_per_package() {
+        let temp_dir = TempDir::new().expect("Failed to create temp directory");
+        let repo = Repository::init(temp_dir.path()).expect("Failed to init repository");
+
+        commit_file_on_head(&repo, "packages/core/lib.rs", "feat(core): initial");
+        commit_file_on_head(&repo, "packages/cli/main.rs", "feat(cli): initial");
+        let released = commit_on_head(&repo, "chore: release");
+        repo.tag_lightweight("v1.0.0", &repo.find_object(released, None).unwrap(), false)
+            .unwrap();
+        let core_fix = commit_file_on_head(&repo, "packages/core/lib.rs", "fix(core): bug");
+        commit_file_on_head(&repo, "README.md", "docs: readme");
+
+        let config = Config::default();
+        let window = collect_release_window(&repo, &config, None).await.unwrap();
+        assert_eq!(window.len(), 2);
+
+        let core = commits_touching_path(&repo, window.clone(), "packages/core").unwrap();
+        assert_eq!(core.len(), 1);
+        assert_eq!(core[0].sha, core_fix.to_string());
+        assert_eq!(highest_bump(&core, &config), BumpType::Patch);
+
+        let cli = commits_touching_path(&repo, window, "packages/cli").unwrap();
+        assert!(cli.is_empty());
+        assert_eq!(highest_bump(&cli, &config), BumpType::None);
+    }
+
+    #[tokio::test]
+    async fn test_collect_release_window_stops_at_previous_release() {
+        let temp_dir = TempDir::new().expect("Failed to create temp directory");
+        let repo = Repository::init(temp_dir.path()).expect("Failed to init repository");
+
+        commit_on_head(&repo, "feat: initial");
+        let released = commit_on_head(&repo, "fix: released fix");
+        repo.tag_lightweight("v1.0.0", &repo.find_object(released, None).unwrap(), false)
+            .unwrap();
+        let feature = commit_on_head(&repo, "feat: new feature");
+        let docs = commit_on_head(&repo, "docs: update readme");
+
+        let window = collect_release_window(&repo, &Config::default(), None)
+            .await
+            .unwrap();
+        let messages: Vec<&str> = window.iter().map(|c| c.message.as_str()).collect();
+        let shas: Vec<&str> = window.iter().map(|c| c.sha.as_str()).collect();
+
+        assert_eq!(messages, vec!["docs: update readme", "feat: new feature"]);
+        assert_eq!(shas, vec![docs.to_string(), feature.to_string()]);
+        assert_eq!(window[0].author_name, "Test User");
+        assert_eq!(window[0].author_email, "test@example.com");
+    }
+
+    #[tokio::test]
+    async fn test_collect_release_window_branchy_history() {
+        let temp_dir = TempDir::new().expect("Failed to create temp directory");
+        let repo = Repository::init(temp_dir.path()).expect("Failed to init repository");
+        let sig = Signature::now("Test User", "test@example.com").unwrap();
+        let tree = repo
+            .find_tree(repo.index().unwrap().write_tree().unwrap())
+            .unwrap();
+
+        let base = commit_on_head(&repo, "feat: initial");
+        // A side branch forks before the release and contains a fix shipped in it
+        let side = repo
+            .commit(
+                None,
+                &sig,
+                &sig,
+                "fix: side fix",
+                &tree,
+                &[&repo.find_commit(base).unwrap()],
+            )
+            .unwrap();
+        let main_tip = commit_on_head(&repo, "feat: main work");
+        let merged = repo
+            .commit(
+                Some("HEAD"),
+                &sig,
+                &sig,
+                "Merge side",
+                &tree,
+                &[
+                    &repo.find_commit(main_tip).unwrap(),
+                    &repo.find_commit(side).unwrap(),
+                ],
+            )
+            .unwrap();
+
+        // The release commit sits off the main line, with main as its parent
+        let release = repo
+            .commit(
+                None,
+                &sig,
+                &sig,
+                "chore: release version 1.0.0",
+                &tree,
+                &[&repo.find_commit(merged).unwrap()],
+            )
+            .unwrap();
+        repo.tag_lightweight("v1.0.0", &repo.find_object(release, None).unwrap(), false)
+            .unwrap();
+
+        // After the release, the same side branch gets another commit and is merged again
+        let side_followup = repo
+            .commit(
+                None,
+                &sig,
+                &sig,
+                "fix: side followup",
+                &tree,
+                &[&repo.find_commit(side).unwrap()],
+            )
+            .unwrap();
+        let new_work = commit_on_head(&repo, "feat: after release");
+        repo.commit(
+            Some("HEAD"),
+            &sig,
+            &sig,
+            "Merge side again",
+            &tree,
+            &[
+                &repo.find_commit(new_work).unwrap(),
+                &repo.find_commit(side_followup).unwrap(),
+            ],
+        )
+        .unwrap();
+
+        let window = collect_release_window(&repo, &Config::default(), None)
+            .await
+            .unwrap();
+        let mut messages: Vec<&str> = window.iter().map(|c| c.message.as_str()).collect();
+        messages.sort();
+
+        assert_eq!(
+            messages,
+            vec![
+                "Merge side again",
+                "feat: after release",
+                "fix: side followup"
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_collect_release_window_since_tag() {
+        let temp_dir = TempDir::new().expect("Failed to create temp directory");
+        let repo = Repository::init(temp_dir.path()).expect("Failed to init repository");
+
+        let first = commit_on_head(&repo, "feat: initial");
+        repo.tag_lightweight("v0.9.0", &repo.find_object(first, None).unwrap(), false)
+            .unwrap();
+        let second = commit_on_head(&repo, "fix: released fix");
+        repo.tag_lightweight("v1.0.0", &repo.find_object(second, None).unwrap(), false)
+            .unwrap();
+        commit_on_head(&repo, "feat: new feature");
+
+        let config = Config::default();
+        let since = resolve_since_tag("0.9.0", &config);
+        let window = collect_release_window(&repo, &config, Some(&since))
+            .await
+            .unwrap();
+
+        assert_eq!(window.len(), 2);
+        assert_eq!(window[1].message, "fix: released fix");
+    }
+
+    #[tokio::test]
+    async fn test_count_releasable_commits_below_threshold() {
+        let temp_dir = TempDir::new().expect("Failed to create temp directory");
+        let repo = Repository::init(temp_dir.path()).expect("Failed to init repository");
+
+        let released = commit_on_head(&repo, "feat: initial");
+        repo.tag_lightweight("v1.0.0", &repo.find_object(released, None).unwrap(), false)
+            .unwrap();
+        commit_on_head(&repo, "feat: first feature");
+        commit_on_head(&repo, "docs: update readme");
+        commit_on_head(&repo, "fix: a bug");
+
+        let mut config = Config::default();
+        config.release.min_releasable_commits = Some(3);
+        let window = collect_release_window(&repo, &config, None).await.unwrap();
+
+        assert_eq!(count_releasable_commits(&window, &config), 2);
+    }
+
+    #[tokio::test]
+    async fn test_highest_bump_since_previous_release() {
+        let temp_dir = TempDir::new().expect("Failed to create temp directory");
+        let repo = Repository::init(temp_dir.path()).expect("Failed to init repository");
+
+        commit_on_head(&repo, "feat!: released breaking change");
+        let released = commit_on_head(&repo, "fix: released fix");
+        repo.tag_lightweight("v1.0.0", &repo.find_object(released, None).unwrap(), false)
+            .unwrap();
+        commit_on_head(&repo, "fix: a bug");
+        commit_on_head(&repo, "feat: a feature");
+        commit_on_head(&repo, "chore: tidy up");
+
+        let config = Config::default();
+        let window = collect_release_window(&repo, &config, None).await.unwrap();
+
+        // The chore tip alone wouldn't release, and the breaking change was already released
+        assert_eq!(window.len(), 3);
+        assert_eq!(highest_bump(&window, &config), BumpType::Minor);
+        assert_eq!(highest_bump(&window[..1], &config), BumpType::None);
+    }
+
+    #[test]
+    fn test_explain_result_names_adjustments() {
+        assert_eq!(explain_result(&BumpType::Minor, &[]), "Result: minor bump");
+        assert_eq!(
+            explain_result(
+                &BumpType::None,
+                &["min_releasable_commits: 1 of 2 releasable commit(s) → none".to_string()]
+            ),
+            "Result: none bump\n  ↳ min_releasable_commits: 1 of 2 releasable commit(s) → none"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_explain_bump_for_mixed_commits() {
+        let temp_dir = TempDir::new().expect("Failed to create temp directory");
+        let repo = Repository::init(temp_dir.path()).expect("Failed to init repository");
+
+        let released = commit_on_head(&repo, "feat: released feature");
+        repo.tag_lightweight("v1.0.0", &repo.find_object(released, None).unwrap(), false)
+            .unwrap();
+        let fix = commit_on_head(&repo, "fix(parser): a bug");
+        let feat = commit_on_head(&repo, "feat(api)!: new endpoint");
+        let chore = commit_on_head(&repo, "chore: tidy up");
+        let other = commit_on_head(&repo, "Update README");
+
+        let config = Config::default();
+        let window = collect_release_window(&repo, &config, None).await.unwrap();
+        let explanation = explain_bump(&window, &config);
+        let short = |oid: git2::Oid| oid.to_string()[..7].to_string();
+
+        assert_eq!(
+            explanation,
+            format!(
+                "{} (not conventional) → none\n\
+                 {} chore → none\n\
+                 {} feat(api)! → major\n\
+                 {} fix(parser) → patch\n\
+                 Commits call for: major bump from 4 commit(s)",
+                short(other),
+                short(chore),
+                short(feat),
+                short(fix)
+            )
+        );
+    }
+
+    fn commit_with_message(message: &str) -> Commit {
+        Commit {
+            sha: "0".repeat(40),
+            message: message.to_string(),
+            author_name: "Test User".to_string(),
+            author_email: "test@example.com".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_release_as_version() {
+        let current = semver::Version::parse("1.4.2").unwrap();
+        let commits = vec![
+            commit_with_message("fix: a bug"),
+            commit_with_message("chore: release 2.0.0\n\nRelease-As: 2.0.0"),
+            commit_with_message("chore: release 1.9.0\n\nRelease-As: 1.9.0"),
+        ];
+
+        let forced = release_as_version(&commits, &current).unwrap();
+        assert_eq!(forced, Some(semver::Version::parse("2.0.0").unwrap()));
+        assert_eq!(
+            BumpType::between(&current, &forced.unwrap()),
+            BumpType::Major
+        );
+
+        assert_eq!(release_as_version(&commits[..1], &current).unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn test_skip_release_marker_holds_back_release() {
+        let temp_dir = TempDir::new().expect("Failed to create temp directory");
+        let repo = Repository::init(temp_dir.path()).expect("Failed to init repository");
+
+        let released = commit_on_head(&repo, "feat: initial");
+        repo.tag_lightweight("v1.0.0", &repo.find_object(released, None).unwrap(), false)
+            .unwrap();
+        let skipped = commit_on_head(&repo, "feat: internal-only tooling [skip release]");
+        commit_on_head(&repo, "docs: update readme");
+
+        let config = Config::default();
+        let window = collect_release_window(&repo, &config, None).await.unwrap();
+        assert_eq!(highest_bump(&window, &config), BumpType::Minor);
+
+        let (window, skipped_commits) = split_skip_release_commits(window);
+        assert_eq!(highest_bump(&window, &config), BumpType::None);
+        assert_eq!(skipped_commits.len(), 1);
+        assert_eq!(skipped_commits[0].sha, skipped.to_string());
+    }
+
+    #[tokio::test]
+    async fn test_feature_after_skipped_commit_releases() {
+        let temp_dir = TempDir::new().expect("Failed to create temp directory");
+        let repo = Repository::init(temp_dir.path()).expect("Failed to init repository");
+
+        let released = commit_on_head(&repo, "feat: initial");
+        repo.tag_lightweight("v1.0.0", &repo.find_object(released, None).unwrap(), false)
+            .unwrap();
+        commit_on_head(&repo, "fix: internal-only tweak [skip release]");
+        commit_on_head(&repo, "feat: public api");
+
+        let config = Config::default();
+        let window = collect_release_window(&repo, &config, None).await.unwrap();
+        let (window, skipped) = split_skip_release_commits(window);
+
+        assert_eq!(highest_bump(&window, &config), BumpType::Minor);
+        assert_eq!(window.len(), 1);
+        assert_eq!(skipped.len(), 1);
+    }
+
+    #[test]
+    fn test_has_skip_release_marker() {
+        assert!(has_skip_release_marker(
+            "feat: x\n\nInternal only. [No Release]"
+        ));
+        assert!(has_skip_release_marker("fix: y [skip release]"));
+        assert!(!has_skip_release_marker(
+            "fix: skip release notes for drafts"
+        ));
+        let (kept, skipped) = split_skip_release_commits(vec![commit_with_message("feat: z")]);
+        assert_eq!(kept.len(), 1);
+        assert!(skipped.is_empty());
+    }
+
+    #[test]
+    fn test_release_as_version_rejects_downgrade() {
+        let current = semver::Version::parse("1.4.2").unwrap();
+
+        let commits = vec![commit_with_message("chore: pin\n\nRelease-As: 1.4.2")];
+        let error = release_as_version(&commits, &current).unwrap_err();
+        assert!(
+            error.to_string().contains("greater"),
+            "Unexpected error: {}",
+            error
+        );
+
+        let commits = vec![commit_with_message("chore: pin\n\nRelease-As: 1.0.0")];
+        assert!(release_as_version(&commits, &current).is_err());
+
+        let commits = vec![commit_with_message("chore: pin\n\nRelease-As: two")];
+        assert!(release_as_version(&commits, &current).is_err());
+    }
+
+    #[test]
+    fn test_get_impact_from_message_file() {
+        let temp_dir = TempDir::new().expect("Failed to create temp directory");
+        let path = temp_dir.path().join("COMMIT_EDITMSG");
+        std::fs::write(
+            &path,
+            "feat(cli): add hook mode\n\n# Please enter the commit message for your changes.\n",
+        )
+        .unwrap();
+
+        assert_eq!(
+            get_impact_from_message_file(&path, &Config::default()).unwrap(),
+            BumpType::Minor
+        );
+
+        std::fs::write(&path, "# fix!: commented out\nfix: real message\n").unwrap();
+        assert_eq!(
+            get_impact_from_message_file(&path, &Config::default()).unwrap(),
+            BumpType::Patch
+        );
+    }
+
+    #[tokio::test]
+    async fn test_collect_release_window_excludes_release_commits() {
+        let temp_dir = TempDir::new().expect("Failed to create temp directory");
+        let repo = Repository::init(temp_dir.path()).expect("Failed to init repository");
+
+        let base = commit_on_head(&repo, "feat: initial");
+        commit_on_head(&repo, "docs: tweak");
+        let release = commit_on_head(&repo, "chore: release version 1.0.0");
+        repo.tag_lightweight("v1.0.0", &repo.find_object(release, None).unwrap(), false)
+            .unwrap();
+        commit_on_head(&repo, "docs: more docs");
+
+        // With chores releasing a patch, the release commit would otherwise trigger one
+        let config = Config {
+            commit_types: std::collections::HashMap::from([(
+                "chore".to_string(),
+                "patch".to_string(),
+            )]),
+            ..Config::default()
+        };
+        let window = collect_release_window(&repo, &config, Some(&base.to_string()))
+            .await
+            .unwrap();
+        let messages: Vec<&str> = window.iter().map(|c| c.message.as_str()).collect();
+
+        assert_eq!(messages, vec!["docs: more docs", "docs: tweak"]);
+        assert_eq!(count_releasable_commits(&window, &config), 0);
+        assert_eq!(highest_bump(&window, &config), BumpType::None);
+    }
+
+    #[test]
+    fn test_bump_for_message_with_type_aliases() {
+        let mut config = Config::default();
+        config
+            .commits
+            .type_aliases
+            .insert("feature".to_string(), "feat".to_string());
+        config
+            .commits
+            .type_aliases
+            .insert("bugfix".to_string(), "fix".to_string());
+
+        assert_eq!(
+            bump_for_message("feature(web): add dark mode", &config),
+            BumpType::Minor
+        );
+        assert_eq!(
+            bump_for_message("bugfix: handle nulls", &config),
+            BumpType::Patch
+        );
+        assert_eq!(
+            bump_for_message("bugfix: handle nulls", &Config::default()),
+            BumpType::None
+        );
+    }
+
+    #[test]
+    fn test_resolve_since_tag() {
+        let mut config = Config::default();
+        assert_eq!(resolve_since_tag("1.2.0", &config), "v1.2.0");
+        assert_eq!(resolve_since_tag("v1.2.0", &config), "v1.2.0");
+
+        config.version.tag_prefix = Some("pkg-v".to_string());
+        config.version.tag_suffix = Some("-final".to_string());
+        assert_eq!(resolve_since_tag("1.2.0", &config), "pkg-v1.2.0-final");
+        assert_eq!(
+            resolve_since_tag("pkg-v1.2.0-final", &config),
+            "pkg-v1.2.0-final"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_get_impact_from_pull_request_checked_minor() {
+        let mut server = mockito::Server::new_async().await;
+        let body = "## Release type\n\n- [ ] major\n- [x] minor\n- [ ] patch\n";
+        let mock = server
+            .mock("GET", "/repos/owner/repo/commits/abc123/pulls")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                serde_json::json!([{
+                    "number": 7,
+                    "title": "feat: something",
+                    "body": body,
+                    "merged_at": "2024-01-01T00:00:00Z"
+                }])
+                .to_string(),
+            )
+            .create_async()
+            .await;
+
+        let client = GitHubClient::new("test-token".to_string())
+            .unwrap()
+            .with_base_url(server.url());
+        let repo_info = RepositoryInfo {
+            full_name: "owner/repo".to_string(),
+            default_branch: "main".to_string(),
+        };
+
+        let bump = get_impact_from_pull_request(&client, &repo_info, "abc123", &Config::default())
+            .await
+            .unwrap();
+
+        mock.assert_async().await;
+        assert_eq!(bump, Some(BumpType::Minor));
+    }
+
+    #[tokio::test]
+    async fn test_last_stable_release_tag_skips_prereleases() {
+        let mut server = mockito::Server::new_async().await;
+        let release = |tag: &str, prerelease: bool| {
+            serde_json::json!({
+                "id": 1,
+                "tag_name": tag,
+                "name": tag,
+                "body": null,
+                "draft": false,
+                "prerelease": prerelease,
+                "html_url": "https://github.com/owner/repo/releases",
+                "upload_url": "https://uploads.github.com/upload"
+            })
+        };
+        let mock = server
+            .mock("GET", "/repos/owner/repo/releases")
+            .match_query(mockito::Matcher::Any)
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                serde_json::json!([
+                    release("v2.0.0-rc.2", true),
+                    release("v2.0.0-rc.1", true),
+                    release("v1.5.0", false),
+                    release("v1.5.0-beta.1", true),
+                    release("v1.4.2", false)
+                ])
+                .to_string(),
+            )
+            .create_async()
+            .await;
+
+        let client = GitHubClient::new("test-token".to_string())
+            .unwrap()
+            .with_base_url(server.url());
+        let repo_info = RepositoryInfo {
+            full_name: "owner/repo".to_string(),
+            default_branch: "main".to_string(),
+        };
+
+        let tag = last_stable_release_tag(&client, &repo_info, &Config::default())
+            .await
+            .unwrap();
+
+        mock.assert_async().await;
+        assert_eq!(tag.as_deref(), Some("v1.5.0"));
+    }
+
+    #[test]
+    fn test_bump_from_pr_body_without_checked_box() {
+        let pattern = Regex::new(crate::config::DEFAULT_PR_BODY_PATTERN).unwrap();
+        assert_eq!(
+            bump_from_pr_body("- [ ] major\n- [ ] minor", &pattern),
+            None
+        );
+        assert_eq!(
+            bump_from_pr_body("* [X] Patch", &pattern),
+            Some(BumpType::Patch)
+        );
     }
 
     #[test]
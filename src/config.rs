@@ -1,72 +1,1407 @@
+use crate::bump_type::BumpType;
+use crate::error::ReleaseError;
+use log::warn;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::path::Path;
 
-#[derive(Debug, Deserialize, Serialize, Clone)]
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
 pub struct Config {
+    /// Kill-switch: when false, runs exit immediately without doing anything
+    #[serde(default = "default_true")]
+    pub enabled: bool,
     pub version: VersionConfig,
+    #[serde(default)]
+    pub release: ReleaseConfig,
+    #[serde(default)]
+    pub changelog: ChangelogConfig,
+    #[serde(default)]
+    pub commits: CommitsConfig,
+    /// Bump produced by each commit type (`"major"`, `"minor"`, `"patch"` or `"none"`),
+    /// overriding the built-in mapping, e.g. `improvement = "minor"`
+    #[serde(default)]
+    pub commit_types: HashMap<String, String>,
+    /// Monorepo packages, each versioned and released on its own; empty for a single package
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub packages: Vec<PackageConfig>,
 }
 
-#[derive(Debug, Deserialize, Serialize, Clone)]
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
 pub struct VersionConfig {
     pub initial_version: Option<String>,
     pub tag_prefix: Option<String>,
     pub tag_suffix: Option<String>,
+    /// Placed between `tag_prefix` and the version, e.g. `"-"` for `release-1.2.3`
+    pub tag_separator: Option<String>,
+    /// Template for tag names, e.g. `"mypkg/v{version}"`; may use `{prefix}`, `{separator}`
+    /// and `{suffix}`, and must contain `{version}` once
+    pub tag_format: Option<String>,
     pub files: Option<Vec<FileUpdateConfig>>,
+    /// Precedence of prerelease labels on the same base version, lowest first
+    pub prerelease_order: Option<Vec<String>>,
+    /// Where the bump comes from: `"commits"` (default) or `"pr_body"`
+    pub bump_source: Option<String>,
+    /// Regex matching a checked release-type box in a PR body; group 1 is the bump name
+    pub pr_body_pattern: Option<String>,
+    /// The most recently released version, maintained when `update_config_version` is set
+    pub current: Option<String>,
+    /// Write each released version back into `current` in the config file
+    #[serde(default)]
+    pub update_config_version: bool,
+    /// Largest bump allowed on this branch (`"major"`, `"minor"`, `"patch"`)
+    pub max_bump: Option<String>,
+    /// Clamp a bump exceeding `max_bump` down to it instead of failing the run
+    #[serde(default)]
+    pub max_bump_clamp: bool,
+    /// Fail before releasing when any configured file is missing, instead of skipping it
+    #[serde(default)]
+    pub require_files_exist: bool,
+    /// Prerelease channel such as `"rc"`: releases become `1.2.0-rc.1`, `1.2.0-rc.2`, ...
+    /// Unset it to promote the latest prerelease to its final version.
+    pub prerelease: Option<String>,
+    /// Below 1.0.0, let breaking changes bump the minor and features the patch
+    #[serde(default = "default_true")]
+    pub respect_zerover: bool,
+    /// Where the current version is read from: `"api"` (default) or `"local"` tags
+    pub tag_source: Option<String>,
+    /// Ignore prerelease tags when looking for the current version, unless `prerelease` is set
+    #[serde(default)]
+    pub stable_tags_only: bool,
+    /// `"semver"` (default) or `"calver"` for `YYYY.MM.PATCH` versions based on the release date
+    pub versioning: Option<String>,
+    /// Fetch tags from the remote before reading them with `tag_source = "local"`; on by
+    /// default when running in GitHub Actions
+    pub fetch_tags: Option<bool>,
 }
 
-#[derive(Debug, Deserialize, Serialize, Clone)]
+/// How new versions are computed
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Versioning {
+    /// Bump major, minor or patch by the commits
+    SemVer,
+    /// `YYYY.MM.PATCH`: the release month, and a patch counting releases within it
+    CalVer,
+}
+
+/// Where the tags holding the current version are listed from
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TagSource {
+    Api,
+    /// The checked-out repository, falling back to the API when it has no version tags
+    Local,
+}
+
+/// Release commit message unless `commit_message_template` is set
+pub const DEFAULT_COMMIT_MESSAGE_TEMPLATE: &str = "chore: release version {version}";
+
+/// Tag names unless `tag_format` is set
+pub const DEFAULT_TAG_FORMAT: &str = "{prefix}{separator}{version}{suffix}";
+
+/// Marker the default configuration replaces in `Cargo.toml`
+pub const DEFAULT_VERSION_MARKER: &str = "0.0.0+local";
+
+pub const DEFAULT_PR_BODY_PATTERN: &str = r"(?im)^\s*[-*]\s*\[x\]\s*(major|minor|patch|none)\b";
+
+impl VersionConfig {
+    /// The tag format with everything but `{version}` filled in
+    fn tag_template(&self) -> String {
+        self.tag_format
+            .as_deref()
+            .unwrap_or(DEFAULT_TAG_FORMAT)
+            .replace("{prefix}", self.tag_prefix.as_deref().unwrap_or(""))
+            .replace("{separator}", self.tag_separator.as_deref().unwrap_or(""))
+            .replace("{suffix}", self.tag_suffix.as_deref().unwrap_or(""))
+    }
+
+    /// Everything in front of and behind the version in a tag name
+    fn tag_affixes(&self) -> (String, String) {
+        let template = self.tag_template();
+        match template.split_once("{version}") {
+            Some((lead, trail)) => (lead.to_string(), trail.to_string()),
+            None => (template, String::new()),
+        }
+    }
+
+    /// The tag name for `version`, by default `{prefix}{separator}{version}{suffix}`
+    pub fn tag_name(&self, version: &impl std::fmt::Display) -> String {
+        let (lead, trail) = self.tag_affixes();
+        format!("{}{}{}", lead, version, trail)
+    }
+
+    /// Strip what the tag format puts around the version from a tag name, where present
+    pub fn version_part<'t>(&self, tag_name: &'t str) -> &'t str {
+        let (lead, trail) = self.tag_affixes();
+
+        let mut version_str = tag_name;
+        if !lead.is_empty() && version_str.starts_with(&lead) {
+            version_str = &version_str[lead.len()..];
+        }
+        if !trail.is_empty() && version_str.ends_with(&trail) {
+            version_str = &version_str[..version_str.len() - trail.len()];
+        }
+        version_str
+    }
+
+    /// Catch tag formats without a single `{version}` and prefix/separator combinations that
+    /// would produce tags like `v--1.2.3`
+    fn validate_tag_format(&self) -> std::result::Result<(), String> {
+        if let Some(format) = &self.tag_format {
+            if format.matches("{version}").count() != 1 {
+                return Err(format!(
+                    "tag_format {:?} must contain {{version}} exactly once",
+                    format
+                ));
+            }
+        }
+
+        let Some(separator) = self.tag_separator.as_deref() else {
+            return Ok(());
+        };
+        let prefix = self.tag_prefix.as_deref().unwrap_or("");
+
+        if prefix.is_empty() {
+            return Err(format!(
+                "tag_separator {:?} requires a tag_prefix; without one tags would start with it",
+                separator
+            ));
+        }
+        if prefix.ends_with(separator) {
+            return Err(format!(
+                "tag_prefix {:?} already ends with tag_separator {:?}, producing tags like {:?}; \
+                 remove the separator from the prefix",
+                prefix,
+                separator,
+                self.tag_name(&"1.2.3")
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Whether tags of prerelease versions are skipped: only in a stable flow that asks for it
+    pub fn ignores_prerelease_tags(&self) -> bool {
+        self.stable_tags_only && self.prerelease.is_none()
+    }
+
+    pub fn bump_from_pr_body(&self) -> bool {
+        self.bump_source.as_deref() == Some("pr_body")
+    }
+
+    /// The configured bump cap, if any
+    pub fn max_bump(&self) -> std::result::Result<Option<BumpType>, String> {
+        match &self.max_bump {
+            Some(name) => BumpType::from_name(name)
+                .map(Some)
+                .ok_or_else(|| format!("Invalid max_bump {:?}", name)),
+            None => Ok(None),
+        }
+    }
+
+    fn validate_prerelease(&self) -> std::result::Result<(), String> {
+        let Some(channel) = self.prerelease.as_deref() else {
+            return Ok(());
+        };
+        if channel.is_empty() || channel.contains('.') {
+            return Err(format!(
+                "Invalid prerelease channel {:?}: expected a single identifier such as \"rc\"",
+                channel
+            ));
+        }
+        semver::Prerelease::new(&format!("{}.1", channel))
+            .map(|_| ())
+            .map_err(|e| format!("Invalid prerelease channel {:?}: {}", channel, e))
+    }
+
+    pub fn tag_source(&self) -> std::result::Result<TagSource, String> {
+        match self.tag_source.as_deref() {
+            None | Some("api") => Ok(TagSource::Api),
+            Some("local") => Ok(TagSource::Local),
+            Some(other) => Err(format!(
+                "Invalid tag_source {:?}: expected \"api\" or \"local\"",
+                other
+            )),
+        }
+    }
+
+    pub fn versioning(&self) -> std::result::Result<Versioning, String> {
+        match self.versioning.as_deref() {
+            None | Some("semver") => Ok(Versioning::SemVer),
+            Some("calver") => Ok(Versioning::CalVer),
+            Some(other) => Err(format!(
+                "Invalid versioning {:?}: expected \"semver\" or \"calver\"",
+                other
+            )),
+        }
+    }
+
+    pub fn pr_body_pattern(&self) -> &str {
+        self.pr_body_pattern
+            .as_deref()
+            .unwrap_or(DEFAULT_PR_BODY_PATTERN)
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq)]
+pub struct ReleaseConfig {
+    /// Prefix for the temporary branch the release commit is pushed through
+    pub temp_branch_prefix: Option<String>,
+    /// Glob patterns of files to upload to the GitHub release
+    pub assets: Option<Vec<String>>,
+    /// Upload a SHA256SUMS file covering the uploaded assets
+    #[serde(default)]
+    pub asset_checksums: bool,
+    /// Hold back releases until this many releasable commits have accumulated
+    pub min_releasable_commits: Option<usize>,
+    /// Exit with this code instead of 0 when no release was needed
+    pub no_release_exit_code: Option<i32>,
+    /// How the moving `vN` ref is published: `"branch"` (default) or `"tag"`
+    pub major_ref_type: Option<String>,
+    /// Create the GitHub release as a draft to be reviewed and published by hand
+    #[serde(default)]
+    pub draft: bool,
+    /// Mark every GitHub release as a prerelease, not only versions like `1.2.0-rc.1`
+    #[serde(default)]
+    pub prerelease: bool,
+    /// Let GitHub generate the release notes from merged pull requests instead of
+    /// building them from the commits
+    #[serde(default)]
+    pub auto_notes: bool,
+    /// Retries of GitHub API calls failing transiently (429, 502, 503, 504, network errors)
+    pub api_max_retries: Option<u32>,
+    /// Delay before the first retry in milliseconds, doubled for each further attempt
+    pub api_retry_delay_ms: Option<u64>,
+    /// Author and committer of the release commit, e.g. `github-actions[bot]`
+    pub commit_author_name: Option<String>,
+    pub commit_author_email: Option<String>,
+    /// Message of the release commit with `{version}` and `{tag}` placeholders
+    pub commit_message_template: Option<String>,
+    /// Create an annotated release tag carrying the release title instead of a lightweight one
+    #[serde(default)]
+    pub annotated_tags: bool,
+    /// Sign the (annotated) release tag with gpg, using `gpg.program` from the git config
+    #[serde(default)]
+    pub sign_tags: bool,
+    /// Key to sign tags with; gpg's default key when unset
+    pub signing_key: Option<String>,
+    /// `false` to skip the moving major version ref, or its name pattern such as
+    /// `"release/v{major}"`
+    pub major_branch: Option<MajorBranch>,
+    /// Commit types left out of the release notes (not the changelog file); by default the
+    /// types that don't bump the version
+    pub exclude_types: Option<Vec<String>>,
+    /// Fail instead of warning when the checkout is a shallow clone
+    #[serde(default)]
+    pub fail_on_shallow_clone: bool,
+    /// Git remote the release refs are pushed to (default: `origin`)
+    pub remote: Option<String>,
+    /// Put in front of the moving `vN` ref, so each monorepo package gets its own
+    #[serde(skip)]
+    pub major_ref_prefix: Option<String>,
+}
+
+/// The `major_branch` setting: on/off, or the name pattern of the ref
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+#[serde(untagged)]
+pub enum MajorBranch {
+    Enabled(bool),
+    Pattern(String),
+}
+
+/// Name of the moving major version ref unless `major_branch` sets a pattern
+pub const DEFAULT_MAJOR_BRANCH_PATTERN: &str = "v{major}";
+
+/// Kind of ref used for the moving major version pointer (`vN`)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MajorRefType {
+    Branch,
+    Tag,
+}
+
+impl ReleaseConfig {
+    pub fn temp_branch_prefix(&self) -> &str {
+        self.temp_branch_prefix.as_deref().unwrap_or("release-")
+    }
+
+    pub fn remote(&self) -> &str {
+        self.remote.as_deref().unwrap_or("origin")
+    }
+
+    pub fn commit_author_name(&self) -> &str {
+        self.commit_author_name.as_deref().unwrap_or("Release Bot")
+    }
+
+    pub fn commit_author_email(&self) -> &str {
+        self.commit_author_email
+            .as_deref()
+            .unwrap_or("release@github.com")
+    }
+
+    /// The release commit message for `version`, tagged as `tag`
+    pub fn commit_message(&self, version: &semver::Version, tag: &str) -> String {
+        self.commit_message_template
+            .as_deref()
+            .unwrap_or(DEFAULT_COMMIT_MESSAGE_TEMPLATE)
+            .replace("{version}", &version.to_string())
+            .replace("{tag}", tag)
+    }
+
+    /// Whether the release tag is an annotated tag made here (and pushed) rather than a
+    /// lightweight tag left to GitHub
+    pub fn annotated_tags(&self) -> bool {
+        self.annotated_tags || self.sign_tags
+    }
+
+    pub fn major_ref_prefix(&self) -> &str {
+        self.major_ref_prefix.as_deref().unwrap_or("")
+    }
+
+    /// Short name of the moving major version ref for `version` (e.g. `v1`), or `None` when
+    /// `major_branch = false`
+    pub fn major_ref_name(&self, version: &semver::Version) -> Option<String> {
+        let pattern = match &self.major_branch {
+            Some(MajorBranch::Enabled(false)) => return None,
+            Some(MajorBranch::Pattern(pattern)) => pattern.as_str(),
+            Some(MajorBranch::Enabled(true)) | None => DEFAULT_MAJOR_BRANCH_PATTERN,
+        };
+        Some(format!(
+            "{}{}",
+            self.major_ref_prefix(),
+            pattern.replace("{major}", &version.major.to_string())
+        ))
+    }
+
+    pub fn major_ref_type(&self) -> std::result::Result<MajorRefType, String> {
+        match self.major_ref_type.as_deref() {
+            None | Some("branch") => Ok(MajorRefType::Branch),
+            Some("tag") => Ok(MajorRefType::Tag),
+            Some(other) => Err(format!(
+                "Invalid major_ref_type {:?}: expected \"branch\" or \"tag\"",
+                other
+            )),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+pub struct CommitsConfig {
+    /// Treat `BREAKING CHANGE:` anywhere in the body as breaking, rather than only as a
+    /// footer in the final paragraph
+    #[serde(default = "default_true")]
+    pub scan_body_for_breaking: bool,
+    /// Alternative type names mapped to their canonical type (e.g. `feature = "feat"`)
+    #[serde(default)]
+    pub type_aliases: HashMap<String, String>,
+    /// URL of a JSON commit policy merged underneath these settings at startup
+    pub policy_url: Option<String>,
+    /// Scopes accepted in PR titles; any scope is accepted when unset
+    pub allowed_scopes: Option<Vec<String>>,
+    /// Types accepted in PR titles, replacing the built-in and configured types when set
+    pub allowed_types: Option<Vec<String>>,
+}
+
+impl Default for CommitsConfig {
+    fn default() -> Self {
+        Self {
+            scan_body_for_breaking: true,
+            type_aliases: HashMap::new(),
+            policy_url: None,
+            allowed_scopes: None,
+            allowed_types: None,
+        }
+    }
+}
+
+fn default_true() -> bool {
+    true
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq)]
+pub struct ChangelogConfig {
+    /// Add a "Contributors" section listing the authors of the released commits
+    #[serde(default)]
+    pub contributors: bool,
+    /// Truncate entry descriptions longer than this many characters, with an ellipsis
+    pub max_description_len: Option<usize>,
+    /// Section titles for scopes, used instead of the type-based section
+    #[serde(default)]
+    pub scope_sections: HashMap<String, String>,
+    /// Section titles for commit types, replacing the built-in ones; unmapped types are left out
+    pub sections: Option<HashMap<String, String>>,
+    /// Commit types in the order their sections are listed
+    pub section_order: Option<Vec<String>>,
+    /// What entries reference: `"commit"` (default) or `"pr"`
+    pub link_target: Option<String>,
+    /// Notes for the very first release: `"full"`, `"summary"` (default) or `"empty"`
+    pub first_release_changelog: Option<String>,
+    /// List entries with the same type, scope and description only once per section
+    #[serde(default)]
+    pub dedupe: bool,
+    /// Prepend each release to a Keep a Changelog style file in the release commit
+    #[serde(default)]
+    pub enabled: bool,
+    /// Changelog file written when `enabled` (default: `CHANGELOG.md`)
+    pub path: Option<String>,
+    /// Link entries to their commit or pull request and add a compare link to the previous release
+    #[serde(default)]
+    pub links: bool,
+}
+
+/// How the notes of the first release, whose window is the whole history, are rendered
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FirstReleaseChangelog {
+    Full,
+    Summary,
+    Empty,
+}
+
+/// Section titles of the commit types listed in release notes by default, in display order
+pub const DEFAULT_TYPE_SECTIONS: &[(&str, &str)] = &[
+    ("feat", "Features"),
+    ("fix", "Bug Fixes"),
+    ("perf", "Performance Improvements"),
+    ("security", "Security"),
+];
+
+impl ChangelogConfig {
+    /// `(commit type, section title)` pairs in display order: the types in `section_order`
+    /// first, then the remaining ones in the built-in order, then alphabetically
+    pub fn type_sections(&self) -> Vec<(&str, &str)> {
+        let mut sections: Vec<(&str, &str)> = match &self.sections {
+            Some(sections) => sections
+                .iter()
+                .map(|(commit_type, title)| (commit_type.as_str(), title.as_str()))
+                .collect(),
+            None => DEFAULT_TYPE_SECTIONS.to_vec(),
+        };
+
+        let order = self.section_order.as_deref().unwrap_or_default();
+        sections.sort_by_key(|(commit_type, _)| {
+            (
+                order
+                    .iter()
+                    .position(|listed| listed == commit_type)
+                    .unwrap_or(usize::MAX),
+                DEFAULT_TYPE_SECTIONS
+                    .iter()
+                    .position(|(default, _)| default == commit_type)
+                    .unwrap_or(usize::MAX),
+                *commit_type,
+            )
+        });
+        sections
+    }
+
+    fn validate_sections(&self) -> std::result::Result<(), String> {
+        let sections = self.type_sections();
+        for commit_type in self.section_order.as_deref().unwrap_or_default() {
+            if !sections.iter().any(|(mapped, _)| mapped == commit_type) {
+                return Err(format!(
+                    "section_order lists {:?}, which has no entry in [changelog.sections]",
+                    commit_type
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    pub fn path(&self) -> &str {
+        self.path.as_deref().unwrap_or("CHANGELOG.md")
+    }
+
+    /// Whether entries should reference their pull request rather than the commit
+    pub fn links_to_pull_requests(&self) -> std::result::Result<bool, String> {
+        match self.link_target.as_deref() {
+            None | Some("commit") => Ok(false),
+            Some("pr") => Ok(true),
+            Some(other) => Err(format!(
+                "Invalid link_target {:?}: expected \"commit\" or \"pr\"",
+                other
+            )),
+        }
+    }
+
+    pub fn first_release_changelog(&self) -> std::result::Result<FirstReleaseChangelog, String> {
+        match self.first_release_changelog.as_deref() {
+            Some("full") => Ok(FirstReleaseChangelog::Full),
+            None | Some("summary") => Ok(FirstReleaseChangelog::Summary),
+            Some("empty") => Ok(FirstReleaseChangelog::Empty),
+            Some(other) => Err(format!(
+                "Invalid first_release_changelog {:?}: expected \"full\", \"summary\" or \"empty\"",
+                other
+            )),
+        }
+    }
+}
+
+/// One `[[packages]]` entry of a monorepo
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+pub struct PackageConfig {
+    pub name: String,
+    /// Directory of the package, relative to the repository root
+    pub path: String,
+    /// Defaults to `"{name}-v"`, e.g. `core-v1.2.0`
+    pub tag_prefix: Option<String>,
+    /// Defaults to the top-level `version.initial_version`
+    pub initial_version: Option<String>,
+    /// Files to update, with paths relative to the package directory
+    pub files: Option<Vec<FileUpdateConfig>>,
+}
+
+impl PackageConfig {
+    pub fn tag_prefix(&self) -> String {
+        self.tag_prefix
+            .clone()
+            .unwrap_or_else(|| format!("{}-v", self.name))
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
 pub struct FileUpdateConfig {
     pub path: String,
+    /// Not needed with `json_pointer`
+    #[serde(default)]
     pub marker: String,
     pub template: Option<String>,
+    /// Shell command run right after this file is updated, before the next file
+    pub run_after: Option<String>,
+    /// Treat `marker` as a regex whose first capture group holds the version to replace
+    #[serde(default)]
+    pub regex: bool,
+    /// For `.json` files: the JSON pointer of the string to set, e.g. `/version`
+    pub json_pointer: Option<String>,
+    /// Fail the release instead of warning when the marker matches nothing
+    #[serde(default)]
+    pub require_match: bool,
 }
 
 impl Default for Config {
     fn default() -> Self {
         Self {
+            enabled: true,
             version: VersionConfig {
                 initial_version: Some("0.1.0".to_string()),
                 tag_prefix: Some("v".to_string()),
                 tag_suffix: None,
+                tag_separator: None,
+                tag_format: None,
                 files: Some(vec![FileUpdateConfig {
                     path: "Cargo.toml".to_string(),
-                    marker: "0.0.0+local".to_string(),
+                    marker: DEFAULT_VERSION_MARKER.to_string(),
                     template: None,
+                    run_after: None,
+                    regex: false,
+                    json_pointer: None,
+                    require_match: false,
                 }]),
+                prerelease_order: None,
+                bump_source: None,
+                pr_body_pattern: None,
+                current: None,
+                update_config_version: false,
+                max_bump: None,
+                max_bump_clamp: false,
+                require_files_exist: false,
+                prerelease: None,
+                respect_zerover: true,
+                tag_source: None,
+                stable_tags_only: false,
+                versioning: None,
+                fetch_tags: None,
             },
+            release: ReleaseConfig::default(),
+            changelog: ChangelogConfig::default(),
+            commits: CommitsConfig::default(),
+            commit_types: HashMap::new(),
+            packages: Vec::new(),
         }
     }
 }
 
 impl Config {
-    pub fn load<P: AsRef<Path>>(path: P) -> std::result::Result<Self, Box<dyn std::error::Error>> {
+    pub fn load<P: AsRef<Path>>(path: P) -> std::result::Result<Self, ReleaseError> {
         let path = path.as_ref();
 
         if !path.exists() {
-            println!("⚠️  Configuration file not found, using default configuration");
+            warn!("⚠️  Configuration file not found, using default configuration");
             return Ok(Self::default());
         }
 
-        let content = std::fs::read_to_string(path)
-            .map_err(|e| format!("Failed to read config file {:?}: {}", path, e))?;
+        let content = std::fs::read_to_string(path).map_err(|e| {
+            ReleaseError::Config(format!("Failed to read config file {:?}: {}", path, e))
+        })?;
 
-        let config = toml::from_str(&content)
-            .map_err(|e| format!("Failed to parse TOML config {:?}: {}", path, e))?;
+        let config = if is_yaml(path) {
+            serde_yaml::from_str(&content).map_err(|e| {
+                ReleaseError::Config(format!("Failed to parse YAML config {:?}: {}", path, e))
+            })?
+        } else {
+            toml::from_str(&content).map_err(|e| {
+                ReleaseError::Config(format!("Failed to parse TOML config {:?}: {}", path, e))
+            })?
+        };
 
         Ok(config)
     }
 
-    pub fn save<P: AsRef<Path>>(
-        &self,
+    /// Store `version` as `version.current` in the config file at `path`, leaving the rest of
+    /// the file as written
+    pub fn record_version<P: AsRef<Path>>(
         path: P,
-    ) -> std::result::Result<(), Box<dyn std::error::Error>> {
+        version: &semver::Version,
+    ) -> std::result::Result<(), ReleaseError> {
+        let path = path.as_ref();
+        let content = std::fs::read_to_string(path).map_err(|e| {
+            ReleaseError::Config(format!("Failed to read config file {:?}: {}", path, e))
+        })?;
+        let updated = Self::with_recorded_version(path, &content, version)?;
+
+        std::fs::write(path, updated).map_err(|e| {
+            ReleaseError::Config(format!("Failed to write config file {:?}: {}", path, e))
+        })?;
+
+        Ok(())
+    }
+
+    /// `content` of the config file at `path` with `version.current` set to `version`; only
+    /// that value changes, so comments and formatting survive
+    pub fn with_recorded_version(
+        path: &Path,
+        content: &str,
+        version: &semver::Version,
+    ) -> std::result::Result<String, ReleaseError> {
+        let version = version.to_string();
+        if is_yaml(path) {
+            return record_yaml_version(content, &version).map_err(|e| {
+                ReleaseError::Config(format!("Failed to record version in {:?}: {}", path, e))
+            });
+        }
+
+        let mut document = content.parse::<toml_edit::DocumentMut>().map_err(|e| {
+            ReleaseError::Config(format!("Failed to parse TOML config {:?}: {}", path, e))
+        })?;
+        let current = &mut document["version"]["current"];
+        // Keep a comment trailing the old value
+        let decor = current.as_value().map(|value| value.decor().clone());
+        *current = toml_edit::value(version);
+        if let (Some(decor), Some(value)) = (decor, current.as_value_mut()) {
+            *value.decor_mut() = decor;
+        }
+        Ok(document.to_string())
+    }
+
+    /// The `[commit_types]` table with each bump name parsed
+    pub fn commit_types(&self) -> std::result::Result<HashMap<String, BumpType>, String> {
+        self.commit_types
+            .iter()
+            .map(|(commit_type, bump)| {
+                BumpType::from_name(bump)
+                    .map(|bump| (commit_type.to_lowercase(), bump))
+                    .ok_or_else(|| {
+                        format!(
+                            "Invalid bump {:?} for commit type {:?}: expected \"major\", \"minor\", \"patch\" or \"none\"",
+                            bump, commit_type
+                        )
+                    })
+            })
+            .collect()
+    }
+
+    /// The configuration for releasing `package` on its own: its tag prefix, initial version
+    /// and files replace the top-level ones, and the changelog lives in the package directory
+    pub fn for_package(&self, package: &PackageConfig) -> Config {
+        let package_dir = Path::new(&package.path);
+        let mut config = self.clone();
+        config.packages = Vec::new();
+        config.version.tag_prefix = Some(package.tag_prefix());
+        if package.initial_version.is_some() {
+            config.version.initial_version = package.initial_version.clone();
+        }
+        config.version.files = package.files.as_ref().map(|files| {
+            files
+                .iter()
+                .map(|file_config| FileUpdateConfig {
+                    path: package_dir
+                        .join(&file_config.path)
+                        .to_string_lossy()
+                        .into_owned(),
+                    ..file_config.clone()
+                })
+                .collect()
+        });
+        // `version.current` and the moving major refs describe the repository as a whole
+        config.version.current = None;
+        config.version.update_config_version = false;
+        config.release.major_ref_prefix = Some(format!("{}-", package.name));
+        config.changelog.path = Some(
+            package_dir
+                .join(self.changelog.path())
+                .to_string_lossy()
+                .into_owned(),
+        );
+        config
+    }
+
+    /// Check that the loaded configuration is semantically usable
+    pub fn validate(&self) -> std::result::Result<(), ReleaseError> {
+        self.check().map_err(ReleaseError::Config)
+    }
+
+    fn check(&self) -> std::result::Result<(), String> {
+        if let Some(initial_version) = &self.version.initial_version {
+            semver::Version::parse(initial_version)
+                .map_err(|e| format!("Invalid initial_version {:?}: {}", initial_version, e))?;
+        }
+
+        match self.version.bump_source.as_deref() {
+            None | Some("commits") | Some("pr_body") => {}
+            Some(other) => {
+                return Err(format!(
+                    "Invalid bump_source {:?}: expected \"commits\" or \"pr_body\"",
+                    other
+                ))
+            }
+        }
+
+        self.version.max_bump()?;
+        if let Some(format) = &self.version.tag_format {
+            check_placeholders(
+                "tag_format",
+                format,
+                &["prefix", "separator", "version", "suffix"],
+            )?;
+        }
+        self.version.validate_tag_format()?;
+        self.version.validate_prerelease()?;
+        self.version.tag_source()?;
+        self.version.versioning()?;
+        if let Some(template) = &self.release.commit_message_template {
+            if template.trim().is_empty() {
+                return Err("commit_message_template must not be empty".to_string());
+            }
+            check_placeholders("commit_message_template", template, &["version", "tag"])?;
+        }
+        self.release.major_ref_type()?;
+        if let Some(MajorBranch::Pattern(pattern)) = &self.release.major_branch {
+            if !pattern.contains("{major}") {
+                return Err(format!(
+                    "major_branch {:?} must contain {{major}}; set it to false to disable the ref",
+                    pattern
+                ));
+            }
+            check_placeholders("major_branch", pattern, &["major"])?;
+        }
+        self.changelog.links_to_pull_requests()?;
+        self.changelog.first_release_changelog()?;
+        self.changelog.validate_sections()?;
+        self.commit_types()?;
+        self.validate_packages()?;
+
+        regex::Regex::new(self.version.pr_body_pattern())
+            .map_err(|e| format!("Invalid pr_body_pattern: {}", e))?;
+
+        if let Some(files) = &self.version.files {
+            for file_config in files {
+                if file_config.path.trim().is_empty() {
+                    return Err("File update entry has an empty path".to_string());
+                }
+                if let Some(pointer) = &file_config.json_pointer {
+                    if !file_config.path.ends_with(".json") {
+                        return Err(format!(
+                            "json_pointer {:?} is set for {:?}, which is not a .json file",
+                            pointer, file_config.path
+                        ));
+                    }
+                } else if file_config.marker.is_empty() {
+                    return Err(format!(
+                        "File update entry {:?} has an empty marker",
+                        file_config.path
+                    ));
+                }
+                if let Some(template) = &file_config.template {
+                    check_placeholders(
+                        &format!("Template for {:?}", file_config.path),
+                        template,
+                        &["version", "major", "minor", "patch", "prerelease", "date"],
+                    )?;
+                }
+                if file_config.regex {
+                    let pattern = regex::Regex::new(&file_config.marker).map_err(|e| {
+                        format!("Invalid marker regex for {:?}: {}", file_config.path, e)
+                    })?;
+                    if pattern.captures_len() < 2 {
+                        return Err(format!(
+                            "Marker regex for {:?} needs a capture group around the version",
+                            file_config.path
+                        ));
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Fail with a readable message unless `path` holds a valid configuration
+    pub fn validate_file<P: AsRef<Path>>(path: P) -> std::result::Result<Self, ReleaseError> {
+        let path = path.as_ref();
+        if !path.exists() {
+            return Err(ReleaseError::Config(format!(
+                "Configuration file {:?} not found",
+                path
+            )));
+        }
+
+        let config = Self::load(path)?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    /// Packages need distinct names and tag prefixes so their releases can't collide
+    fn validate_packages(&self) -> std::result::Result<(), String> {
+        let mut names = std::collections::HashSet::new();
+        let mut prefixes = std::collections::HashSet::new();
+        if let Some(format) = &self.version.tag_format {
+            if !self.packages.is_empty() && !format.contains("{prefix}") {
+                return Err(format!(
+                    "tag_format {:?} needs {{prefix}} so packages get distinct tags",
+                    format
+                ));
+            }
+        }
+        for package in &self.packages {
+            if package.name.trim().is_empty() || package.path.trim().is_empty() {
+                return Err("Package entries need a non-empty name and path".to_string());
+            }
+            if !names.insert(package.name.as_str()) {
+                return Err(format!("Duplicate package name {:?}", package.name));
+            }
+            if !prefixes.insert(package.tag_prefix()) {
+                return Err(format!(
+                    "Package {:?} reuses tag_prefix {:?} of another package",
+                    package.name,
+                    package.tag_prefix()
+                ));
+            }
+            if let Some(initial_version) = &package.initial_version {
+                semver::Version::parse(initial_version).map_err(|e| {
+                    format!(
+                        "Invalid initial_version {:?} for package {:?}: {}",
+                        initial_version, package.name, e
+                    )
+                })?;
+            }
+        }
+        Ok(())
+    }
+
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> std::result::Result<(), ReleaseError> {
         let path = path.as_ref();
-        let content = toml::to_string(self)
-            .map_err(|e| format!("Failed to serialize config to TOML: {}", e))?;
+        let content = if is_yaml(path) {
+            serde_yaml::to_string(self).map_err(|e| {
+                ReleaseError::Config(format!("Failed to serialize config to YAML: {}", e))
+            })?
+        } else {
+            toml::to_string(self).map_err(|e| {
+                ReleaseError::Config(format!("Failed to serialize config to TOML: {}", e))
+            })?
+        };
 
-        std::fs::write(path, content)
-            .map_err(|e| format!("Failed to write config file {:?}: {}", path, e))?;
+        std::fs::write(path, content).map_err(|e| {
+            ReleaseError::Config(format!("Failed to write config file {:?}: {}", path, e))
+        })?;
 
         Ok(())
     }
 }
+
+/// Set `current` under the top-level block `version:` mapping, replacing the existing line or
+/// adding one as the mapping's first entry
+fn record_yaml_version(content: &str, version: &str) -> std::result::Result<String, String> {
+    let mut lines: Vec<String> = content.lines().map(str::to_string).collect();
+    let is_comment_or_blank = |line: &str| {
+        let trimmed = line.trim();
+        trimmed.is_empty() || trimmed.starts_with('#')
+    };
+
+    let start = lines
+        .iter()
+        .position(|line| {
+            line.strip_prefix("version:")
+                .is_some_and(&is_comment_or_blank)
+        })
+        .ok_or("no block-style top-level `version:` mapping")?;
+
+    // The mapping's entries are the indented lines that follow it
+    let entries: Vec<usize> = (start + 1..lines.len())
+        .take_while(|&index| {
+            is_comment_or_blank(&lines[index]) || lines[index].starts_with([' ', '\t'])
+        })
+        .filter(|&index| !is_comment_or_blank(&lines[index]))
+        .collect();
+    let indent = entries
+        .first()
+        .map(|&index| {
+            let line = &lines[index];
+            line[..line.len() - line.trim_start().len()].to_string()
+        })
+        .unwrap_or_else(|| "  ".to_string());
+
+    let entry = format!("{}current: \"{}\"", indent, version);
+    let existing = entries.into_iter().find(|&index| {
+        lines[index]
+            .strip_prefix(indent.as_str())
+            .is_some_and(|rest| rest.starts_with("current:"))
+    });
+    match existing {
+        Some(index) => lines[index] = entry,
+        None => lines.insert(start + 1, entry),
+    }
+
+    let mut updated = lines.join("\n");
+    if content.ends_with('\n') {
+        updated.push('\n');
+    }
+    Ok(updated)
+}
+
+/// Config files ending in `.yaml` or `.yml` are YAML, everything else is TOML
+fn is_yaml(path: &Path) -> bool {
+    matches!(
+        path.extension().and_then(|extension| extension.to_str()),
+        Some("yaml") | Some("yml")
+    )
+}
+
+/// Fail when `template` uses a `{placeholder}` other than the `allowed` ones
+fn check_placeholders(
+    what: &str,
+    template: &str,
+    allowed: &[&str],
+) -> std::result::Result<(), String> {
+    let placeholder = regex::Regex::new(r"\{(\w+)\}").expect("placeholder pattern is valid");
+    for captures in placeholder.captures_iter(template) {
+        if !allowed.contains(&&captures[1]) {
+            let expected: Vec<String> =
+                allowed.iter().map(|name| format!("{{{}}}", name)).collect();
+            return Err(format!(
+                "{} {:?} uses unknown placeholder {}; expected {}",
+                what,
+                template,
+                &captures[0],
+                expected.join(", ")
+            ));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_record_version_preserves_other_fields() {
+        let temp_dir = TempDir::new().expect("Failed to create temp directory");
+        let path = temp_dir.path().join(".release-config.toml");
+        std::fs::write(
+            &path,
+            r#"[version]
+initial_version = "0.0.0"
+tag_prefix = "v"
+update_config_version = true
+
+[[version.files]]
+path = "Cargo.toml"
+marker = "0.0.0+dev"
+"#,
+        )
+        .unwrap();
+
+        Config::record_version(&path, &semver::Version::parse("1.4.0").unwrap()).unwrap();
+
+        let config = Config::load(&path).unwrap();
+        assert_eq!(config.version.current.as_deref(), Some("1.4.0"));
+        assert_eq!(config.version.tag_prefix.as_deref(), Some("v"));
+        assert!(config.version.update_config_version);
+        let files = config.version.files.unwrap();
+        assert_eq!(files[0].marker, "0.0.0+dev");
+    }
+
+    #[test]
+    fn test_record_version_only_changes_current() {
+        let version = semver::Version::parse("1.4.0").unwrap();
+        let toml = r#"# Release settings
+[version]
+initial_version = "0.0.0" # first release
+current = "1.3.0" # maintained by the action
+
+[release]
+draft = true
+"#;
+        assert_eq!(
+            Config::with_recorded_version(Path::new(".release-config.toml"), toml, &version)
+                .unwrap(),
+            toml.replace("\"1.3.0\"", "\"1.4.0\"")
+        );
+
+        let yaml = "# Release settings\nversion:\n    # first release\n    initial_version: 0.0.0\nrelease:\n  draft: true\n";
+        let updated =
+            Config::with_recorded_version(Path::new("release.yml"), yaml, &version).unwrap();
+        assert_eq!(
+            updated,
+            "# Release settings\nversion:\n    current: \"1.4.0\"\n    # first release\n    initial_version: 0.0.0\nrelease:\n  draft: true\n"
+        );
+        let updated = Config::with_recorded_version(
+            Path::new("release.yml"),
+            &updated,
+            &semver::Version::parse("1.5.0").unwrap(),
+        )
+        .unwrap();
+        assert!(updated.contains("    current: \"1.5.0\"\n"), "{}", updated);
+        assert!(!updated.contains("1.4.0"), "{}", updated);
+        // Nothing else is written out
+        assert!(!updated.contains("enabled"), "{}", updated);
+    }
+
+    #[test]
+    fn test_tag_name_with_separator() {
+        let mut version = Config::default().version;
+        let v = semver::Version::parse("1.2.3").unwrap();
+
+        assert_eq!(version.tag_name(&v), "v1.2.3");
+        assert_eq!(version.version_part("v1.2.3"), "1.2.3");
+
+        version.tag_prefix = Some("release".to_string());
+        version.tag_separator = Some("-".to_string());
+        assert_eq!(version.tag_name(&v), "release-1.2.3");
+        assert_eq!(version.version_part("release-1.2.3"), "1.2.3");
+
+        version.tag_prefix = Some("pkg".to_string());
+        version.tag_separator = Some("/v".to_string());
+        version.tag_suffix = Some("-final".to_string());
+        assert_eq!(version.tag_name(&v), "pkg/v1.2.3-final");
+        assert_eq!(version.version_part("pkg/v1.2.3-final"), "1.2.3");
+    }
+
+    #[test]
+    fn test_commit_types_table() {
+        let config: Config = toml::from_str(
+            r#"[version]
+initial_version = "0.1.0"
+
+[commit_types]
+improvement = "minor"
+docs = "patch"
+"#,
+        )
+        .unwrap();
+
+        let commit_types = config.commit_types().unwrap();
+        assert_eq!(commit_types["improvement"], BumpType::Minor);
+        assert_eq!(commit_types["docs"], BumpType::Patch);
+
+        let mut config = Config::default();
+        config
+            .commit_types
+            .insert("improvement".to_string(), "huge".to_string());
+        let error = config.validate().unwrap_err().to_string();
+        assert!(error.contains("huge"), "Unexpected error: {}", error);
+    }
+
+    #[test]
+    fn test_custom_tag_format() {
+        let mut config = Config::default();
+        config.version.tag_format = Some("mypkg/v{version}".to_string());
+        config.validate().unwrap();
+
+        let version = semver::Version::parse("1.4.0-rc.2").unwrap();
+        let tag_name = config.version.tag_name(&version);
+        assert_eq!(tag_name, "mypkg/v1.4.0-rc.2");
+        assert_eq!(
+            semver::Version::parse(config.version.version_part(&tag_name)).unwrap(),
+            version
+        );
+        // Tags of another format don't parse as versions
+        assert!(semver::Version::parse(config.version.version_part("other/v1.0.0")).is_err());
+
+        // The prefix and suffix can be placed anywhere
+        config.version.tag_format = Some("release/{prefix}{version}+{suffix}".to_string());
+        config.version.tag_suffix = Some("linux".to_string());
+        assert_eq!(config.version.tag_name(&"2.0.0"), "release/v2.0.0+linux");
+        assert_eq!(config.version.version_part("release/v2.0.0+linux"), "2.0.0");
+    }
+
+    #[test]
+    fn test_tag_format_validation() {
+        let mut config = Config::default();
+        config.version.tag_format = Some("v{major}".to_string());
+        assert!(config.validate().is_err());
+
+        config.version.tag_format = Some("{version}-{version}".to_string());
+        assert!(config.validate().is_err());
+
+        config.version.tag_format = Some("app/v{version}".to_string());
+        config.packages = vec![PackageConfig {
+            name: "core".to_string(),
+            path: "crates/core".to_string(),
+            tag_prefix: None,
+            initial_version: None,
+            files: None,
+        }];
+        let error = config.validate().unwrap_err().to_string();
+        assert!(error.contains("{prefix}"), "Unexpected error: {}", error);
+    }
+
+    #[test]
+    fn test_commit_message_template() {
+        let mut release = ReleaseConfig::default();
+        let version = semver::Version::parse("1.2.0").unwrap();
+        assert_eq!(
+            release.commit_message(&version, "v1.2.0"),
+            "chore: release version 1.2.0"
+        );
+
+        release.commit_message_template = Some("chore(release): {tag} [skip ci]".to_string());
+        assert_eq!(
+            release.commit_message(&version, "v1.2.0"),
+            "chore(release): v1.2.0 [skip ci]"
+        );
+    }
+
+    #[test]
+    fn test_validate_config_file() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let path = temp_dir.path().join(".release-config.toml");
+
+        std::fs::write(
+            &path,
+            r#"[version]
+initial_version = "0.1.0"
+
+[[version.files]]
+path = "VERSION"
+marker = "0.0.0"
+template = "{major}.{minor}.{patch}"
+"#,
+        )
+        .unwrap();
+        Config::validate_file(&path).unwrap();
+
+        std::fs::write(&path, "[version]\ninitial_version = \"one\"\n").unwrap();
+        let error = Config::validate_file(&path).unwrap_err();
+        assert!(matches!(error, ReleaseError::Config(_)));
+        assert!(error.to_string().contains("initial_version"), "{}", error);
+
+        std::fs::write(
+            &path,
+            "[version]\n\n[release]\ncommit_message_template = \"release {versoin}\"\n",
+        )
+        .unwrap();
+        let error = Config::validate_file(&path).unwrap_err().to_string();
+        assert!(error.contains("unknown placeholder {versoin}"), "{}", error);
+
+        let missing = Config::validate_file(temp_dir.path().join("missing.toml")).unwrap_err();
+        assert!(missing.to_string().contains("not found"), "{}", missing);
+    }
+
+    #[test]
+    fn test_major_branch_setting() {
+        let parse = |release: &str| {
+            toml::from_str::<Config>(&format!("[version]\n\n[release]\n{}", release))
+                .unwrap()
+                .release
+        };
+        let version = semver::Version::parse("2.4.0").unwrap();
+
+        assert_eq!(parse("").major_ref_name(&version).as_deref(), Some("v2"));
+        assert_eq!(parse("major_branch = false").major_ref_name(&version), None);
+        assert_eq!(
+            parse("major_branch = \"release/v{major}\"")
+                .major_ref_name(&version)
+                .as_deref(),
+            Some("release/v2")
+        );
+
+        let mut config = Config::default();
+        config.release.major_branch = Some(MajorBranch::Pattern("stable".to_string()));
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_versioning_validation() {
+        let mut config = Config::default();
+        assert_eq!(config.version.versioning(), Ok(Versioning::SemVer));
+        config.version.versioning = Some("calver".to_string());
+        assert_eq!(config.version.versioning(), Ok(Versioning::CalVer));
+        config.version.versioning = Some("datever".to_string());
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_tag_source_validation() {
+        let mut config = Config::default();
+        assert_eq!(config.version.tag_source(), Ok(TagSource::Api));
+
+        config.version.tag_source = Some("local".to_string());
+        assert_eq!(config.version.tag_source(), Ok(TagSource::Local));
+
+        config.version.tag_source = Some("remote".to_string());
+        assert!(matches!(config.validate(), Err(ReleaseError::Config(_))));
+    }
+
+    #[test]
+    fn test_tag_separator_validation() {
+        let mut config = Config::default();
+        config.version.tag_prefix = Some("v-".to_string());
+        config.version.tag_separator = Some("-".to_string());
+        let error = config.validate().unwrap_err().to_string();
+        assert!(error.contains("v--1.2.3"), "Unexpected error: {}", error);
+
+        config.version.tag_prefix = None;
+        assert!(matches!(config.validate(), Err(ReleaseError::Config(_))));
+
+        config.version.tag_prefix = Some("v".to_string());
+        assert!(config.validate().is_ok());
+    }
+
+    const TWO_PACKAGES: &str = r#"
+[version]
+initial_version = "0.1.0"
+tag_prefix = "v"
+
+[[packages]]
+name = "core"
+path = "crates/core"
+initial_version = "1.0.0"
+files = [{ path = "Cargo.toml", marker = "0.0.0+local" }]
+
+[[packages]]
+name = "cli"
+path = "crates/cli"
+tag_prefix = "cli@"
+"#;
+
+    #[test]
+    fn test_package_config_overrides() {
+        let config: Config = toml::from_str(TWO_PACKAGES).unwrap();
+        config.validate().unwrap();
+        assert_eq!(config.packages.len(), 2);
+
+        let core = config.for_package(&config.packages[0]);
+        assert_eq!(core.version.tag_name(&"1.2.0"), "core-v1.2.0");
+        assert_eq!(core.version.initial_version.as_deref(), Some("1.0.0"));
+        let files = core.version.files.unwrap();
+        assert_eq!(
+            Path::new(&files[0].path),
+            Path::new("crates/core/Cargo.toml")
+        );
+        assert_eq!(
+            Path::new(core.changelog.path()),
+            Path::new("crates/core/CHANGELOG.md")
+        );
+        assert_eq!(core.release.major_ref_prefix(), "core-");
+
+        let cli = config.for_package(&config.packages[1]);
+        assert_eq!(cli.version.tag_name(&"0.2.0"), "cli@0.2.0");
+        assert_eq!(cli.version.initial_version.as_deref(), Some("0.1.0"));
+        assert!(cli.version.files.is_none());
+    }
+
+    #[test]
+    fn test_packages_compute_versions_independently() {
+        use crate::version_manager::VersionManager;
+
+        let config: Config = toml::from_str(TWO_PACKAGES).unwrap();
+        let core = config.for_package(&config.packages[0]);
+        let cli = config.for_package(&config.packages[1]);
+        let tags = [
+            "v9.0.0",
+            "core-v1.2.0",
+            "core-v1.3.0",
+            "cli@0.4.1",
+            "cli@0.4.0",
+        ];
+
+        let core_manager = VersionManager::offline(&core);
+        let core_current = core_manager
+            .select_latest_version(tags.iter().copied())
+            .unwrap();
+        assert_eq!(core_current.to_string(), "1.3.0");
+        assert_eq!(
+            core_manager
+                .calculate_new_version(&core_current, &BumpType::Minor)
+                .unwrap()
+                .to_string(),
+            "1.4.0"
+        );
+
+        let cli_manager = VersionManager::offline(&cli);
+        let cli_current = cli_manager
+            .select_latest_version(tags.iter().copied())
+            .unwrap();
+        assert_eq!(cli_current.to_string(), "0.4.1");
+        assert_eq!(
+            cli_manager
+                .calculate_new_version(&cli_current, &BumpType::Patch)
+                .unwrap()
+                .to_string(),
+            "0.4.2"
+        );
+    }
+
+    #[test]
+    fn test_packages_need_distinct_tag_prefixes() {
+        let mut config: Config = toml::from_str(TWO_PACKAGES).unwrap();
+        config.packages[1].tag_prefix = Some("core-v".to_string());
+        let error = config.validate().unwrap_err().to_string();
+        assert!(error.contains("core-v"), "Unexpected error: {}", error);
+    }
+
+    #[test]
+    fn test_yaml_and_toml_configs_are_equivalent() {
+        let temp_dir = TempDir::new().expect("Failed to create temp directory");
+        let toml_path = temp_dir.path().join(".release-config.toml");
+        let yaml_path = temp_dir.path().join(".releaserc.yaml");
+        std::fs::write(
+            &toml_path,
+            r#"
+[version]
+tag_prefix = "v"
+files = [{ path = "package.json", marker = "0.0.0+local" }]
+
+[release]
+draft = true
+
+[commit_types]
+improvement = "minor"
+"#,
+        )
+        .unwrap();
+        std::fs::write(
+            &yaml_path,
+            r#"
+version:
+  tag_prefix: v
+  files:
+    - path: package.json
+      marker: 0.0.0+local
+release:
+  draft: true
+commit_types:
+  improvement: minor
+"#,
+        )
+        .unwrap();
+
+        let from_toml = Config::load(&toml_path).unwrap();
+        let from_yaml = Config::load(&yaml_path).unwrap();
+        assert_eq!(from_toml, from_yaml);
+        assert!(from_yaml.release.draft);
+
+        // Recording the version keeps the file in its own format
+        Config::record_version(&yaml_path, &semver::Version::parse("1.2.3").unwrap()).unwrap();
+        let reloaded = Config::load(&yaml_path).unwrap();
+        assert_eq!(reloaded.version.current.as_deref(), Some("1.2.3"));
+    }
+}
@@ -0,0 +1,138 @@
+use crate::commit::Commit;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Contributor {
+    pub name: String,
+    pub email: String,
+    pub login: Option<String>,
+}
+
+impl Contributor {
+    /// Name used in release notes and outputs: `@login` when known, otherwise the author name
+    pub fn display_name(&self) -> String {
+        match &self.login {
+            Some(login) => format!("@{}", login),
+            None => self.name.clone(),
+        }
+    }
+}
+
+/// Extract a GitHub login from a `users.noreply.github.com` address.
+///
+/// GitHub uses both `login@users.noreply.github.com` and
+/// `12345+login@users.noreply.github.com`; other addresses yield `None`.
+pub fn github_login_from_email(email: &str) -> Option<String> {
+    let local = email
+        .to_lowercase()
+        .strip_suffix("@users.noreply.github.com")?
+        .to_string();
+    let login = match local.split_once('+') {
+        Some((_, login)) => login.to_string(),
+        None => local,
+    };
+
+    if login.is_empty() {
+        None
+    } else {
+        Some(login)
+    }
+}
+
+/// Collect the unique authors of the given commits, sorted by display name.
+///
+/// Authors are deduplicated on their GitHub login when one is known and on
+/// their (case-insensitive) email address otherwise.
+pub fn collect_contributors(commits: &[Commit]) -> Vec<Contributor> {
+    let mut contributors: Vec<Contributor> = Vec::new();
+
+    for commit in commits {
+        let login = github_login_from_email(&commit.author_email);
+        let already_listed = contributors
+            .iter()
+            .any(|existing| match (&login, &existing.login) {
+                (Some(login), Some(existing_login)) => login == existing_login,
+                _ => existing.email.eq_ignore_ascii_case(&commit.author_email),
+            });
+
+        if !already_listed {
+            contributors.push(Contributor {
+                name: commit.author_name.clone(),
+                email: commit.author_email.clone(),
+                login,
+            });
+        }
+    }
+
+    contributors.sort_by_key(|contributor| contributor.display_name().to_lowercase());
+    contributors
+}
+
+/// Render a markdown "Contributors" section, or an empty string when there are none
+pub fn render_contributors_section(contributors: &[Contributor]) -> String {
+    if contributors.is_empty() {
+        return String::new();
+    }
+
+    let mut section = String::from("## Contributors\n\n");
+    for contributor in contributors {
+        section.push_str(&format!("- {}\n", contributor.display_name()));
+    }
+    section
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn commit(name: &str, email: &str) -> Commit {
+        Commit {
+            sha: "0000000".to_string(),
+            message: "fix: something".to_string(),
+            author_name: name.to_string(),
+            author_email: email.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_github_login_from_email() {
+        assert_eq!(
+            github_login_from_email("12345+octocat@users.noreply.github.com"),
+            Some("octocat".to_string())
+        );
+        assert_eq!(
+            github_login_from_email("octocat@users.noreply.github.com"),
+            Some("octocat".to_string())
+        );
+        assert_eq!(github_login_from_email("octocat@example.com"), None);
+    }
+
+    #[test]
+    fn test_collect_contributors_deduplicates_and_sorts() {
+        let commits = vec![
+            commit("Zoe", "zoe@example.com"),
+            commit("The Octocat", "12345+octocat@users.noreply.github.com"),
+            commit("Zoe", "ZOE@example.com"),
+            commit("Octo Cat", "octocat@users.noreply.github.com"),
+            commit("Alice", "alice@example.com"),
+        ];
+
+        let contributors = collect_contributors(&commits);
+        let names: Vec<String> = contributors.iter().map(|c| c.display_name()).collect();
+
+        assert_eq!(names, vec!["@octocat", "Alice", "Zoe"]);
+    }
+
+    #[test]
+    fn test_render_contributors_section() {
+        let contributors = collect_contributors(&[
+            commit("Alice", "alice@example.com"),
+            commit("The Octocat", "octocat@users.noreply.github.com"),
+        ]);
+
+        assert_eq!(
+            render_contributors_section(&contributors),
+            "## Contributors\n\n- @octocat\n- Alice\n"
+        );
+        assert_eq!(render_contributors_section(&[]), "");
+    }
+}
@@ -1,4 +1,7 @@
 use crate::bump_type::BumpType;
+use crate::config::Config;
+use regex::Regex;
+use std::collections::HashMap;
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct ConventionalCommit {
@@ -7,35 +10,230 @@ pub struct ConventionalCommit {
     pub description: String,
     pub body: Option<String>,
     pub footer: Option<String>,
+    /// Footer trailers as `(token, value)` pairs, in message order
+    pub footers: Vec<(String, String)>,
     pub breaking_change: bool,
+    /// Text of every `BREAKING CHANGE:` footer, in message order
+    pub breaking_descriptions: Vec<String>,
+}
+
+/// Options controlling how lenient commit parsing is
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseOptions {
+    /// Accept a `BREAKING CHANGE:` token anywhere in the message body, not only as a
+    /// proper footer in the final paragraph
+    pub scan_body_for_breaking: bool,
+    /// Alternative type names mapped to their canonical type
+    pub type_aliases: HashMap<String, String>,
+    /// Bumps for custom or overridden commit types, consulted before the built-in mapping
+    pub commit_types: HashMap<String, BumpType>,
+    /// The only types accepted by validation, when set
+    pub allowed_types: Option<Vec<String>>,
+}
+
+impl Default for ParseOptions {
+    fn default() -> Self {
+        Self {
+            scan_body_for_breaking: true,
+            type_aliases: HashMap::new(),
+            commit_types: HashMap::new(),
+            allowed_types: None,
+        }
+    }
+}
+
+impl ParseOptions {
+    pub fn from_config(config: &Config) -> Self {
+        Self {
+            scan_body_for_breaking: config.commits.scan_body_for_breaking,
+            type_aliases: config.commits.type_aliases.clone(),
+            commit_types: config.commit_types().unwrap_or_default(),
+            allowed_types: config.commits.allowed_types.clone(),
+        }
+    }
+}
+
+/// Commit types recognized without any configuration
+pub const DEFAULT_TYPES: &[&str] = &[
+    "feat", "fix", "docs", "style", "refactor", "perf", "test", "chore", "build", "ci", "revert",
+    "security",
+];
+
+impl ParseOptions {
+    /// Whether `commit_type` (already canonicalized) is accepted: any type is, unless
+    /// `allowed_types` restricts them
+    pub fn is_known_type(&self, commit_type: &str) -> bool {
+        match &self.allowed_types {
+            Some(allowed) => allowed.iter().any(|allowed| allowed == commit_type),
+            None => true,
+        }
+    }
+
+    /// Every accepted type name, including aliases, for error messages
+    pub fn known_types(&self) -> Vec<String> {
+        if let Some(allowed) = &self.allowed_types {
+            let mut aliases: Vec<String> = self
+                .type_aliases
+                .iter()
+                .filter(|(_, canonical)| allowed.contains(canonical))
+                .map(|(alias, _)| alias.clone())
+                .collect();
+            aliases.sort();
+            return allowed.iter().cloned().chain(aliases).collect();
+        }
+        let mut types: Vec<String> = DEFAULT_TYPES.iter().map(|t| t.to_string()).collect();
+        let mut custom: Vec<String> = self
+            .commit_types
+            .keys()
+            .filter(|t| !DEFAULT_TYPES.contains(&t.as_str()))
+            .cloned()
+            .collect();
+        custom.sort();
+        types.extend(custom);
+        let mut aliases: Vec<String> = self.type_aliases.keys().cloned().collect();
+        aliases.sort();
+        types.extend(aliases);
+        types
+    }
+}
+
+/// Characters allowed in commit types and footer tokens: alphanumerics and hyphens, as in
+/// git trailers (`Reviewed-by`, `X-Build-Id`)
+fn is_token_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c == '-'
+}
+
+fn is_token(text: &str) -> bool {
+    !text.is_empty() && text.chars().all(is_token_char)
+}
+
+/// Footer tokens marking a breaking change; the spec allows the hyphenated spelling because
+/// git trailer tokens can't contain spaces
+const BREAKING_TOKENS: [&str; 2] = ["BREAKING CHANGE:", "BREAKING-CHANGE:"];
+
+fn mentions_breaking_change(line: &str) -> bool {
+    BREAKING_TOKENS.iter().any(|token| line.contains(token))
+}
+
+/// Text following a breaking change token at the start of `line`
+fn breaking_change_text(line: &str) -> Option<&str> {
+    BREAKING_TOKENS
+        .iter()
+        .find_map(|token| line.strip_prefix(token))
+}
+
+/// Whether `line` is a footer: `Token: value`, `Token #value` or a `BREAKING CHANGE:` line
+fn is_footer_line(line: &str) -> bool {
+    if mentions_breaking_change(line) {
+        return true;
+    }
+
+    let token_len = line.chars().take_while(|c| is_token_char(*c)).count();
+    token_len > 0 && {
+        let rest = &line[token_len..];
+        rest.starts_with(": ") || rest.starts_with(" #")
+    }
+}
+
+/// Split a footer line into its token and value: `Token: value` yields `value`, while the
+/// issue reference form `Token #value` keeps the `#` (`#42`)
+fn split_footer(line: &str) -> Option<(String, String)> {
+    if let Some(token) = BREAKING_TOKENS
+        .iter()
+        .find(|token| line.starts_with(*token))
+    {
+        let key = &token[..token.len() - 1];
+        return Some((key.to_string(), line[token.len()..].trim().to_string()));
+    }
+
+    let token_len = line.chars().take_while(|c| is_token_char(*c)).count();
+    if token_len == 0 {
+        return None;
+    }
+    let (token, rest) = line.split_at(token_len);
+    let value = rest.strip_prefix(": ").or_else(|| {
+        rest.strip_prefix(' ')
+            .filter(|value| value.starts_with('#'))
+    })?;
+    Some((token.to_string(), value.trim().to_string()))
+}
+
+/// Footer lines as `(token, value)` pairs; lines that don't start a new footer continue the
+/// value of the previous one
+fn parse_footers(footer: &str) -> Vec<(String, String)> {
+    let mut footers: Vec<(String, String)> = Vec::new();
+    for line in footer.lines() {
+        match (split_footer(line), footers.last_mut()) {
+            (Some(pair), _) => footers.push(pair),
+            (None, Some((_, value))) => {
+                value.push('\n');
+                value.push_str(line.trim());
+            }
+            (None, None) => {}
+        }
+    }
+    footers
+}
+
+/// Lines of the trailing paragraph of a message (where git trailers live), if it has a body
+fn trailer_lines(message: &str) -> Vec<&str> {
+    let paragraphs: Vec<&str> = message
+        .split("\n\n")
+        .filter(|paragraph| !paragraph.trim().is_empty())
+        .collect();
+
+    match paragraphs.as_slice() {
+        [_header, .., last] => last.lines().collect(),
+        _ => Vec::new(),
+    }
 }
 
 impl ConventionalCommit {
     pub fn parse(message: &str) -> Result<Self, String> {
+        Self::parse_with(message, &ParseOptions::default())
+    }
+
+    pub fn parse_with(message: &str, options: &ParseOptions) -> Result<Self, String> {
         let lines: Vec<&str> = message.split('\n').collect();
         let header = lines[0];
 
         // Parse header: type(scope)!: description
-        let breaking_change = header.contains('!');
-
-        // Find the colon
         let colon_pos = header.find(':').ok_or("Invalid format: missing ':'")?;
         let (type_part, description) = header.split_at(colon_pos);
         let description = description[1..].trim().to_string();
 
+        // The breaking change marker only counts right before the colon
+        let (type_part, breaking_change) = match type_part.strip_suffix('!') {
+            Some(type_part) => (type_part, true),
+            None => (type_part, false),
+        };
+
         // Parse type and scope
-        let type_part = type_part.replace('!', ""); // Remove breaking change marker
         let (commit_type, scope) = if let Some(paren_start) = type_part.find('(') {
             let paren_end = type_part
                 .find(')')
                 .ok_or("Invalid format: unclosed parenthesis")?;
-            let commit_type = type_part[..paren_start].to_string();
+            let commit_type = type_part[..paren_start].to_lowercase();
             let scope = type_part[paren_start + 1..paren_end].to_string();
             (commit_type, Some(scope))
         } else {
-            (type_part.to_string(), None)
+            (type_part.to_lowercase(), None)
         };
 
+        if !is_token(&commit_type) {
+            return Err(format!(
+                "Invalid commit type {:?}: only letters, digits and '-' are allowed",
+                commit_type
+            ));
+        }
+
+        // Map aliases such as `feature` onto their canonical type
+        let commit_type = options
+            .type_aliases
+            .get(&commit_type)
+            .cloned()
+            .unwrap_or(commit_type);
+
         // Parse body and footer
         let mut body = None;
         let mut footer = None;
@@ -51,14 +249,7 @@ impl ConventionalCommit {
                 }
 
                 // Check if this is a footer (BREAKING CHANGE: or token: value)
-                if line.contains("BREAKING CHANGE:")
-                    || (line.contains(':')
-                        && line
-                            .chars()
-                            .take_while(|c| c.is_alphabetic() || *c == '-')
-                            .count()
-                            > 0)
-                {
+                if is_footer_line(line) {
                     in_footer = true;
                 }
 
@@ -77,11 +268,33 @@ impl ConventionalCommit {
             }
         }
 
-        // Check for BREAKING CHANGE in footer
+        // Check for BREAKING CHANGE: anywhere after the header when lenient, or only as a
+        // trailer in the final paragraph when strict
+        let breaking_lines: Vec<&str> = if options.scan_body_for_breaking {
+            footer
+                .as_deref()
+                .map(|f| f.lines().collect())
+                .unwrap_or_default()
+        } else {
+            trailer_lines(message)
+                .into_iter()
+                .filter(|line| breaking_change_text(line).is_some())
+                .collect()
+        };
         let breaking_change = breaking_change
-            || footer
-                .as_ref()
-                .map_or(false, |f| f.contains("BREAKING CHANGE:"));
+            || breaking_lines
+                .iter()
+                .any(|line| mentions_breaking_change(line));
+
+        let footers = footer.as_deref().map(parse_footers).unwrap_or_default();
+
+        // The text of each BREAKING CHANGE footer describes what users need to change
+        let breaking_descriptions = breaking_lines
+            .iter()
+            .filter_map(|line| breaking_change_text(line))
+            .map(|text| text.trim().to_string())
+            .filter(|text| !text.is_empty())
+            .collect();
 
         Ok(ConventionalCommit {
             commit_type,
@@ -89,7 +302,9 @@ impl ConventionalCommit {
             description,
             body,
             footer,
+            footers,
             breaking_change,
+            breaking_descriptions,
         })
     }
 
@@ -107,6 +322,48 @@ impl ConventionalCommit {
             }
         }
     }
+
+    /// Like `bump_type`, but consulting the configured `commit_types` first; breaking changes
+    /// are always major
+    pub fn bump_type_with(&self, options: &ParseOptions) -> BumpType {
+        match options.commit_types.get(&self.commit_type) {
+            Some(bump) if !self.breaking_change => bump.clone(),
+            _ => self.bump_type(),
+        }
+    }
+
+    /// Version requested by a `Release-As: <version>` footer, unparsed
+    pub fn release_as(&self) -> Option<&str> {
+        self.footers
+            .iter()
+            .find(|(token, _)| token.eq_ignore_ascii_case("Release-As"))
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Issue and pull request numbers referenced from the body or footers with keywords such
+    /// as `Closes #12` or `Refs: #3, #4`, sorted and without duplicates
+    pub fn referenced_issues(&self) -> Vec<u64> {
+        let keyword = Regex::new(
+            r"(?i)\b(?:close[sd]?|fix(?:e[sd])?|resolve[sd]?|refs?|references?)\b:?((?:[\s,]*(?:and\s+)?#\d+)+)",
+        )
+        .expect("issue reference pattern is valid");
+        let number = Regex::new(r"#(\d+)").expect("issue number pattern is valid");
+
+        let mut issues: Vec<u64> = [self.body.as_deref(), self.footer.as_deref()]
+            .into_iter()
+            .flatten()
+            .flat_map(|text| keyword.captures_iter(text))
+            .flat_map(|references| {
+                number
+                    .captures_iter(references.get(1).map_or("", |m| m.as_str()))
+                    .filter_map(|issue| issue[1].parse().ok())
+                    .collect::<Vec<u64>>()
+            })
+            .collect();
+        issues.sort_unstable();
+        issues.dedup();
+        issues
+    }
 }
 
 #[cfg(test)]
@@ -119,8 +376,26 @@ mod tests {
         assert_eq!(commit.commit_type, "feat");
         assert_eq!(commit.scope, None);
         assert_eq!(commit.description, "add new feature");
-        assert_eq!(commit.breaking_change, false);
+        assert!(!commit.breaking_change);
+        assert_eq!(commit.bump_type(), BumpType::Minor);
+    }
+
+    #[test]
+    fn test_type_aliases_are_canonicalized() {
+        let options = ParseOptions {
+            type_aliases: HashMap::from([
+                ("feature".to_string(), "feat".to_string()),
+                ("bugfix".to_string(), "fix".to_string()),
+            ]),
+            ..ParseOptions::default()
+        };
+
+        let commit = ConventionalCommit::parse_with("feature(ui): dark mode", &options).unwrap();
+        assert_eq!(commit.commit_type, "feat");
         assert_eq!(commit.bump_type(), BumpType::Minor);
+
+        let commit = ConventionalCommit::parse_with("bugfix: handle nulls", &options).unwrap();
+        assert_eq!(commit.bump_type(), BumpType::Patch);
     }
 
     #[test]
@@ -136,7 +411,7 @@ mod tests {
     fn test_breaking_change_with_exclamation() {
         let commit = ConventionalCommit::parse("feat!: remove deprecated API").unwrap();
         assert_eq!(commit.commit_type, "feat");
-        assert_eq!(commit.breaking_change, true);
+        assert!(commit.breaking_change);
         assert_eq!(commit.bump_type(), BumpType::Major);
     }
 
@@ -145,7 +420,7 @@ mod tests {
         let commit = ConventionalCommit::parse("feat(api)!: remove old endpoint").unwrap();
         assert_eq!(commit.commit_type, "feat");
         assert_eq!(commit.scope, Some("api".to_string()));
-        assert_eq!(commit.breaking_change, true);
+        assert!(commit.breaking_change);
         assert_eq!(commit.bump_type(), BumpType::Major);
     }
 
@@ -163,10 +438,248 @@ BREAKING CHANGE: removes basic auth support";
         assert_eq!(commit.scope, Some("api".to_string()));
         assert!(commit.body.is_some());
         assert!(commit.footer.is_some());
-        assert_eq!(commit.breaking_change, true);
+        assert!(commit.breaking_change);
+        assert_eq!(
+            commit.breaking_descriptions,
+            vec!["removes basic auth support".to_string()]
+        );
         assert_eq!(commit.bump_type(), BumpType::Major);
     }
 
+    #[test]
+    fn test_breaking_token_in_body_lenient() {
+        let message = "fix: adjust parser
+
+The old behaviour is gone. BREAKING CHANGE: inputs are now trimmed.
+More explanation follows here.";
+
+        let commit = ConventionalCommit::parse_with(message, &ParseOptions::default()).unwrap();
+        assert!(commit.breaking_change);
+        assert_eq!(commit.bump_type(), BumpType::Major);
+    }
+
+    #[test]
+    fn test_breaking_token_in_body_strict() {
+        let message = "fix: adjust parser
+
+BREAKING CHANGE: mentioned in the body paragraph
+More explanation follows here.
+
+Reviewed-by: Someone";
+
+        let options = ParseOptions {
+            scan_body_for_breaking: false,
+            ..ParseOptions::default()
+        };
+        let commit = ConventionalCommit::parse_with(message, &options).unwrap();
+        assert!(!commit.breaking_change);
+        assert_eq!(commit.bump_type(), BumpType::Patch);
+    }
+
+    #[test]
+    fn test_breaking_footer_strict() {
+        let message = "fix: adjust parser
+
+Some body text.
+
+BREAKING CHANGE: inputs are now trimmed";
+
+        let options = ParseOptions {
+            scan_body_for_breaking: false,
+            ..ParseOptions::default()
+        };
+        let commit = ConventionalCommit::parse_with(message, &options).unwrap();
+        assert!(commit.breaking_change);
+        assert_eq!(
+            commit.breaking_descriptions,
+            vec!["inputs are now trimmed".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_multiple_breaking_footers() {
+        let message = "feat(api): overhaul endpoints
+
+Reworks the API surface.
+
+BREAKING CHANGE: /v1/users is removed
+BREAKING CHANGE: tokens expire after one hour";
+
+        let commit = ConventionalCommit::parse(message).unwrap();
+        assert_eq!(
+            commit.breaking_descriptions,
+            vec![
+                "/v1/users is removed".to_string(),
+                "tokens expire after one hour".to_string()
+            ]
+        );
+    }
+
+    #[test]
+    fn test_hyphenated_breaking_footer() {
+        let message = "refactor: load settings lazily
+
+BREAKING-CHANGE: drop old config format";
+
+        let commit = ConventionalCommit::parse(message).unwrap();
+        assert!(commit.breaking_change);
+        assert_eq!(
+            commit.breaking_descriptions,
+            vec!["drop old config format".to_string()]
+        );
+        assert_eq!(commit.bump_type(), BumpType::Major);
+
+        let options = ParseOptions {
+            scan_body_for_breaking: false,
+            ..ParseOptions::default()
+        };
+        let commit = ConventionalCommit::parse_with(message, &options).unwrap();
+        assert_eq!(commit.bump_type(), BumpType::Major);
+
+        // The token is case-sensitive
+        let commit = ConventionalCommit::parse("fix: tweak\n\nbreaking-change: nope").unwrap();
+        assert_eq!(commit.bump_type(), BumpType::Patch);
+    }
+
+    #[test]
+    fn test_configured_commit_types() {
+        let mut config = Config::default();
+        config
+            .commit_types
+            .insert("improvement".to_string(), "minor".to_string());
+        config
+            .commit_types
+            .insert("feat".to_string(), "patch".to_string());
+        let options = ParseOptions::from_config(&config);
+
+        let commit =
+            ConventionalCommit::parse_with("improvement: faster startup", &options).unwrap();
+        assert_eq!(commit.bump_type_with(&options), BumpType::Minor);
+        assert!(options.is_known_type("improvement"));
+
+        let commit = ConventionalCommit::parse_with("feat: small thing", &options).unwrap();
+        assert_eq!(commit.bump_type_with(&options), BumpType::Patch);
+
+        let commit =
+            ConventionalCommit::parse_with("improvement!: new defaults", &options).unwrap();
+        assert_eq!(commit.bump_type_with(&options), BumpType::Major);
+
+        // Types missing from the table keep the built-in mapping
+        let commit = ConventionalCommit::parse_with("fix: typo", &options).unwrap();
+        assert_eq!(commit.bump_type_with(&options), BumpType::Patch);
+    }
+
+    #[test]
+    fn test_type_with_digits() {
+        let commit = ConventionalCommit::parse("build2(deps): bump serde").unwrap();
+        assert_eq!(commit.commit_type, "build2");
+        assert_eq!(commit.scope, Some("deps".to_string()));
+
+        assert!(ConventionalCommit::parse("fe@t: nope").is_err());
+    }
+
+    #[test]
+    fn test_trailer_with_digits_is_a_footer() {
+        let message = "feat: add build metadata
+
+Records where a build came from.
+Format of the id: numeric.
+
+X-Build-Id: 42
+Reviewed-by: Someone";
+
+        let commit = ConventionalCommit::parse(message).unwrap();
+        assert_eq!(
+            commit.body.as_deref(),
+            Some("Records where a build came from.\nFormat of the id: numeric.")
+        );
+        assert_eq!(
+            commit.footer.as_deref(),
+            Some("X-Build-Id: 42\nReviewed-by: Someone")
+        );
+    }
+
+    #[test]
+    fn test_structured_footers() {
+        let message = "fix(auth): expire sessions
+
+Sessions now expire after an hour of inactivity.
+
+Closes #42
+Reviewed-by: Someone
+BREAKING CHANGE: sessions are no longer permanent
+Refs: #7";
+
+        let commit = ConventionalCommit::parse(message).unwrap();
+        let pairs = |pairs: &[(&str, &str)]| -> Vec<(String, String)> {
+            pairs
+                .iter()
+                .map(|(key, value)| (key.to_string(), value.to_string()))
+                .collect()
+        };
+        assert_eq!(
+            commit.footers,
+            pairs(&[
+                ("Closes", "#42"),
+                ("Reviewed-by", "Someone"),
+                ("BREAKING CHANGE", "sessions are no longer permanent"),
+                ("Refs", "#7"),
+            ])
+        );
+        assert!(commit.breaking_change);
+        assert_eq!(
+            commit.footer.as_deref(),
+            Some(
+                "Closes #42\nReviewed-by: Someone\nBREAKING CHANGE: sessions are no longer permanent\nRefs: #7"
+            )
+        );
+    }
+
+    #[test]
+    fn test_footer_value_continues_on_following_lines() {
+        let message = "feat: new config loader
+
+BREAKING CHANGE: the `paths` key is gone,
+use `files` instead";
+
+        let commit = ConventionalCommit::parse(message).unwrap();
+        assert_eq!(
+            commit.footers,
+            vec![(
+                "BREAKING CHANGE".to_string(),
+                "the `paths` key is gone,\nuse `files` instead".to_string()
+            )]
+        );
+        assert!(ConventionalCommit::parse("chore: no footers")
+            .unwrap()
+            .footers
+            .is_empty());
+    }
+
+    #[test]
+    fn test_referenced_issues() {
+        let message = "fix(api): validate input
+
+Fixes #45 and #12, see #99 for background.
+closes #7
+
+Refs: #9, #45
+RESOLVED #3";
+
+        let commit = ConventionalCommit::parse(message).unwrap();
+        assert_eq!(commit.referenced_issues(), vec![3, 7, 9, 12, 45]);
+    }
+
+    #[test]
+    fn test_referenced_issues_requires_keyword() {
+        let message = "feat: add prefixes
+
+Prefixes look like #123 and suffixes like x#4. Prefix #5.";
+
+        let commit = ConventionalCommit::parse(message).unwrap();
+        assert!(commit.referenced_issues().is_empty());
+    }
+
     #[test]
     fn test_invalid_format() {
         let result = ConventionalCommit::parse("invalid message format");
@@ -0,0 +1,214 @@
+use crate::bump_type::BumpType;
+use crate::changelog::prepend_section;
+use crate::config::Config;
+use crate::error::ReleaseError;
+use crate::file_updater::updated_file_content;
+use semver::Version;
+use similar::TextDiff;
+use std::path::Path;
+
+/// A file the release would change, with the unified diff of the change
+#[derive(Debug, Clone, PartialEq)]
+pub struct FileChange {
+    pub path: String,
+    pub diff: String,
+}
+
+/// Everything a release would do, worked out without touching the repository
+#[derive(Debug, Clone)]
+pub struct ReleasePlan {
+    pub current_version: Version,
+    pub new_version: Version,
+    pub bump: BumpType,
+    pub tag: String,
+    /// `None` when GitHub generates the notes (`auto_notes`)
+    pub release_notes: Option<String>,
+    pub file_changes: Vec<FileChange>,
+}
+
+impl ReleasePlan {
+    /// Human-readable summary for the dry-run log
+    pub fn render(&self) -> String {
+        let mut plan = format!(
+            "Version: {} → {} ({:?} bump), tagged {}\n",
+            self.current_version, self.new_version, self.bump, self.tag
+        );
+
+        plan.push_str("\nRelease notes:\n");
+        match &self.release_notes {
+            Some(notes) if !notes.trim().is_empty() => plan.push_str(notes.trim_end()),
+            Some(_) => plan.push_str("(empty)"),
+            None => plan.push_str("(generated by GitHub from the merged pull requests)"),
+        }
+        plan.push('\n');
+
+        if self.file_changes.is_empty() {
+            plan.push_str("\nNo files would change\n");
+        }
+        for change in &self.file_changes {
+            plan.push_str(&format!("\nChanges to {}:\n{}", change.path, change.diff));
+        }
+        plan
+    }
+}
+
+/// Diff the configured version files, the changelog and the config file at `config_path` (when
+/// `update_config_version` is set) as releasing `version` would leave them, computed in
+/// memory. `run_after` hooks are not run.
+pub fn preview_file_changes(
+    base: &Path,
+    config: &Config,
+    config_path: Option<&Path>,
+    version: &Version,
+    changelog_section: Option<&str>,
+) -> std::result::Result<Vec<FileChange>, ReleaseError> {
+    let mut changes = Vec::new();
+
+    for file_config in config.version.files.as_deref().unwrap_or_default() {
+        if let Some((content, updated)) = updated_file_content(base, file_config, version)? {
+            changes.extend(file_change(&file_config.path, &content, &updated));
+        }
+    }
+
+    if let Some(section) = changelog_section {
+        let path = config.changelog.path();
+        let existing = std::fs::read_to_string(base.join(path)).unwrap_or_default();
+        changes.extend(file_change(
+            path,
+            &existing,
+            &prepend_section(&existing, section),
+        ));
+    }
+
+    if config.version.update_config_version {
+        if let Some(config_path) = config_path.filter(|path| path.exists()) {
+            let content = std::fs::read_to_string(config_path).map_err(|e| {
+                ReleaseError::Config(format!(
+                    "Failed to read config file {:?}: {}",
+                    config_path, e
+                ))
+            })?;
+            let updated = Config::with_recorded_version(config_path, &content, version)?;
+            // Shown relative to the repository when it lives inside it
+            let label = base
+                .canonicalize()
+                .ok()
+                .and_then(|base| config_path.strip_prefix(base).ok().map(Path::to_path_buf))
+                .unwrap_or_else(|| config_path.to_path_buf());
+            changes.extend(file_change(&label.to_string_lossy(), &content, &updated));
+        }
+    }
+
+    Ok(changes)
+}
+
+fn file_change(path: &str, before: &str, after: &str) -> Option<FileChange> {
+    (before != after).then(|| FileChange {
+        path: path.to_string(),
+        diff: TextDiff::from_lines(before, after)
+            .unified_diff()
+            .header(&format!("a/{}", path), &format!("b/{}", path))
+            .to_string(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::FileUpdateConfig;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_plan_shows_bump_notes_and_diffs_without_writing() {
+        let temp_dir = TempDir::new().unwrap();
+        std::fs::write(temp_dir.path().join("VERSION"), "1.2.0\n").unwrap();
+
+        let mut config = Config::default();
+        config.version.files = Some(vec![FileUpdateConfig {
+            path: "VERSION".to_string(),
+            marker: "1.2.0".to_string(),
+            template: None,
+            run_after: None,
+            regex: false,
+            json_pointer: None,
+            require_match: false,
+        }]);
+
+        let version = Version::parse("1.3.0").unwrap();
+        let plan = ReleasePlan {
+            current_version: Version::parse("1.2.0").unwrap(),
+            new_version: version.clone(),
+            bump: BumpType::Minor,
+            tag: "v1.3.0".to_string(),
+            release_notes: Some("## Features\n\n- add exports\n".to_string()),
+            file_changes: preview_file_changes(
+                temp_dir.path(),
+                &config,
+                None,
+                &version,
+                Some("## [1.3.0] - 2026-10-16\n"),
+            )
+            .unwrap(),
+        };
+        let rendered = plan.render();
+
+        assert!(
+            rendered.contains("1.2.0 → 1.3.0 (Minor bump)"),
+            "{}",
+            rendered
+        );
+        assert!(
+            rendered.contains("## Features\n\n- add exports"),
+            "{}",
+            rendered
+        );
+        assert!(
+            rendered.contains("--- a/VERSION\n+++ b/VERSION\n"),
+            "{}",
+            rendered
+        );
+        assert!(rendered.contains("-1.2.0\n+1.3.0\n"), "{}", rendered);
+        assert!(
+            rendered.contains("+## [1.3.0] - 2026-10-16"),
+            "{}",
+            rendered
+        );
+
+        // Nothing was written
+        assert_eq!(
+            std::fs::read_to_string(temp_dir.path().join("VERSION")).unwrap(),
+            "1.2.0\n"
+        );
+        assert!(!temp_dir.path().join(config.changelog.path()).exists());
+    }
+
+    #[test]
+    fn test_preview_shows_recorded_config_version() {
+        let temp_dir = TempDir::new().unwrap();
+        let config_path = temp_dir.path().join(".release-config.toml");
+        let content = "[version]\ncurrent = \"1.2.0\"\nupdate_config_version = true\n";
+        std::fs::write(&config_path, content).unwrap();
+
+        let mut config = Config::default();
+        config.version.update_config_version = true;
+        let changes = preview_file_changes(
+            temp_dir.path(),
+            &config,
+            Some(&config_path.canonicalize().unwrap()),
+            &Version::parse("1.3.0").unwrap(),
+            None,
+        )
+        .unwrap();
+
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].path, ".release-config.toml");
+        assert!(
+            changes[0]
+                .diff
+                .contains("-current = \"1.2.0\"\n+current = \"1.3.0\"\n"),
+            "{}",
+            changes[0].diff
+        );
+        assert_eq!(std::fs::read_to_string(&config_path).unwrap(), content);
+    }
+}
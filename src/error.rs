@@ -0,0 +1,83 @@
+use thiserror::Error;
+
+/// Why a run failed
+#[derive(Debug, Error)]
+pub enum ReleaseError {
+    /// The configuration file can't be read, parsed or is semantically invalid
+    #[error("{0}")]
+    Config(String),
+    /// A git operation on the working repository or a remote failed
+    #[error("{0}")]
+    Git(String),
+    /// The GitHub API answered with a non-success status
+    #[error("GitHub API error {status}: {body}")]
+    GitHubApi { status: u16, body: String },
+    /// A pull request title or commit message doesn't follow the conventions
+    #[error("{0}")]
+    Validation(String),
+    /// A version string isn't valid semver or can't be used for the release
+    #[error("{0}")]
+    InvalidVersion(String),
+    /// Any other failure: I/O, network, asset uploads, ...
+    #[error("{0}")]
+    Other(String),
+}
+
+pub type Result<T> = std::result::Result<T, ReleaseError>;
+
+impl ReleaseError {
+    /// The process exit code for this error: 2 for bad configuration or input, else 1
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            ReleaseError::Config(_) | ReleaseError::InvalidVersion(_) => 2,
+            _ => 1,
+        }
+    }
+}
+
+impl From<String> for ReleaseError {
+    fn from(message: String) -> Self {
+        ReleaseError::Other(message)
+    }
+}
+
+impl From<&str> for ReleaseError {
+    fn from(message: &str) -> Self {
+        ReleaseError::Other(message.to_string())
+    }
+}
+
+impl From<git2::Error> for ReleaseError {
+    fn from(error: git2::Error) -> Self {
+        ReleaseError::Git(error.message().to_string())
+    }
+}
+
+impl From<std::io::Error> for ReleaseError {
+    fn from(error: std::io::Error) -> Self {
+        ReleaseError::Other(error.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_exit_codes() {
+        assert_eq!(ReleaseError::Config("bad".to_string()).exit_code(), 2);
+        assert_eq!(
+            ReleaseError::InvalidVersion("bad".to_string()).exit_code(),
+            2
+        );
+        assert_eq!(ReleaseError::Git("bad".to_string()).exit_code(), 1);
+        assert_eq!(
+            ReleaseError::GitHubApi {
+                status: 404,
+                body: "Not Found".to_string()
+            }
+            .exit_code(),
+            1
+        );
+    }
+}
@@ -1,37 +1,508 @@
-use crate::config::FileUpdateConfig;
+use crate::config::{FileUpdateConfig, DEFAULT_VERSION_MARKER};
+use crate::error::ReleaseError;
+use log::{debug, info, warn};
+use regex::Regex;
 use semver::Version;
 use std::path::Path;
+use std::process::Command;
+
+/// Update `files` (relative to `base`) in their declared order, running each file's
+/// `run_after` hook in `base` right after it
+pub fn update_files(
+    base: &Path,
+    files: &[FileUpdateConfig],
+    version: &Version,
+) -> std::result::Result<(), ReleaseError> {
+    for file_config in files {
+        update_file_version(base, file_config, version)?;
+
+        if let Some(command) = &file_config.run_after {
+            run_hook(base, command, version)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Fail with the list of configured files that don't exist under `base`
+pub fn check_files_exist(
+    files: &[FileUpdateConfig],
+    base: &Path,
+) -> std::result::Result<(), ReleaseError> {
+    let missing: Vec<&str> = files
+        .iter()
+        .filter(|file_config| !base.join(&file_config.path).exists())
+        .map(|file_config| file_config.path.as_str())
+        .collect();
+
+    if !missing.is_empty() {
+        return Err(format!("Configured version files not found: {}", missing.join(", ")).into());
+    }
+
+    Ok(())
+}
+
+fn run_hook(
+    base: &Path,
+    command: &str,
+    version: &Version,
+) -> std::result::Result<(), ReleaseError> {
+    info!("🪝 Running: {}", command);
+    let status = Command::new("sh")
+        .arg("-c")
+        .arg(command)
+        .current_dir(base)
+        .env("RELEASE_VERSION", version.to_string())
+        .status()
+        .map_err(|e| format!("Failed to run {:?}: {}", command, e))?;
+
+    if !status.success() {
+        return Err(format!("Command {:?} failed with {}", command, status).into());
+    }
+
+    Ok(())
+}
 
 pub fn update_file_version(
+    base: &Path,
+    file_config: &FileUpdateConfig,
+    version: &Version,
+) -> std::result::Result<(), ReleaseError> {
+    let Some((content, updated_content)) = updated_file_content(base, file_config, version)? else {
+        return Ok(());
+    };
+
+    // Only write if content actually changed
+    if content != updated_content {
+        std::fs::write(base.join(&file_config.path), updated_content)?;
+        info!("📝 Updated {} version to {}", file_config.path, version);
+    } else {
+        debug!("⚠️  No changes needed for {}", file_config.path);
+    }
+
+    Ok(())
+}
+
+/// The current and the updated content of a version file, without writing it.
+///
+/// `None` when the file doesn't exist or its marker matches nothing (after warning about it).
+pub fn updated_file_content(
+    base: &Path,
     file_config: &FileUpdateConfig,
     version: &Version,
-) -> std::result::Result<(), Box<dyn std::error::Error>> {
-    let path = Path::new(&file_config.path);
+) -> std::result::Result<Option<(String, String)>, ReleaseError> {
+    let path = base.join(&file_config.path);
 
     if !path.exists() {
-        println!("⚠️  File {} does not exist, skipping", file_config.path);
-        return Ok(());
+        warn!("⚠️  File {} does not exist, skipping", file_config.path);
+        return Ok(None);
     }
 
-    let content = std::fs::read_to_string(path)?;
+    let content = std::fs::read_to_string(&path)?;
 
-    let updated_content = {
+    let (updated_content, matched) = {
         let replacement = if let Some(template) = &file_config.template {
-            template.replace("{version}", &version.to_string())
+            let date = chrono::Utc::now().format("%Y-%m-%d").to_string();
+            render_template(template, version, &date)
         } else {
             version.to_string()
         };
 
-        content.replace(&file_config.marker, &replacement)
+        if let Some(pointer) = &file_config.json_pointer {
+            let updated = update_json_pointer(&content, pointer, &replacement)
+                .map_err(|e| format!("Failed to update {}: {}", file_config.path, e))?;
+            (updated, true)
+        } else if file_config.regex {
+            let pattern = Regex::new(&file_config.marker)
+                .map_err(|e| format!("Invalid marker regex for {}: {}", file_config.path, e))?;
+            (
+                replace_captured(&content, &pattern, &replacement),
+                pattern.is_match(&content),
+            )
+        } else {
+            (
+                content.replace(&file_config.marker, &replacement),
+                content.contains(&file_config.marker),
+            )
+        }
     };
 
-    // Only write if content actually changed
-    if content != updated_content {
-        std::fs::write(path, updated_content)?;
-        println!("📝 Updated {} version to {}", file_config.path, version);
-    } else {
-        println!("⚠️  No changes needed for {}", file_config.path);
+    if !matched {
+        let message = default_marker_warning(file_config, &content).unwrap_or_else(|| {
+            format!(
+                "{} does not contain the marker {:?}, so no version was written to it",
+                file_config.path, file_config.marker
+            )
+        });
+        if file_config.require_match {
+            return Err(message.into());
+        }
+        warn!("::warning title=Version marker not found::{}", message);
+        return Ok(None);
     }
 
-    Ok(())
+    Ok(Some((content, updated_content)))
+}
+
+/// Fill a file template's `{version}`, `{major}`, `{minor}`, `{patch}`, `{prerelease}` and
+/// `{date}` placeholders
+pub fn render_template(template: &str, version: &Version, date: &str) -> String {
+    template
+        .replace("{version}", &version.to_string())
+        .replace("{major}", &version.major.to_string())
+        .replace("{minor}", &version.minor.to_string())
+        .replace("{patch}", &version.patch.to_string())
+        .replace("{prerelease}", version.pre.as_str())
+        .replace("{date}", date)
+}
+
+/// Set the string at `pointer` (e.g. `/version`) in a JSON document, keeping key order,
+/// the indentation of the original and its trailing newline
+fn update_json_pointer(
+    content: &str,
+    pointer: &str,
+    replacement: &str,
+) -> std::result::Result<String, Box<dyn std::error::Error>> {
+    let mut document: serde_json::Value = serde_json::from_str(content)?;
+    let value = document
+        .pointer_mut(pointer)
+        .ok_or_else(|| format!("JSON pointer {:?} does not exist", pointer))?;
+    *value = serde_json::Value::String(replacement.to_string());
+
+    let indent = content
+        .lines()
+        .skip(1)
+        .find(|line| !line.trim().is_empty())
+        .map(|line| &line[..line.len() - line.trim_start().len()])
+        .filter(|indent| !indent.is_empty())
+        .unwrap_or("  ");
+    let mut output = Vec::new();
+    let formatter = serde_json::ser::PrettyFormatter::with_indent(indent.as_bytes());
+    let mut serializer = serde_json::Serializer::with_formatter(&mut output, formatter);
+    serde::Serialize::serialize(&document, &mut serializer)?;
+
+    let mut updated = String::from_utf8(output)?;
+    if content.ends_with('\n') {
+        updated.push('\n');
+    }
+    Ok(updated)
+}
+
+/// Replace only the first capture group of every match of `pattern`, keeping the rest of
+/// the match (e.g. `version = "` and `"`) as is
+fn replace_captured(content: &str, pattern: &Regex, replacement: &str) -> String {
+    pattern
+        .replace_all(content, |captures: &regex::Captures| {
+            let whole = captures.get(0).expect("group 0 is the whole match");
+            match captures.get(1) {
+                Some(version) => format!(
+                    "{}{}{}",
+                    &content[whole.start()..version.start()],
+                    replacement,
+                    &content[version.end()..whole.end()]
+                ),
+                None => whole.as_str().to_string(),
+            }
+        })
+        .into_owned()
+}
+
+/// Warn when the out-of-the-box `Cargo.toml` marker is still configured but absent from the file.
+///
+/// That almost always means `[[version.files]]` was never set up, and the release would
+/// otherwise go out without any version being written.
+pub fn default_marker_warning(file_config: &FileUpdateConfig, content: &str) -> Option<String> {
+    let is_default = Path::new(&file_config.path).file_name() == Some("Cargo.toml".as_ref())
+        && file_config.marker == DEFAULT_VERSION_MARKER
+        && file_config.template.is_none();
+
+    if !is_default || content.contains(DEFAULT_VERSION_MARKER) {
+        return None;
+    }
+
+    Some(format!(
+        "{} does not contain the default marker {:?}, so no version was written to it. \
+         Set `version = \"{}\"` in {} or configure [[version.files]] for your project.",
+        file_config.path, DEFAULT_VERSION_MARKER, DEFAULT_VERSION_MARKER, file_config.path
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_update_files_runs_hooks_in_declared_order() {
+        let temp_dir = TempDir::new().expect("Failed to create temp directory");
+        let first = temp_dir.path().join("Cargo.toml");
+        let second = temp_dir.path().join("Cargo.lock");
+        let log = temp_dir.path().join("hooks.log");
+        std::fs::write(&first, "version = \"0.0.0+local\"").unwrap();
+        std::fs::write(&second, "version = \"0.0.0+local\"").unwrap();
+
+        // Each hook records the state of both files at the moment it runs
+        let snapshot = format!(
+            "cat {} {} >> {} && echo >> {}",
+            first.display(),
+            second.display(),
+            log.display(),
+            log.display()
+        );
+        let files = vec![
+            FileUpdateConfig {
+                path: first.to_string_lossy().to_string(),
+                marker: "0.0.0+local".to_string(),
+                template: None,
+                run_after: Some(snapshot.clone()),
+                regex: false,
+                json_pointer: None,
+                require_match: false,
+            },
+            FileUpdateConfig {
+                path: second.to_string_lossy().to_string(),
+                marker: "0.0.0+local".to_string(),
+                template: None,
+                run_after: Some(snapshot),
+                regex: false,
+                json_pointer: None,
+                require_match: false,
+            },
+        ];
+
+        update_files(Path::new("."), &files, &Version::parse("1.2.3").unwrap()).unwrap();
+
+        let log = std::fs::read_to_string(&log).unwrap();
+        assert_eq!(
+            log,
+            "version = \"1.2.3\"version = \"0.0.0+local\"\n\
+             version = \"1.2.3\"version = \"1.2.3\"\n"
+        );
+    }
+
+    #[test]
+    fn test_default_marker_warning_with_default_config() {
+        let temp_dir = TempDir::new().expect("Failed to create temp directory");
+        let path = temp_dir.path().join("Cargo.toml");
+        std::fs::write(&path, "[package]\nname = \"app\"\nversion = \"1.4.2\"\n").unwrap();
+
+        let mut file_config = crate::config::Config::default().version.files.unwrap()[0].clone();
+        file_config.path = path.to_string_lossy().to_string();
+        let content = std::fs::read_to_string(&path).unwrap();
+
+        let warning = default_marker_warning(&file_config, &content).unwrap();
+        assert!(warning.contains("0.0.0+local"));
+        assert!(warning.contains("[[version.files]]"));
+
+        // Once the marker is in place there is nothing to warn about
+        assert!(default_marker_warning(&file_config, "version = \"0.0.0+local\"").is_none());
+    }
+
+    #[test]
+    fn test_default_marker_warning_ignores_custom_markers() {
+        let file_config = FileUpdateConfig {
+            path: "Cargo.toml".to_string(),
+            marker: "0.0.0-dev".to_string(),
+            template: None,
+            run_after: None,
+            regex: false,
+            json_pointer: None,
+            require_match: false,
+        };
+        assert!(default_marker_warning(&file_config, "version = \"1.0.0\"").is_none());
+    }
+
+    #[test]
+    fn test_check_files_exist_lists_missing_files() {
+        let temp_dir = TempDir::new().expect("Failed to create temp directory");
+        std::fs::write(temp_dir.path().join("Cargo.toml"), "").unwrap();
+        let file = |path: &str| FileUpdateConfig {
+            path: path.to_string(),
+            marker: "0.0.0+local".to_string(),
+            template: None,
+            run_after: None,
+            regex: false,
+            json_pointer: None,
+            require_match: false,
+        };
+
+        assert!(check_files_exist(&[file("Cargo.toml")], temp_dir.path()).is_ok());
+
+        let error = check_files_exist(
+            &[file("Cargo.toml"), file("package.json"), file("action.yml")],
+            temp_dir.path(),
+        )
+        .unwrap_err()
+        .to_string();
+        assert_eq!(
+            error,
+            "Configured version files not found: package.json, action.yml"
+        );
+    }
+
+    #[test]
+    fn test_update_files_fails_on_hook_error() {
+        let temp_dir = TempDir::new().expect("Failed to create temp directory");
+        let path = temp_dir.path().join("version.txt");
+        std::fs::write(&path, "0.0.0+local").unwrap();
+        let files = vec![FileUpdateConfig {
+            path: path.to_string_lossy().to_string(),
+            marker: "0.0.0+local".to_string(),
+            template: None,
+            run_after: Some("exit 3".to_string()),
+            regex: false,
+            json_pointer: None,
+            require_match: false,
+        }];
+
+        let result = update_files(Path::new("."), &files, &Version::parse("1.2.3").unwrap());
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_regex_marker_replaces_captured_version() {
+        let temp_dir = TempDir::new().expect("Failed to create temp directory");
+        let path = temp_dir.path().join("Cargo.toml");
+        std::fs::write(
+            &path,
+            "[package]\nname = \"app\"\nversion = \"1.2.3\"\n\n[dependencies]\nserde = { version = \"1.0\" }\n",
+        )
+        .unwrap();
+        let file_config = FileUpdateConfig {
+            path: path.to_string_lossy().to_string(),
+            marker: r#"(?m)^version = "([^"]+)""#.to_string(),
+            template: None,
+            run_after: None,
+            regex: true,
+            json_pointer: None,
+            require_match: false,
+        };
+
+        update_file_version(
+            Path::new("."),
+            &file_config,
+            &Version::parse("1.3.0").unwrap(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            std::fs::read_to_string(&path).unwrap(),
+            "[package]\nname = \"app\"\nversion = \"1.3.0\"\n\n[dependencies]\nserde = { version = \"1.0\" }\n"
+        );
+    }
+
+    #[test]
+    fn test_render_template_placeholders() {
+        let version = Version::parse("2.5.1-rc.2").unwrap();
+
+        assert_eq!(
+            render_template("v{major}.{minor}", &version, "2024-05-01"),
+            "v2.5"
+        );
+        assert_eq!(
+            render_template("{version} ({date})", &version, "2024-05-01"),
+            "2.5.1-rc.2 (2024-05-01)"
+        );
+        assert_eq!(
+            render_template("{patch}/{prerelease}", &version, "2024-05-01"),
+            "1/rc.2"
+        );
+    }
+
+    fn json_file(path: &std::path::Path, pointer: &str) -> FileUpdateConfig {
+        FileUpdateConfig {
+            path: path.to_string_lossy().to_string(),
+            marker: String::new(),
+            template: None,
+            run_after: None,
+            regex: false,
+            json_pointer: Some(pointer.to_string()),
+            require_match: false,
+        }
+    }
+
+    #[test]
+    fn test_json_pointer_updates_version_field() {
+        let temp_dir = TempDir::new().expect("Failed to create temp directory");
+        let path = temp_dir.path().join("package.json");
+        std::fs::write(
+            &path,
+            "{\n  \"name\": \"app\",\n  \"version\": \"1.0.0\",\n  \"dependencies\": {\n    \"left-pad\": \"1.0.0\"\n  }\n}\n",
+        )
+        .unwrap();
+
+        update_file_version(
+            Path::new("."),
+            &json_file(&path, "/version"),
+            &Version::parse("1.1.0").unwrap(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            std::fs::read_to_string(&path).unwrap(),
+            "{\n  \"name\": \"app\",\n  \"version\": \"1.1.0\",\n  \"dependencies\": {\n    \"left-pad\": \"1.0.0\"\n  }\n}\n"
+        );
+    }
+
+    #[test]
+    fn test_json_pointer_updates_nested_field() {
+        let temp_dir = TempDir::new().expect("Failed to create temp directory");
+        let path = temp_dir.path().join("manifest.json");
+        std::fs::write(
+            &path,
+            "{\n    \"packages\": {\n        \"\": {\n            \"version\": \"1.0.0\"\n        }\n    }\n}",
+        )
+        .unwrap();
+
+        update_file_version(
+            Path::new("."),
+            &json_file(&path, "/packages//version"),
+            &Version::parse("2.0.0").unwrap(),
+        )
+        .unwrap();
+        assert_eq!(
+            std::fs::read_to_string(&path).unwrap(),
+            "{\n    \"packages\": {\n        \"\": {\n            \"version\": \"2.0.0\"\n        }\n    }\n}"
+        );
+
+        let error = update_file_version(
+            Path::new("."),
+            &json_file(&path, "/missing"),
+            &Version::parse("2.0.0").unwrap(),
+        )
+        .unwrap_err()
+        .to_string();
+        assert!(error.contains("/missing"), "Unexpected error: {}", error);
+    }
+
+    #[test]
+    fn test_missing_marker_warns_or_fails() {
+        let temp_dir = TempDir::new().expect("Failed to create temp directory");
+        let path = temp_dir.path().join("VERSION");
+        std::fs::write(&path, "1.0.0\n").unwrap();
+        let mut file_config = FileUpdateConfig {
+            path: path.to_string_lossy().to_string(),
+            marker: "0.0.0-dev".to_string(),
+            template: None,
+            run_after: None,
+            regex: false,
+            json_pointer: None,
+            require_match: false,
+        };
+        let version = Version::parse("1.1.0").unwrap();
+
+        // By default a missing marker only warns
+        update_file_version(Path::new("."), &file_config, &version).unwrap();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "1.0.0\n");
+
+        file_config.require_match = true;
+        let error = update_file_version(Path::new("."), &file_config, &version)
+            .unwrap_err()
+            .to_string();
+        assert!(error.contains("0.0.0-dev"), "Unexpected error: {}", error);
+
+        file_config.marker = r"^(\d+\.\d+\.\d+)$".to_string();
+        file_config.regex = true;
+        assert!(update_file_version(Path::new("."), &file_config, &version).is_err());
+    }
 }
@@ -1,145 +1,859 @@
+use log::{info, warn};
+use std::borrow::Cow;
 use std::env;
+use std::path::Path;
+use std::time::Instant;
 
+pub mod assets;
 pub mod bump_type;
+pub mod changelog;
 pub mod cli;
 pub mod commit;
 pub mod commit_analyzer;
 pub mod config;
+pub mod contributors;
 pub mod conventional_commit;
+pub mod dry_run;
+pub mod error;
 pub mod file_updater;
+pub mod logging;
 pub mod output;
+pub mod policy;
 pub mod release;
+pub mod release_notes;
 pub mod scm;
+pub mod self_test;
+pub mod token;
 pub mod validation;
 pub mod version_manager;
 
+use crate::assets::{upload_release_assets, ReleaseAssets};
+use crate::changelog::render_changelog_section;
 use crate::cli::Args;
-use crate::commit_analyzer::get_impact_from_latest_commit;
-use crate::config::Config;
-use crate::output::ActionOutput;
-use crate::release::{create_release_commit, delete_remote_branch, push_commit_to_remote};
+use crate::commit::Commit;
+use crate::commit_analyzer::{
+    commits_touching_path, count_releasable_commits, explain_bump, explain_result,
+    get_commits_since_previous_release, get_impact_from_message_file, get_impact_from_pull_request,
+    get_last_commit, has_skip_release_marker, highest_bump, last_stable_release_tag,
+    release_as_version, resolve_since_tag, split_skip_release_commits,
+};
+use crate::config::{Config, PackageConfig, TagSource};
+use crate::contributors::collect_contributors;
+use crate::dry_run::{preview_file_changes, ReleasePlan};
+use crate::error::ReleaseError;
+use crate::file_updater::check_files_exist;
+use crate::output::{release_timing, ActionOutput, NoReleaseReason, Outcome, PackageRelease};
+use crate::policy::fetch_commit_policy;
+use crate::release::{
+    create_release_commit, delete_remote_branch, fetch_tags, find_previous_release_commit,
+    push_commit_to_remote, restore_head,
+};
+use crate::release_notes::{
+    build_first_release_notes, build_release_notes, resolve_pull_request_links, PullRequestLinks,
+    ReleaseLinks,
+};
+use crate::scm::git::{
+    ensure_git_repository, open_repository, repository_from_remote, shallow_clone_warning,
+    trust_directory, SafeDirectoryScope,
+};
 use crate::scm::github::GitHubClient;
+use crate::scm::ScmClient;
+use crate::self_test::run_self_test;
 use crate::validation::{should_validate_pr, validate_pr_title};
 use crate::version_manager::VersionManager;
 
 pub struct ReleaseApplication {
     config: Config,
     args: Args,
+    scm_client: Option<Box<dyn ScmClient>>,
 }
 
 impl ReleaseApplication {
-    pub fn new(args: Args, config: Config) -> Self {
-        Self { config, args }
+    pub fn new(args: Args, mut config: Config) -> Self {
+        // `--remote` wins over the config file
+        if let Some(remote) = &args.remote {
+            config.release.remote = Some(remote.clone());
+        }
+        Self {
+            config,
+            args,
+            scm_client: None,
+        }
     }
 
-    pub async fn run(&self) -> std::result::Result<ActionOutput, Box<dyn std::error::Error>> {
-        // Change to working directory
-        env::set_current_dir(&self.args.working_directory).map_err(|e| {
-            format!(
-                "Failed to change to working directory {:?}: {}",
-                self.args.working_directory, e
+    /// The GitHub token from `--token-file` / `GITHUB_TOKEN_FILE`, else `GITHUB_TOKEN`
+    fn github_token(&self) -> std::result::Result<Option<String>, ReleaseError> {
+        token::github_token(self.args.token_file.as_deref(), |name| env::var(name).ok())
+    }
+
+    /// Add the working directory to `safe.directory` in the git config chosen by
+    /// `--safe-directory-scope` / `SAFE_DIRECTORY_SCOPE`, so a checkout owned by another user opens
+    fn trust_working_directory(&self) -> std::result::Result<(), ReleaseError> {
+        let scope = match self.args.safe_directory_scope {
+            Some(scope) => scope,
+            None => SafeDirectoryScope::from_env()?,
+        };
+        if scope != SafeDirectoryScope::None {
+            trust_directory(
+                &git2::Config::open_default()?,
+                scope,
+                &self.args.working_directory,
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Publish through `scm_client` instead of a `GitHubClient` built from `GITHUB_TOKEN`
+    pub fn with_scm_client(mut self, scm_client: impl ScmClient + 'static) -> Self {
+        self.scm_client = Some(Box::new(scm_client));
+        self
+    }
+
+    pub async fn run(&self) -> std::result::Result<ActionOutput, ReleaseError> {
+        // Validating a config must work even when that config disables the action
+        if self.args.validate_config {
+            Config::validate_file(&self.args.config_file)?;
+            info!("✅ Configuration {:?} is valid", self.args.config_file);
+            return Ok(ActionOutput::default());
+        }
+
+        if !self.is_enabled() {
+            info!("⏸️  Action is disabled (ACTION_ENABLED or `enabled` in the config), skipping");
+            return Ok(ActionOutput {
+                reason: Some(NoReleaseReason::Disabled),
+                ..ActionOutput::default()
+            });
+        }
+
+        // Before the self-test, which checks that the repository opens
+        self.trust_working_directory()?;
+
+        if self.args.self_test {
+            let report = run_self_test(
+                &self.args.config_file,
+                &self.args.working_directory,
+                self.config.release.remote(),
+                self.github_token()?,
+                None,
             )
-        })?;
+            .await;
 
-        println!("🔧 Loaded configuration from {:?}", self.args.config_file);
+            if !report.passed() {
+                return Err("Self-test failed, see the checks above".into());
+            }
+
+            info!("✅ Self-test passed");
+            return Ok(ActionOutput {
+                released: false,
+                ..ActionOutput::default()
+            });
+        }
+
+        // A config that would e.g. produce malformed tags must not get anywhere near a release;
+        // the self-test above reports the same problems as a failed check
+        self.config.validate()?;
+
+        // The organization-wide commit policy, fetched once for the whole run
+        let config = self.config_with_policy().await?;
+        let config = config.as_ref();
+
+        // Make sure the working directory is a repository before doing anything else
+        ensure_git_repository(&self.args.working_directory)?;
+
+        // The config path is relative to where we were invoked, not to the working directory
+        let config_path = self.args.config_file.canonicalize().ok();
+
+        info!("🔧 Loaded configuration from {:?}", self.args.config_file);
+
+        // Preview the bump of a commit that hasn't been created yet (commit-msg hook mode)
+        if let Some(message_file) = &self.args.message_file {
+            let version_bump = get_impact_from_message_file(
+                &self.args.working_directory.join(message_file),
+                config,
+            )?;
+            let version_manager = self.with_supplied_version(VersionManager::offline(config))?;
+            let current_version = version_manager
+                .get_version_from_local_tags(&open_repository(&self.args.working_directory)?)?;
+            let new_version =
+                version_manager.calculate_new_version(&current_version, &version_bump)?;
+
+            info!(
+                "🧮 This commit message would cause a {:?} bump: {} → {}",
+                version_bump, current_version, new_version
+            );
+            return Ok(ActionOutput {
+                previous_version: Some(current_version.to_string()),
+                bump_type: Some(version_bump.name().to_string()),
+                released: false,
+                version: Some(new_version.to_string()),
+                ..ActionOutput::default()
+            });
+        }
 
         // Check if this is a PR and validate the title
         if should_validate_pr() {
             if let Ok(event_path) = env::var("GITHUB_EVENT_PATH") {
-                validate_pr_title(&event_path).await?;
+                let validation = validate_pr_title(&event_path, config).await?;
                 return Ok(ActionOutput {
+                    outcome: if validation.is_valid() {
+                        Outcome::Informational
+                    } else {
+                        Outcome::InvalidPullRequest
+                    },
                     released: false,
-                    version: None,
-                    tag: None,
-                    release_url: None,
+                    ..ActionOutput::default()
                 });
             }
         }
 
-        // Initialize GitHub client
-        let github_token = env::var("GITHUB_TOKEN")
-            .map_err(|_| "GITHUB_TOKEN environment variable is required")?;
-        let github_client = GitHubClient::new(github_token)?;
+        // Missing history silently under-bumps, so say so before analyzing it
+        if let Some(message) =
+            shallow_clone_warning(&open_repository(&self.args.working_directory)?)
+        {
+            if config.release.fail_on_shallow_clone {
+                return Err(ReleaseError::Git(message));
+            }
+            warn!("::warning title=Shallow clone::{}", message);
+        }
+
+        // A checkout without tags would otherwise look like the first release
+        let fetch_tags_by_default = env::var("GITHUB_ACTIONS").is_ok();
+        let tag_source = self
+            .config
+            .version
+            .tag_source()
+            .map_err(ReleaseError::Config)?;
+        if tag_source == TagSource::Local
+            && self
+                .config
+                .version
+                .fetch_tags
+                .unwrap_or(fetch_tags_by_default)
+        {
+            let repo = open_repository(&self.args.working_directory)?;
+            if let Err(e) = fetch_tags(
+                &repo,
+                config.release.remote(),
+                self.github_token()?.as_deref(),
+            ) {
+                warn!("⚠️  {}, using the tags available locally", e);
+            }
+        }
+
+        // One client for the whole run, unless another SCM client was supplied
+        let default_client;
+        let scm_client: &dyn ScmClient = match &self.scm_client {
+            Some(scm_client) => scm_client.as_ref(),
+            None => {
+                let github_token = self.github_token()?.ok_or_else(|| {
+                    ReleaseError::Config(
+                        "GITHUB_TOKEN or GITHUB_TOKEN_FILE environment variable is required"
+                            .to_string(),
+                    )
+                })?;
+                default_client = GitHubClient::new(github_token)?.with_retry_config(config);
+                &default_client
+            }
+        };
+
+        if config.packages.is_empty() {
+            return self
+                .release(scm_client, config, None, config_path.as_deref())
+                .await;
+        }
+        self.release_packages(scm_client, config, config_path.as_deref())
+            .await
+    }
+
+    /// Release every `[[packages]]` entry on its own, each starting from the original HEAD
+    async fn release_packages(
+        &self,
+        scm_client: &dyn ScmClient,
+        config: &Config,
+        config_path: Option<&Path>,
+    ) -> std::result::Result<ActionOutput, ReleaseError> {
+        let repo = open_repository(&self.args.working_directory)?;
+        let head = repo.head()?;
+        let branch = head
+            .is_branch()
+            .then(|| head.name().map(str::to_string))
+            .flatten();
+        let head_commit = head.peel_to_commit()?.id();
+
+        let mut releases = Vec::new();
+        for package in &config.packages {
+            info!(
+                "📦 Releasing package {} from {}",
+                package.name, package.path
+            );
+            let output = self
+                .release(
+                    scm_client,
+                    &config.for_package(package),
+                    Some(package),
+                    config_path,
+                )
+                .await?;
+            if output.released {
+                // The release commit detached HEAD; the next package starts from the same commit
+                restore_head(&repo, branch.as_deref(), head_commit)?;
+            }
+            releases.push(PackageRelease {
+                name: package.name.clone(),
+                previous_version: output.previous_version,
+                version: output.version,
+                released: output.released,
+                reason: output.reason,
+                release_url: output.release_url,
+            });
+        }
+
+        let released = releases.iter().any(|release| release.released);
+        let outcome = if released {
+            Outcome::Released
+        } else if self.args.dry_run {
+            Outcome::Informational
+        } else {
+            Outcome::NoRelease
+        };
+        // Packages agreeing on why none of them was released share that reason; otherwise
+        // each package's own reason is in `releases`
+        let reason = match releases.first().map(|release| release.reason) {
+            _ if released => None,
+            _ if self.args.dry_run => Some(NoReleaseReason::DryRun),
+            Some(reason) if releases.iter().all(|release| release.reason == reason) => reason,
+            _ => None,
+        };
+        Ok(ActionOutput {
+            outcome,
+            released,
+            reason,
+            releases,
+            ..ActionOutput::default()
+        })
+    }
+
+    /// Compute and publish the release described by `config`. For a `package` only commits
+    /// touching it count.
+    async fn release(
+        &self,
+        github_client: &dyn ScmClient,
+        config: &Config,
+        package: Option<&PackageConfig>,
+        config_path: Option<&Path>,
+    ) -> std::result::Result<ActionOutput, ReleaseError> {
+        // Catch misconfigured file paths before any side effects
+        if config.version.require_files_exist {
+            check_files_exist(
+                config.version.files.as_deref().unwrap_or_default(),
+                &self.args.working_directory,
+            )?;
+        }
 
         // Get repository information
-        let repo_info = github_client.get_repository_info().await?;
-        println!("📂 Working with repository: {}", repo_info.full_name);
+        let repository = self.resolve_repository()?;
+        let repo_info = github_client.get_repository_info(&repository).await?;
+        info!("📂 Working with repository: {}", repo_info.full_name);
 
         // Initialize version manager
-        let version_manager = VersionManager::new(&self.config, &repo_info);
+        let repo = open_repository(&self.args.working_directory)?;
+        let version_manager = self.with_supplied_version(
+            VersionManager::new(config, github_client, &repo_info).with_repository(&repo),
+        )?;
 
         // Get current version
         let current_version = version_manager.get_current_version().await?;
-        println!("📋 Current version: {}", current_version);
+        info!("📋 Current version: {}", current_version);
+
+        // Determine version bump, preferring the merged PR's release-type checkbox when configured
+        let pr_bump = if config.version.bump_from_pr_body() {
+            let head = get_last_commit(&self.args.working_directory).await?;
+            if has_skip_release_marker(&head.message) {
+                None
+            } else {
+                get_impact_from_pull_request(github_client, &repo_info, &head.sha, config).await?
+            }
+        } else {
+            None
+        };
+
+        // Collect the commits going into this release
+        let since = match (&self.args.since, &self.args.since_tag) {
+            (Some(revision), _) => Some(revision.clone()),
+            (None, Some(tag)) => Some(resolve_since_tag(tag, config)),
+            (None, None) => None,
+        };
+        let window = self
+            .collect_window(config, package, since.as_deref())
+            .await?;
+        // `[skip release]` / `[no release]` commits never count towards a release, so a marked
+        // commit doesn't hold back the ones that land after it
+        let (mut window, skipped) = split_skip_release_commits(window);
+
+        if self.args.explain {
+            info!(
+                "🔎 Commits since the last release:\n{}",
+                explain_bump(&window, config)
+            );
+        }
+
+        // Rules that changed the bump the commits call for, for `--explain`
+        let mut adjustments = Vec::new();
+        let skipped_shas = skipped
+            .iter()
+            .map(|commit| &commit.sha[..commit.sha.len().min(7)])
+            .collect::<Vec<_>>()
+            .join(", ");
+        if !skipped.is_empty() {
+            adjustments.push(format!(
+                "[skip release] / [no release]: ignored {}",
+                skipped_shas
+            ));
+        }
+
+        // Otherwise the largest bump of any commit since the last release wins
+        let mut version_bump = match pr_bump {
+            Some(bump) => {
+                adjustments.push(format!("pull request release-type box → {}", bump.name()));
+                bump
+            }
+            None => highest_bump(&window, config),
+        };
 
-        // Determine version bump
-        let version_bump = get_impact_from_latest_commit().await?;
+        // Why nothing gets released, when that turns out to be the case
+        let mut no_release_reason = None;
+
+        if version_bump != bump_type::BumpType::None {
+            if let Some(minimum) = config.release.min_releasable_commits {
+                let releasable = count_releasable_commits(&window, config);
+                if releasable < minimum {
+                    info!(
+                        "ℹ️ Only {} releasable commit(s) since the last release, waiting for {}",
+                        releasable, minimum
+                    );
+                    version_bump = bump_type::BumpType::None;
+                    no_release_reason = Some(NoReleaseReason::BelowMinimumCommits);
+                    adjustments.push(format!(
+                        "min_releasable_commits: {} of {} releasable commit(s) → none",
+                        releasable, minimum
+                    ));
+                }
+            }
+        }
+
+        // Maintenance branches can cap the bump so a stray breaking change can't ship a major
+        if let Some(max_bump) = config.version.max_bump()? {
+            let capped = version_bump
+                .clone()
+                .capped_at(&max_bump, config.version.max_bump_clamp)?;
+            if capped != version_bump {
+                adjustments.push(format!(
+                    "max_bump = \"{}\": {} → {}",
+                    max_bump.name(),
+                    version_bump.name(),
+                    capped.name()
+                ));
+            }
+            version_bump = capped;
+        }
+
+        // A `Release-As:` footer pins the exact version, e.g. to jump to 2.0.0
+        let release_as = release_as_version(&window, &current_version)?;
+        if let Some(forced) = &release_as {
+            info!("📌 Release-As footer requests version {}", forced);
+            version_bump = bump_type::BumpType::between(&current_version, forced);
+            adjustments.push(format!("Release-As: {} → {}", forced, version_bump.name()));
+        }
+
+        // Say so when only marked commits would have triggered a release
+        if version_bump == bump_type::BumpType::None
+            && no_release_reason.is_none()
+            && highest_bump(&skipped, config) != bump_type::BumpType::None
+        {
+            info!(
+                "⏭️ Release skipped by request: commit(s) {} contain a [skip release] or [no release] marker",
+                skipped_shas
+            );
+            no_release_reason = Some(NoReleaseReason::SkipMarker);
+        }
+
+        if self.args.explain {
+            info!("🔎 {}", explain_result(&version_bump, &adjustments));
+        }
 
         if version_bump == bump_type::BumpType::None {
-            println!("ℹ️ No release needed based on the latest commit");
+            info!("ℹ️ No release needed based on the commits since the last release");
+            no_release_reason.get_or_insert(NoReleaseReason::NoReleasableCommits);
         }
 
-        let new_version = version_manager.calculate_new_version(&current_version, &version_bump)?;
+        let new_version = match release_as {
+            Some(forced) => forced,
+            None => version_manager.calculate_new_version(&current_version, &version_bump)?,
+        };
 
-        if self.args.dry_run {
-            println!("🚀 Proposed new version: {}", new_version);
-            println!("🔍 Dry run mode - no release will be created");
+        // Where the window starts, for comparing against the new release
+        let mut window_start = since
+            .clone()
+            .unwrap_or_else(|| config.version.tag_name(&current_version));
+
+        // A stable release covers everything since the last stable one, including prereleases
+        if since.is_none() && new_version.pre.is_empty() {
+            if let Some(tag) = last_stable_release_tag(github_client, &repo_info, config).await? {
+                if open_repository(&self.args.working_directory)?
+                    .revparse_single(&tag)
+                    .is_ok()
+                {
+                    let stable_window = self.collect_window(config, package, Some(&tag)).await?;
+                    window = split_skip_release_commits(stable_window).0;
+                    window_start = tag;
+                } else {
+                    warn!(
+                        "⚠️  Last stable release tag {} is not available locally, \
+                         release notes start at the previous release instead",
+                        tag
+                    );
+                }
+            }
+        }
+
+        // A dry run that wouldn't release anyway reports why not rather than the dry run
+        if self.args.dry_run && version_bump == bump_type::BumpType::None {
+            info!("🚀 Proposed new version: {}", new_version);
+            info!("🔍 Dry run mode - no release will be created");
             return Ok(ActionOutput {
+                previous_version: Some(current_version.to_string()),
+                bump_type: Some(version_bump.name().to_string()),
                 released: false,
+                reason: no_release_reason,
                 version: Some(new_version.to_string()),
-                tag: None,
-                release_url: None,
+                commits: window.iter().map(|commit| commit.sha.clone()).collect(),
+                ..ActionOutput::default()
             });
         }
 
         if version_bump == bump_type::BumpType::None {
             return Ok(ActionOutput {
+                previous_version: Some(current_version.to_string()),
+                bump_type: Some(version_bump.name().to_string()),
+                outcome: Outcome::NoRelease,
+                released: false,
+                reason: no_release_reason,
+                version: Some(new_version.to_string()),
+                ..ActionOutput::default()
+            });
+        }
+
+        let contributors = collect_contributors(&window);
+
+        let pull_requests = if config.changelog.links_to_pull_requests()? {
+            resolve_pull_request_links(github_client, &repo_info, &window).await?
+        } else {
+            PullRequestLinks::new()
+        };
+        // Without a previous release the window is the whole history
+        let first_release = since.is_none()
+            && find_previous_release_commit(
+                &open_repository(&self.args.working_directory)?,
+                config,
+            )
+            .await?
+            .is_none();
+        let links = config.changelog.links.then(|| {
+            ReleaseLinks::from_env(
+                &repo_info.full_name,
+                (!first_release).then_some(window_start),
+                config.version.tag_name(&new_version),
+            )
+        });
+        // With auto_notes GitHub writes the notes from the merged pull requests instead
+        let release_body = if config.release.auto_notes {
+            String::new()
+        } else if first_release {
+            build_first_release_notes(
+                &window,
+                &version_bump,
+                config,
+                &pull_requests,
+                links.as_ref(),
+            )?
+        } else {
+            build_release_notes(
+                &window,
+                &version_bump,
+                config,
+                &pull_requests,
+                links.as_ref(),
+            )
+        };
+
+        let changelog_section = config.changelog.enabled.then(|| {
+            render_changelog_section(
+                &new_version,
+                &chrono::Utc::now().format("%Y-%m-%d").to_string(),
+                &window,
+                config,
+                &pull_requests,
+                links.as_ref(),
+            )
+        });
+
+        if self.args.dry_run {
+            let plan = ReleasePlan {
+                current_version: current_version.clone(),
+                new_version: new_version.clone(),
+                bump: version_bump.clone(),
+                tag: config.version.tag_name(&new_version),
+                release_notes: (!config.release.auto_notes).then_some(release_body),
+                file_changes: preview_file_changes(
+                    &self.args.working_directory,
+                    config,
+                    config_path,
+                    &new_version,
+                    changelog_section.as_deref(),
+                )?,
+            };
+            info!("🗺️  Release plan:\n{}", plan.render());
+            info!("🔍 Dry run mode - no release will be created");
+            return Ok(ActionOutput {
+                previous_version: Some(current_version.to_string()),
+                bump_type: Some(version_bump.name().to_string()),
                 released: false,
+                reason: Some(NoReleaseReason::DryRun),
                 version: Some(new_version.to_string()),
-                tag: None,
-                release_url: None,
+                commits: window.iter().map(|commit| commit.sha.clone()).collect(),
+                ..ActionOutput::default()
             });
         }
 
+        // A retried workflow may find its release already published
+        let tag_name = config.version.tag_name(&new_version);
+        if let Some(existing) = github_client
+            .get_release_by_tag(&repo_info, &tag_name)
+            .await?
+        {
+            info!(
+                "ℹ️ Release {} already exists, nothing to do: {}",
+                tag_name, existing.url
+            );
+            return Ok(ActionOutput {
+                previous_version: Some(current_version.to_string()),
+                bump_type: Some(version_bump.name().to_string()),
+                outcome: Outcome::NoRelease,
+                released: false,
+                reason: Some(NoReleaseReason::AlreadyReleased),
+                version: Some(new_version.to_string()),
+                tag: Some(existing.tag_name),
+                release_url: Some(existing.url),
+                ..ActionOutput::default()
+            });
+        }
+
+        // Resolve the assets up front, so a missing artifact fails before anything is published
+        let assets = ReleaseAssets::resolve(&self.args.working_directory, config)?;
+
         // Create release
-        println!("🚀 Proposed new version: {}", new_version);
-        let release_commit_sha = create_release_commit(&new_version, &self.config).await?;
-        println!("📦 Created release commit: {}", release_commit_sha);
+        let release_started = Instant::now();
+        info!("🚀 Proposed new version: {}", new_version);
+        let release_commit_sha = create_release_commit(
+            &self.args.working_directory,
+            &new_version,
+            config,
+            config_path,
+            changelog_section.as_deref(),
+        )
+        .await?;
+        info!("📦 Created release commit: {}", release_commit_sha);
 
         // Push the commit to remote and get the branch name
-        let branch_name = push_commit_to_remote(&release_commit_sha, &new_version).await?;
+        let github_token = self.github_token()?;
+        let branch_name = push_commit_to_remote(
+            &self.args.working_directory,
+            &release_commit_sha,
+            &new_version,
+            config,
+            github_token.as_deref(),
+        )
+        .await?;
 
-        let release_info = github_client
-            .create_release(&repo_info, &new_version, &self.config, &release_commit_sha)
-            .await?;
+        let published = async {
+            let release_info = github_client
+                .create_release(
+                    &repo_info,
+                    &new_version,
+                    config,
+                    &release_commit_sha,
+                    release_body,
+                )
+                .await?;
+            upload_release_assets(github_client, &release_info, &assets).await?;
+            Ok::<_, ReleaseError>(release_info)
+        }
+        .await;
 
-        // Delete the temporary remote branch after releasing
-        delete_remote_branch(&branch_name).await?;
+        // Delete the temporary remote branch after releasing, and also when that failed
+        let cleanup = delete_remote_branch(
+            &self.args.working_directory,
+            config.release.remote(),
+            &branch_name,
+            github_token.as_deref(),
+        )
+        .await;
+        if let (Err(_), Err(e)) = (&published, &cleanup) {
+            warn!(
+                "⚠️  Failed to delete the temporary branch {}: {}",
+                branch_name, e
+            );
+        }
+        let release_info = published?;
+        cleanup?;
 
-        println!("✅ Successfully created release: {}", release_info.html_url);
+        info!("✅ Successfully created release: {}", release_info.url);
+        let (released_at, duration_ms) = release_timing(release_started);
 
         Ok(ActionOutput {
+            previous_version: Some(current_version.to_string()),
+            bump_type: Some(version_bump.name().to_string()),
+            outcome: Outcome::Released,
             released: true,
             version: Some(new_version.to_string()),
             tag: Some(release_info.tag_name.clone()),
-            release_url: Some(release_info.html_url),
+            release_url: Some(release_info.url),
+            contributors: contributors.iter().map(|c| c.display_name()).collect(),
+            commits: window.iter().map(|commit| commit.sha.clone()).collect(),
+            released_at: Some(released_at),
+            duration_ms: Some(duration_ms),
+            ..ActionOutput::default()
         })
     }
+
+    /// The commits since the previous release, narrowed to those touching `package`
+    async fn collect_window(
+        &self,
+        config: &Config,
+        package: Option<&PackageConfig>,
+        since: Option<&str>,
+    ) -> std::result::Result<Vec<Commit>, ReleaseError> {
+        let window =
+            get_commits_since_previous_release(&self.args.working_directory, config, since).await?;
+        match package {
+            Some(package) => commits_touching_path(
+                &open_repository(&self.args.working_directory)?,
+                window,
+                &package.path,
+            ),
+            None => Ok(window),
+        }
+    }
+
+    /// The config with the commit policy at `policy_url` merged in, when one is configured
+    async fn config_with_policy(&self) -> std::result::Result<Cow<'_, Config>, ReleaseError> {
+        let Some(policy_url) = &self.config.commits.policy_url else {
+            return Ok(Cow::Borrowed(&self.config));
+        };
+        let policy = fetch_commit_policy(policy_url, &self.config).await?;
+        let mut config = self.config.clone();
+        policy.apply_to(&mut config);
+        info!("🏛️ Applied commit policy from {}", policy_url);
+        Ok(Cow::Owned(config))
+    }
+
+    /// Whether releases are enabled; `ACTION_ENABLED` overrides the config's `enabled`
+    fn is_enabled(&self) -> bool {
+        match env::var("ACTION_ENABLED") {
+            Ok(value) if !value.trim().is_empty() => !matches!(
+                value.trim().to_lowercase().as_str(),
+                "false" | "0" | "no" | "off"
+            ),
+            _ => self.config.enabled,
+        }
+    }
+
+    /// Apply `--current-version`, if given, so the tag lookup is skipped
+    fn with_supplied_version<'a>(
+        &self,
+        version_manager: VersionManager<'a>,
+    ) -> std::result::Result<VersionManager<'a>, ReleaseError> {
+        match &self.args.current_version {
+            Some(version) => {
+                let version = semver::Version::parse(version).map_err(|e| {
+                    ReleaseError::InvalidVersion(format!(
+                        "Invalid --current-version {:?}: {}",
+                        version, e
+                    ))
+                })?;
+                Ok(version_manager.with_current_version(version))
+            }
+            None => Ok(version_manager),
+        }
+    }
+
+    /// The process exit code for a finished run
+    pub fn exit_code(&self, output: &ActionOutput) -> i32 {
+        output::exit_code(output, self.config.release.no_release_exit_code)
+    }
+
+    /// Resolve `owner/name` from `--repository`, then `GITHUB_REPOSITORY`, then the release remote
+    fn resolve_repository(&self) -> std::result::Result<String, ReleaseError> {
+        if let Some(repository) = &self.args.repository {
+            return Ok(repository.clone());
+        }
+        if let Ok(repository) = env::var("GITHUB_REPOSITORY") {
+            return Ok(repository);
+        }
+
+        let repo = open_repository(&self.args.working_directory)?;
+        let remote = self.config.release.remote();
+        let repository = repository_from_remote(&repo, remote).ok_or_else(|| {
+            format!(
+                "Could not determine the repository: set GITHUB_REPOSITORY, pass --repository, \
+                 or add an '{}' remote pointing at GitHub",
+                remote
+            )
+        })?;
+        info!(
+            "🔗 Detected repository {} from the {} remote",
+            repository, remote
+        );
+
+        Ok(repository)
+    }
 }
 
 // Factory function for easier testing and dependency injection
-pub async fn create_release_application(
-) -> std::result::Result<ReleaseApplication, Box<dyn std::error::Error>> {
+pub async fn create_release_application() -> std::result::Result<ReleaseApplication, ReleaseError> {
     // Parse command line arguments or use environment variables (for GitHub Actions)
     let args = if env::var("GITHUB_ACTIONS").is_ok() {
         Args::from_env()
     } else {
-        Args::parse()
+        // Usage errors are reported like any other error; `--help` and `--version` exit
+        match Args::try_parse_args(env::args_os()) {
+            Ok(args) => args,
+            Err(e) => {
+                logging::init(logging::level(false, false), None);
+                return Err(e);
+            }
+        }
     };
+    // Hide the token from the logs; an unreadable token file is reported when it's needed
+    let github_token = token::github_token(args.token_file.as_deref(), |name| env::var(name).ok())
+        .ok()
+        .flatten();
+    if let Some(token) = &github_token {
+        logging::mask_github_token(token);
+    }
+    logging::init(logging::level(args.verbose, args.quiet), github_token);
 
-    // Load configuration
-    let config = Config::load(&args.config_file)
-        .map_err(|e| format!("Failed to load config from {:?}: {}", args.config_file, e))?;
+    // Load configuration; in self-test mode a broken config is reported by the check itself
+    let config = match Config::load(&args.config_file) {
+        Ok(config) => config,
+        Err(_) if args.self_test => Config::default(),
+        Err(e) => {
+            return Err(ReleaseError::Config(format!(
+                "Failed to load config from {:?}: {}",
+                args.config_file, e
+            )))
+        }
+    };
 
     Ok(ReleaseApplication::new(args, config))
 }
@@ -154,6 +868,19 @@ mod tests {
             config_file: PathBuf::from("test-config.toml"),
             dry_run: true,
             working_directory: PathBuf::from("."),
+            self_test: false,
+            validate_config: false,
+            since: None,
+            since_tag: None,
+            repository: None,
+            remote: None,
+            token_file: None,
+            message_file: None,
+            explain: false,
+            current_version: None,
+            verbose: false,
+            quiet: false,
+            safe_directory_scope: Some(SafeDirectoryScope::None),
         }
     }
 
@@ -181,4 +908,161 @@ mod tests {
 
         assert!(!app.args.dry_run);
     }
+
+    #[test]
+    fn test_invalid_current_version_is_rejected() {
+        let mut args = create_test_args();
+        args.current_version = Some("not-a-version".to_string());
+        let config = create_test_config();
+        let app = ReleaseApplication::new(args, config);
+
+        let error = app
+            .with_supplied_version(VersionManager::offline(&app.config))
+            .err()
+            .unwrap();
+        assert!(matches!(
+            &error,
+            ReleaseError::InvalidVersion(message) if message.contains("Invalid --current-version")
+        ));
+        assert_eq!(error.exit_code(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_disabled_action_short_circuits_run() {
+        // Outside a repository any real work would fail immediately
+        let temp_dir = tempfile::TempDir::new().expect("Failed to create temp directory");
+        let mut args = create_test_args();
+        args.working_directory = temp_dir.path().to_path_buf();
+        let mut config = create_test_config();
+        config.enabled = false;
+        // Nor is the commit policy fetched, so an unreachable endpoint doesn't matter
+        config.commits.policy_url = Some("http://127.0.0.1:9/policy.json".to_string());
+        let app = ReleaseApplication::new(args, config);
+
+        let output = app.run().await.unwrap();
+        assert!(!output.released);
+        assert_eq!(output.outcome, Outcome::Informational);
+    }
+
+    #[tokio::test]
+    async fn test_run_fails_early_outside_git_repository() {
+        let temp_dir = tempfile::TempDir::new().expect("Failed to create temp directory");
+        let mut args = create_test_args();
+        args.working_directory = temp_dir.path().to_path_buf();
+        let app = ReleaseApplication::new(args, create_test_config());
+
+        let error_msg = app.run().await.err().unwrap().to_string();
+        assert!(
+            error_msg.contains("is not a git repository"),
+            "Unexpected error: {}",
+            error_msg
+        );
+    }
+
+    #[tokio::test]
+    async fn test_validate_config_runs_without_repository() {
+        // Neither a repository nor a token is needed
+        let temp_dir = tempfile::TempDir::new().expect("Failed to create temp directory");
+        let config_file = temp_dir.path().join(".release-config.toml");
+        std::fs::write(&config_file, "[version]\ninitial_version = \"1.0.0\"\n").unwrap();
+
+        let mut args = create_test_args();
+        args.working_directory = temp_dir.path().to_path_buf();
+        args.config_file = config_file.clone();
+        args.validate_config = true;
+        let output = ReleaseApplication::new(args, create_test_config())
+            .run()
+            .await
+            .unwrap();
+        assert!(!output.released);
+
+        std::fs::write(&config_file, "[version]\ninitial_version = \"1.0\"\n").unwrap();
+        let mut args = create_test_args();
+        args.config_file = config_file;
+        args.validate_config = true;
+        let error = ReleaseApplication::new(args, create_test_config())
+            .run()
+            .await
+            .err()
+            .unwrap();
+        assert_eq!(error.exit_code(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_invalid_config_fails_the_release() {
+        let mut config = create_test_config();
+        config.version.tag_prefix = Some("v-".to_string());
+        config.version.tag_separator = Some("-".to_string());
+
+        let error = ReleaseApplication::new(create_test_args(), config)
+            .run()
+            .await
+            .err()
+            .unwrap();
+        assert!(error.to_string().contains("tag_prefix"), "{}", error);
+        assert_eq!(error.exit_code(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_invalid_commit_type_bump_fails_the_release() {
+        // Rather than silently falling back to the built-in bumps for every type
+        let mut config = create_test_config();
+        config
+            .commit_types
+            .insert("improvement".to_string(), "huge".to_string());
+
+        let error = ReleaseApplication::new(create_test_args(), config)
+            .run()
+            .await
+            .err()
+            .unwrap();
+        assert!(error.to_string().contains("huge"), "{}", error);
+        assert_eq!(error.exit_code(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_colliding_package_prefixes_fail_the_release() {
+        let package = |name: &str| config::PackageConfig {
+            name: name.to_string(),
+            path: format!("crates/{}", name),
+            tag_prefix: Some("v".to_string()),
+            initial_version: None,
+            files: None,
+        };
+        let mut config = create_test_config();
+        config.packages = vec![package("core"), package("cli")];
+
+        let error = ReleaseApplication::new(create_test_args(), config)
+            .run()
+            .await
+            .err()
+            .unwrap();
+        assert!(error.to_string().contains("reuses tag_prefix"), "{}", error);
+        assert_eq!(error.exit_code(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_validate_config_when_disabled() {
+        let temp_dir = tempfile::TempDir::new().expect("Failed to create temp directory");
+        let config_file = temp_dir.path().join(".release-config.toml");
+        std::fs::write(
+            &config_file,
+            "enabled = false\n[version]\ninitial_version = \"1.0\"\n",
+        )
+        .unwrap();
+
+        let mut args = create_test_args();
+        args.config_file = config_file;
+        args.validate_config = true;
+        let config = Config {
+            enabled: false,
+            ..create_test_config()
+        };
+        let error = ReleaseApplication::new(args, config)
+            .run()
+            .await
+            .err()
+            .unwrap();
+        assert_eq!(error.exit_code(), 2);
+    }
 }
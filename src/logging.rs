@@ -0,0 +1,84 @@
+use log::{Level, LevelFilter};
+use std::env;
+use std::io::Write;
+
+/// How much the run reports
+pub fn level(verbose: bool, quiet: bool) -> LevelFilter {
+    if quiet {
+        LevelFilter::Warn
+    } else if verbose {
+        LevelFilter::Debug
+    } else {
+        LevelFilter::Info
+    }
+}
+
+/// Route log messages as plain lines, so workflow commands like `::warning::` still reach the
+/// Actions runner: warnings and errors to stderr, everything else to stdout. `secret`, e.g. the
+/// GitHub token, is redacted from them.
+pub fn init(level: LevelFilter, secret: Option<String>) {
+    let secret = secret.unwrap_or_default();
+    let _ = env_logger::Builder::new()
+        .filter_level(level)
+        .format(move |buf, record| {
+            let line = redact(&record.args().to_string(), &secret);
+            if goes_to_stderr(record.level()) {
+                writeln!(std::io::stderr(), "{}", line)
+            } else {
+                writeln!(buf, "{}", line)
+            }
+        })
+        .target(env_logger::Target::Stdout)
+        .try_init();
+}
+
+/// Whether records of `level` are problems, which belong on stderr
+fn goes_to_stderr(level: Level) -> bool {
+    level <= Level::Warn
+}
+
+/// Replace every occurrence of `secret` in `text` with `***`
+pub fn redact(text: &str, secret: &str) -> String {
+    if secret.is_empty() {
+        return text.to_string();
+    }
+    text.replace(secret, "***")
+}
+
+/// Ask the Actions runner to mask `token` in everything it shows, including output that
+/// doesn't go through the logger
+pub fn mask_github_token(token: &str) {
+    if env::var("GITHUB_ACTIONS").is_ok() && !token.is_empty() {
+        println!("::add-mask::{}", token);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_level_from_flags() {
+        assert_eq!(level(false, false), LevelFilter::Info);
+        assert_eq!(level(true, false), LevelFilter::Debug);
+        assert_eq!(level(false, true), LevelFilter::Warn);
+        assert_eq!(level(true, true), LevelFilter::Warn);
+    }
+
+    #[test]
+    fn test_problems_go_to_stderr() {
+        assert!(goes_to_stderr(Level::Error));
+        assert!(goes_to_stderr(Level::Warn));
+        assert!(!goes_to_stderr(Level::Info));
+        assert!(!goes_to_stderr(Level::Debug));
+    }
+
+    #[test]
+    fn test_redact_replaces_every_occurrence() {
+        assert_eq!(
+            redact("token ghp_abc rejected: ghp_abc", "ghp_abc"),
+            "token *** rejected: ***"
+        );
+        assert_eq!(redact("nothing to hide", ""), "nothing to hide");
+    }
+}
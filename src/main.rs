@@ -1,10 +1,25 @@
+use conventional_release_action::error::ReleaseError;
 use conventional_release_action::{create_release_application, output::output_results};
-use std::error::Error;
+use log::error;
 
-#[tokio::main]
-async fn main() -> Result<(), Box<dyn Error>> {
+async fn run() -> Result<i32, ReleaseError> {
     let app = create_release_application().await?;
     let result = app.run().await?;
+    let exit_code = app.exit_code(&result);
     output_results(result)?;
-    Ok(())
+    Ok(exit_code)
+}
+
+#[tokio::main]
+async fn main() {
+    let exit_code = match run().await {
+        Ok(exit_code) => exit_code,
+        Err(e) => {
+            error!("❌ {}", e);
+            e.exit_code()
+        }
+    };
+    if exit_code != 0 {
+        std::process::exit(exit_code);
+    }
 }
@@ -1,26 +1,93 @@
+use crate::error::ReleaseError;
+use chrono::{SecondsFormat, Utc};
+use log::info;
 use serde::Serialize;
 use std::env;
+use std::time::Instant;
 
-#[derive(Serialize)]
+/// What a run ended up doing, used to pick the process exit code
+#[derive(Serialize, Default, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum Outcome {
+    Released,
+    NoRelease,
+    /// Dry runs, previews, validation and self-tests
+    #[default]
+    Informational,
+    /// The PR title doesn't follow the conventional commit format
+    InvalidPullRequest,
+}
+
+/// Why a run didn't release, reported as a stable snake_case string
+#[derive(Serialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum NoReleaseReason {
+    /// Turned off through `enabled` or `ACTION_ENABLED`
+    Disabled,
+    /// A release would have been created, but this is a dry run
+    DryRun,
+    /// None of the commits since the last release calls for a bump
+    NoReleasableCommits,
+    /// Fewer releasable commits than `min_releasable_commits`
+    BelowMinimumCommits,
+    /// A commit carries `[skip release]` or `[no release]`
+    SkipMarker,
+    /// The release for the computed tag exists already
+    AlreadyReleased,
+}
+
+impl NoReleaseReason {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            NoReleaseReason::Disabled => "disabled",
+            NoReleaseReason::DryRun => "dry_run",
+            NoReleaseReason::NoReleasableCommits => "no_releasable_commits",
+            NoReleaseReason::BelowMinimumCommits => "below_minimum_commits",
+            NoReleaseReason::SkipMarker => "skip_marker",
+            NoReleaseReason::AlreadyReleased => "already_released",
+        }
+    }
+}
+
+#[derive(Serialize, Default)]
 pub struct ActionOutput {
+    pub outcome: Outcome,
     pub released: bool,
+    /// Why nothing was released; `None` when a release was created
+    pub reason: Option<NoReleaseReason>,
     pub version: Option<String>,
+    /// The version released before this one
+    pub previous_version: Option<String>,
+    /// `major`, `minor`, `patch` or `none`
+    pub bump_type: Option<String>,
     pub tag: Option<String>,
     pub release_url: Option<String>,
+    pub contributors: Vec<String>,
+    pub commits: Vec<String>,
+    /// When the release finished, as an RFC 3339 UTC timestamp
+    pub released_at: Option<String>,
+    /// How long creating the release took, in milliseconds
+    pub duration_ms: Option<u64>,
+    /// Per-package results in `[[packages]]` mode; empty for single-package runs
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub releases: Vec<PackageRelease>,
 }
 
-pub fn output_results(output: ActionOutput) -> std::result::Result<(), Box<dyn std::error::Error>> {
+#[derive(Serialize, Default, Clone)]
+pub struct PackageRelease {
+    pub name: String,
+    pub previous_version: Option<String>,
+    pub version: Option<String>,
+    pub released: bool,
+    pub reason: Option<NoReleaseReason>,
+    pub release_url: Option<String>,
+}
+
+pub fn output_results(output: ActionOutput) -> std::result::Result<(), ReleaseError> {
     // Output for GitHub Actions
     if env::var("GITHUB_ACTIONS").is_ok() {
         if let Ok(output_file) = env::var("GITHUB_OUTPUT") {
-            let output_content = format!(
-                "released={}\nversion={}\ntag={}\nrelease-url={}",
-                output.released,
-                output.version.as_deref().unwrap_or(""),
-                output.tag.as_deref().unwrap_or(""),
-                output.release_url.as_deref().unwrap_or("")
-            );
-            std::fs::write(output_file, output_content)
+            std::fs::write(output_file, format_github_output(&output))
                 .map_err(|e| format!("Failed to write GitHub Actions output: {}", e))?;
         }
 
@@ -29,45 +96,287 @@ pub fn output_results(output: ActionOutput) -> std::result::Result<(), Box<dyn s
     }
 
     // Also output as JSON for debugging
-    println!("📊 Result: {}", serde_json::to_string_pretty(&output)?);
+    info!(
+        "📊 Result: {}",
+        serde_json::to_string_pretty(&output)
+            .map_err(|e| format!("Failed to serialize the result: {}", e))?
+    );
 
     Ok(())
 }
 
-fn write_step_summary(
-    output: &ActionOutput,
-) -> std::result::Result<(), Box<dyn std::error::Error>> {
-    if let Ok(summary_file) = env::var("GITHUB_STEP_SUMMARY") {
-        let is_pr = env::var("GITHUB_EVENT_NAME").unwrap_or_default() == "pull_request";
+/// Timestamp and elapsed milliseconds for a release operation that began at `started`
+pub fn release_timing(started: Instant) -> (String, u64) {
+    let released_at = Utc::now().to_rfc3339_opts(SecondsFormat::Secs, true);
+    let duration_ms = u64::try_from(started.elapsed().as_millis()).unwrap_or(u64::MAX);
+    (released_at, duration_ms)
+}
+
+/// Exit code for the process: `no_release_exit_code` when no release was needed, 1 for an
+/// invalid PR title, else 0
+pub fn exit_code(output: &ActionOutput, no_release_exit_code: Option<i32>) -> i32 {
+    match (output.outcome, no_release_exit_code) {
+        (Outcome::NoRelease, Some(code)) => code,
+        (Outcome::InvalidPullRequest, _) => 1,
+        _ => 0,
+    }
+}
 
-        let summary_content = if is_pr {
-            // PR Preview Summary
-            if output.released {
-                format!(
-                    "🔍 **Release Preview (Dry Run)**\n\n✅ **This PR would create a new release:**\n- **Proposed Version:** {}\n- **Proposed Tag:** {}\n",
-                    output.version.as_deref().unwrap_or("N/A"),
-                    output.tag.as_deref().unwrap_or("N/A")
-                )
-            } else {
-                "🔍 **Release Preview (Dry Run)**\n\nℹ️ **No release would be created** - no qualifying commits found\n".to_string()
-            }
+/// Render the `key=value` lines written to `GITHUB_OUTPUT`
+pub fn format_github_output(output: &ActionOutput) -> String {
+    format!(
+        "released={}\nreason={}\nversion={}\nprevious-version={}\nbump-type={}\ntag={}\nrelease-url={}\n\
+         contributors={}\ncommit-count={}\nreleased-at={}\nduration-ms={}",
+        output.released,
+        output.reason.map(|reason| reason.as_str()).unwrap_or(""),
+        output.version.as_deref().unwrap_or(""),
+        output.previous_version.as_deref().unwrap_or(""),
+        output.bump_type.as_deref().unwrap_or(""),
+        output.tag.as_deref().unwrap_or(""),
+        output.release_url.as_deref().unwrap_or(""),
+        output.contributors.join(","),
+        output.commits.len(),
+        output.released_at.as_deref().unwrap_or(""),
+        output
+            .duration_ms
+            .map(|ms| ms.to_string())
+            .unwrap_or_default()
+    )
+}
+
+/// Render a collapsible list of the commits included in the release
+fn format_commit_list(commits: &[String]) -> String {
+    if commits.is_empty() {
+        return String::new();
+    }
+
+    let mut content = format!(
+        "\n<details><summary>{} commit(s) included</summary>\n\n",
+        commits.len()
+    );
+    for sha in commits {
+        content.push_str(&format!("- `{}`\n", sha));
+    }
+    content.push_str("\n</details>\n");
+    content
+}
+
+/// Render a markdown table with one row per package
+fn format_package_table(releases: &[PackageRelease]) -> String {
+    let mut content =
+        String::from("| Package | Previous | New | Released | URL |\n|---|---|---|---|---|\n");
+    for release in releases {
+        content.push_str(&format!(
+            "| {} | {} | {} | {} | {} |\n",
+            release.name,
+            release.previous_version.as_deref().unwrap_or("-"),
+            release.version.as_deref().unwrap_or("-"),
+            if release.released { "✅" } else { "—" },
+            release.release_url.as_deref().unwrap_or("-")
+        ));
+    }
+    content
+}
+
+/// Render the markdown written to `GITHUB_STEP_SUMMARY`
+fn format_step_summary(output: &ActionOutput, is_pr: bool) -> String {
+    if !output.releases.is_empty() {
+        let title = if is_pr {
+            "🔍 **Release Preview (Dry Run)**"
         } else {
-            // Release Summary
-            if output.released {
-                format!(
-                    "🎉 **Release Created Successfully!**\n\n- **Version:** {}\n- **Tag:** {}\n- **Release URL:** {}\n",
-                    output.version.as_deref().unwrap_or("N/A"),
-                    output.tag.as_deref().unwrap_or("N/A"),
-                    output.release_url.as_deref().unwrap_or("N/A")
-                )
-            } else {
-                "ℹ️ **No release created** - no qualifying commits found\n".to_string()
-            }
+            "📦 **Package Releases**"
         };
+        return format!("{}\n\n{}", title, format_package_table(&output.releases))
+            + &format_commit_list(&output.commits);
+    }
+
+    let summary_content = if is_pr {
+        // PR Preview Summary
+        if output.released {
+            format!(
+                "🔍 **Release Preview (Dry Run)**\n\n✅ **This PR would create a new release:**\n- **Proposed Version:** {}\n- **Proposed Tag:** {}\n",
+                output.version.as_deref().unwrap_or("N/A"),
+                output.tag.as_deref().unwrap_or("N/A")
+            )
+        } else {
+            "🔍 **Release Preview (Dry Run)**\n\nℹ️ **No release would be created** - no qualifying commits found\n".to_string()
+        }
+    } else {
+        // Release Summary
+        if output.released {
+            format!(
+                "🎉 **Release Created Successfully!**\n\n- **Version:** {}\n- **Tag:** {}\n- **Release URL:** {}\n",
+                output.version.as_deref().unwrap_or("N/A"),
+                output.tag.as_deref().unwrap_or("N/A"),
+                output.release_url.as_deref().unwrap_or("N/A")
+            )
+        } else {
+            "ℹ️ **No release created** - no qualifying commits found\n".to_string()
+        }
+    };
+
+    summary_content + &format_commit_list(&output.commits)
+}
 
-        std::fs::write(summary_file, summary_content)
+fn write_step_summary(output: &ActionOutput) -> std::result::Result<(), ReleaseError> {
+    if let Ok(summary_file) = env::var("GITHUB_STEP_SUMMARY") {
+        let is_pr = env::var("GITHUB_EVENT_NAME").unwrap_or_default() == "pull_request";
+
+        std::fs::write(summary_file, format_step_summary(output, is_pr))
             .map_err(|e| format!("Failed to write GitHub Step Summary: {}", e))?;
     }
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_github_output() {
+        let output = ActionOutput {
+            released: true,
+            version: Some("1.2.0".to_string()),
+            previous_version: Some("1.1.3".to_string()),
+            bump_type: Some("minor".to_string()),
+            tag: Some("v1.2.0".to_string()),
+            release_url: Some("https://github.com/owner/repo/releases/tag/v1.2.0".to_string()),
+            contributors: vec!["@octocat".to_string(), "Alice".to_string()],
+            commits: vec!["abc".to_string(), "def".to_string()],
+            released_at: Some("2024-05-01T12:00:00Z".to_string()),
+            duration_ms: Some(1500),
+            ..ActionOutput::default()
+        };
+
+        assert_eq!(
+            format_github_output(&output),
+            "released=true\nreason=\nversion=1.2.0\nprevious-version=1.1.3\nbump-type=minor\ntag=v1.2.0\n\
+             release-url=https://github.com/owner/repo/releases/tag/v1.2.0\n\
+             contributors=@octocat,Alice\ncommit-count=2\n\
+             released-at=2024-05-01T12:00:00Z\nduration-ms=1500"
+        );
+    }
+
+    #[test]
+    fn test_json_output_includes_previous_version_and_bump_type() {
+        let output = ActionOutput {
+            outcome: Outcome::Released,
+            released: true,
+            version: Some("2.0.0".to_string()),
+            previous_version: Some("1.4.2".to_string()),
+            bump_type: Some("major".to_string()),
+            ..ActionOutput::default()
+        };
+
+        let json = serde_json::to_value(&output).unwrap();
+        assert_eq!(json["previous_version"], "1.4.2");
+        assert_eq!(json["bump_type"], "major");
+        assert!(json["reason"].is_null());
+
+        let json = serde_json::to_value(ActionOutput::default()).unwrap();
+        assert!(json["previous_version"].is_null());
+        assert!(json["bump_type"].is_null());
+    }
+
+    #[test]
+    fn test_no_release_reason_output() {
+        let output = ActionOutput {
+            outcome: Outcome::NoRelease,
+            reason: Some(NoReleaseReason::SkipMarker),
+            ..ActionOutput::default()
+        };
+
+        assert!(format_github_output(&output).starts_with("released=false\nreason=skip_marker\n"));
+        assert_eq!(
+            serde_json::to_value(&output).unwrap()["reason"],
+            "skip_marker"
+        );
+        assert_eq!(
+            serde_json::to_value(NoReleaseReason::DryRun).unwrap(),
+            NoReleaseReason::DryRun.as_str()
+        );
+    }
+
+    #[test]
+    fn test_release_timing() {
+        let (released_at, duration_ms) = release_timing(Instant::now());
+
+        let timestamp = chrono::DateTime::parse_from_rfc3339(&released_at).unwrap();
+        assert_eq!(timestamp.offset().local_minus_utc(), 0);
+        assert!(duration_ms < 60_000);
+    }
+
+    #[test]
+    fn test_exit_code_for_outcome() {
+        let output = |outcome| ActionOutput {
+            outcome,
+            ..ActionOutput::default()
+        };
+
+        assert_eq!(exit_code(&output(Outcome::NoRelease), Some(78)), 78);
+        assert_eq!(exit_code(&output(Outcome::NoRelease), None), 0);
+        assert_eq!(exit_code(&output(Outcome::Released), Some(78)), 0);
+        assert_eq!(exit_code(&output(Outcome::Informational), Some(78)), 0);
+        assert_eq!(exit_code(&output(Outcome::InvalidPullRequest), None), 1);
+    }
+
+    #[test]
+    fn test_format_commit_list() {
+        let list = format_commit_list(&["abc".to_string(), "def".to_string()]);
+        assert!(list.contains("2 commit(s) included"));
+        assert!(list.contains("- `abc`\n- `def`\n"));
+        assert_eq!(format_commit_list(&[]), "");
+    }
+
+    #[test]
+    fn test_step_summary_package_table() {
+        let output = ActionOutput {
+            released: true,
+            releases: vec![
+                PackageRelease {
+                    name: "core".to_string(),
+                    previous_version: Some("1.0.0".to_string()),
+                    version: Some("1.1.0".to_string()),
+                    released: true,
+                    reason: None,
+                    release_url: Some(
+                        "https://github.com/owner/repo/releases/tag/core-v1.1.0".to_string(),
+                    ),
+                },
+                PackageRelease {
+                    name: "cli".to_string(),
+                    previous_version: Some("0.3.2".to_string()),
+                    version: Some("0.3.2".to_string()),
+                    released: false,
+                    reason: Some(NoReleaseReason::NoReleasableCommits),
+                    release_url: None,
+                },
+            ],
+            ..ActionOutput::default()
+        };
+
+        let summary = format_step_summary(&output, false);
+
+        assert!(summary.contains("| Package | Previous | New | Released | URL |\n"));
+        assert!(summary.contains(
+            "| core | 1.0.0 | 1.1.0 | ✅ | https://github.com/owner/repo/releases/tag/core-v1.1.0 |\n"
+        ));
+        assert!(summary.contains("| cli | 0.3.2 | 0.3.2 | — | - |\n"));
+        assert!(!summary.contains("Release Created Successfully"));
+    }
+
+    #[test]
+    fn test_step_summary_single_package() {
+        let output = ActionOutput {
+            released: true,
+            version: Some("1.2.0".to_string()),
+            tag: Some("v1.2.0".to_string()),
+            ..ActionOutput::default()
+        };
+
+        let summary = format_step_summary(&output, false);
+        assert!(summary.starts_with("🎉 **Release Created Successfully!**"));
+        assert!(!summary.contains("| Package |"));
+    }
+}
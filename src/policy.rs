@@ -0,0 +1,214 @@
+use crate::bump_type::BumpType;
+use crate::config::Config;
+use crate::error::ReleaseError;
+use crate::scm::github::{retrying_send, DEFAULT_MAX_RETRIES, DEFAULT_RETRY_DELAY_MS};
+use reqwest::header::USER_AGENT;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::time::Duration;
+
+/// How long fetching the policy may take before the attempt counts as failed
+const POLICY_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Commit conventions published centrally as JSON, e.g. by a platform team
+#[derive(Debug, Deserialize, Default, PartialEq)]
+pub struct CommitPolicy {
+    #[serde(default)]
+    pub type_aliases: HashMap<String, String>,
+    pub allowed_scopes: Option<Vec<String>>,
+    pub allowed_types: Option<Vec<String>>,
+    /// Bump produced by each commit type, merged underneath the local `[commit_types]`
+    #[serde(default)]
+    pub commit_types: HashMap<String, String>,
+}
+
+impl CommitPolicy {
+    /// Merge the policy into `config`; settings in the local config take precedence
+    pub fn apply_to(self, config: &mut Config) {
+        let commits = &mut config.commits;
+        for (alias, canonical) in self.type_aliases {
+            commits.type_aliases.entry(alias).or_insert(canonical);
+        }
+        if commits.allowed_scopes.is_none() {
+            commits.allowed_scopes = self.allowed_scopes;
+        }
+        if commits.allowed_types.is_none() {
+            commits.allowed_types = self.allowed_types;
+        }
+        // Local entries win, compared case-insensitively as commit types are matched
+        for (commit_type, bump) in self.commit_types {
+            let overridden = config
+                .commit_types
+                .keys()
+                .any(|local| local.eq_ignore_ascii_case(&commit_type));
+            if !overridden {
+                config.commit_types.insert(commit_type, bump);
+            }
+        }
+    }
+}
+
+/// Download the commit policy published at `url`, retrying transient failures as configured
+/// by `api_max_retries` / `api_retry_delay_ms` in `config`
+pub async fn fetch_commit_policy(
+    url: &str,
+    config: &Config,
+) -> std::result::Result<CommitPolicy, ReleaseError> {
+    let client = reqwest::Client::builder()
+        .timeout(POLICY_TIMEOUT)
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+    let max_retries = config
+        .release
+        .api_max_retries
+        .unwrap_or(DEFAULT_MAX_RETRIES);
+    let retry_delay = config
+        .release
+        .api_retry_delay_ms
+        .unwrap_or(DEFAULT_RETRY_DELAY_MS);
+
+    let request = client
+        .get(url)
+        .header(USER_AGENT, "conventional-release-action");
+    let response = retrying_send(
+        request,
+        true,
+        max_retries,
+        Duration::from_millis(retry_delay),
+    )
+    .await
+    .map_err(|e| format!("Failed to fetch commit policy from {}: {}", url, e))?;
+
+    if !response.status().is_success() {
+        return Err(format!(
+            "Failed to fetch commit policy from {}: {}",
+            url,
+            response.status()
+        )
+        .into());
+    }
+
+    let policy: CommitPolicy = response
+        .json()
+        .await
+        .map_err(|e| format!("Invalid commit policy at {}: {}", url, e))?;
+
+    for (commit_type, bump) in &policy.commit_types {
+        if BumpType::from_name(bump).is_none() {
+            return Err(ReleaseError::Config(format!(
+                "Invalid commit policy at {}: invalid bump {:?} for commit type {:?}",
+                url, bump, commit_type
+            )));
+        }
+    }
+
+    Ok(policy)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn no_retries() -> Config {
+        let mut config = Config::default();
+        config.release.api_max_retries = Some(0);
+        config
+    }
+
+    #[tokio::test]
+    async fn test_fetch_and_apply_remote_policy() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("GET", "/policy.json")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"{"type_aliases": {"feature": "feat", "bugfix": "fix"}, "allowed_scopes": ["api"],
+                    "commit_types": {"chore": "patch", "improvement": "minor"}}"#,
+            )
+            .create_async()
+            .await;
+
+        let policy = fetch_commit_policy(&format!("{}/policy.json", server.url()), &no_retries())
+            .await
+            .unwrap();
+        mock.assert_async().await;
+
+        let mut config = Config::default();
+        config
+            .commits
+            .type_aliases
+            .insert("bugfix".to_string(), "perf".to_string());
+        config
+            .commit_types
+            .insert("Improvement".to_string(), "patch".to_string());
+        policy.apply_to(&mut config);
+
+        assert_eq!(config.commits.type_aliases["feature"], "feat");
+        // Local configuration overrides the remote policy
+        assert_eq!(config.commits.type_aliases["bugfix"], "perf");
+        assert_eq!(config.commits.allowed_scopes, Some(vec!["api".to_string()]));
+
+        let commit_types = config.commit_types().unwrap();
+        assert_eq!(commit_types["chore"], BumpType::Patch);
+        assert_eq!(commit_types["improvement"], BumpType::Patch);
+        assert_eq!(commit_types.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_fetch_policy_invalid_commit_type_bump() {
+        let mut server = mockito::Server::new_async().await;
+        server
+            .mock("GET", "/policy.json")
+            .with_status(200)
+            .with_body(r#"{"commit_types": {"chore": "huge"}}"#)
+            .create_async()
+            .await;
+
+        let result =
+            fetch_commit_policy(&format!("{}/policy.json", server.url()), &no_retries()).await;
+        assert!(matches!(result, Err(ReleaseError::Config(message)) if message.contains("huge")));
+    }
+
+    #[tokio::test]
+    async fn test_fetch_policy_http_error() {
+        let mut server = mockito::Server::new_async().await;
+        server
+            .mock("GET", "/policy.json")
+            .with_status(404)
+            .create_async()
+            .await;
+
+        let result =
+            fetch_commit_policy(&format!("{}/policy.json", server.url()), &no_retries()).await;
+        assert!(result.unwrap_err().to_string().contains("404"));
+    }
+
+    #[tokio::test]
+    async fn test_fetch_policy_retries_transient_failures() {
+        let mut server = mockito::Server::new_async().await;
+        let unavailable = server
+            .mock("GET", "/policy.json")
+            .with_status(503)
+            .expect(2)
+            .create_async()
+            .await;
+        let available = server
+            .mock("GET", "/policy.json")
+            .with_status(200)
+            .with_body(r#"{"allowed_scopes": ["api"]}"#)
+            .create_async()
+            .await;
+
+        let mut config = Config::default();
+        config.release.api_max_retries = Some(2);
+        config.release.api_retry_delay_ms = Some(1);
+        let policy = fetch_commit_policy(&format!("{}/policy.json", server.url()), &config)
+            .await
+            .unwrap();
+
+        assert_eq!(policy.allowed_scopes, Some(vec!["api".to_string()]));
+        unavailable.assert_async().await;
+        available.assert_async().await;
+    }
+}
@@ -1,33 +1,33 @@
-use crate::config::Config;
-use crate::file_updater::update_file_version;
+use crate::changelog::update_changelog;
+use crate::config::{Config, MajorRefType};
+use crate::error::ReleaseError;
+use crate::file_updater::update_files;
+use crate::logging;
 use crate::scm::git::open_repository;
-use git2::{ObjectType, Repository, Signature};
+use git2::{AutotagOption, Cred, FetchOptions, ObjectType, RemoteCallbacks, Repository, Signature};
+use log::{debug, info};
 use semver::Version;
 use std::collections::hash_map::DefaultHasher;
+use std::collections::HashSet;
 use std::env;
 use std::hash::{Hash, Hasher};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
 
-pub async fn find_previous_release_commit(
+/// Collect `(version, commit)` pairs for every tag matching the configured tag format
+fn collect_release_tags(
     repo: &Repository,
     config: &Config,
-) -> std::result::Result<Option<git2::Oid>, Box<dyn std::error::Error>> {
+) -> std::result::Result<Vec<(Version, git2::Oid)>, ReleaseError> {
     // Get all tags from the repository
-    let tag_prefix = config.version.tag_prefix.as_deref().unwrap_or("");
-    let tag_suffix = config.version.tag_suffix.as_deref().unwrap_or("");
-
     let mut versions_and_commits = Vec::new();
 
     repo.tag_foreach(|oid, name| {
         if let Ok(name_str) = std::str::from_utf8(name) {
             if let Some(tag_name) = name_str.strip_prefix("refs/tags/") {
-                // Remove prefix and suffix to get version string
-                let mut version_str = tag_name;
-                if !tag_prefix.is_empty() && tag_name.starts_with(tag_prefix) {
-                    version_str = &tag_name[tag_prefix.len()..];
-                }
-                if !tag_suffix.is_empty() && version_str.ends_with(tag_suffix) {
-                    version_str = &version_str[..version_str.len() - tag_suffix.len()];
-                }
+                // Remove prefix, separator and suffix to get version string
+                let version_str = config.version.version_part(tag_name);
 
                 if let Ok(version) = Version::parse(version_str) {
                     versions_and_commits.push((version, oid));
@@ -37,6 +37,25 @@ pub async fn find_previous_release_commit(
         true // Continue iteration
     })?;
 
+    // Annotated tags point at tag objects, so peel them to their commits
+    let mut release_tags = Vec::new();
+    for (version, oid) in versions_and_commits {
+        let commit = repo.find_object(oid, None)?.peel_to_commit()?;
+        release_tags.push((version, commit.id()));
+    }
+
+    Ok(release_tags)
+}
+
+pub async fn find_previous_release_commit(
+    repo: &Repository,
+    config: &Config,
+) -> std::result::Result<Option<git2::Oid>, ReleaseError> {
+    let mut versions_and_commits = collect_release_tags(repo, config)?;
+    if config.version.ignores_prerelease_tags() {
+        versions_and_commits.retain(|(version, _)| version.pre.is_empty());
+    }
+
     if versions_and_commits.is_empty() {
         return Ok(None);
     }
@@ -46,11 +65,147 @@ pub async fn find_previous_release_commit(
     Ok(versions_and_commits.into_iter().last().map(|(_, oid)| oid))
 }
 
+/// Release commits made by this action: commits carrying a release tag whose message is the
+/// release commit message for that version. They never count toward a bump
+pub fn find_release_commits(
+    repo: &Repository,
+    config: &Config,
+) -> std::result::Result<HashSet<git2::Oid>, ReleaseError> {
+    let mut release_commits = HashSet::new();
+    for (version, oid) in collect_release_tags(repo, config)? {
+        let expected = config
+            .release
+            .commit_message(&version, &config.version.tag_name(&version));
+        let commit = repo.find_commit(oid)?;
+        if commit.message().map(str::trim_end) == Some(expected.trim_end()) {
+            release_commits.insert(oid);
+        }
+    }
+    Ok(release_commits)
+}
+
+/// Who the release commit is attributed to: the configured identity, else the built-in bot,
+/// else the repository's own `user.name`/`user.email` should that not form a valid signature
+fn release_signature(
+    repo: &Repository,
+    config: &Config,
+) -> std::result::Result<Signature<'static>, ReleaseError> {
+    let name = config.release.commit_author_name();
+    let email = config.release.commit_author_email();
+    match Signature::now(name, email) {
+        Ok(signature) => Ok(signature),
+        Err(e) => repo.signature().map_err(|_| {
+            ReleaseError::Config(format!(
+                "Invalid release commit author {:?} <{}>: {}",
+                name, email, e
+            ))
+        }),
+    }
+}
+
+/// Tag `commit_oid` as `tag_name`: lightweight by default, annotated with the release title
+/// when `annotated_tags` is set and signed with gpg when `sign_tags` is set
+fn create_tag(
+    repo: &Repository,
+    tag_name: &str,
+    commit_oid: git2::Oid,
+    tagger: &Signature,
+    config: &Config,
+) -> std::result::Result<(), ReleaseError> {
+    let target = repo.find_object(commit_oid, Some(ObjectType::Commit))?;
+    if !config.release.annotated_tags() {
+        repo.tag_lightweight(tag_name, &target, false)?;
+        return Ok(());
+    }
+
+    let message = format!("Release {}\n", tag_name);
+    if !config.release.sign_tags {
+        repo.tag(tag_name, &target, tagger, &message, false)?;
+        return Ok(());
+    }
+
+    // A signed tag is an annotated tag whose message ends in a detached signature of the rest
+    let (offset_sign, offset) = match tagger.when().offset_minutes() {
+        minutes if minutes < 0 => ('-', -minutes),
+        minutes => ('+', minutes),
+    };
+    let content = format!(
+        "object {}\ntype commit\ntag {}\ntagger {} <{}> {} {}{:02}{:02}\n\n{}",
+        commit_oid,
+        tag_name,
+        tagger.name().unwrap_or_default(),
+        tagger.email().unwrap_or_default(),
+        tagger.when().seconds(),
+        offset_sign,
+        offset / 60,
+        offset % 60,
+        message
+    );
+    let signature = gpg_sign(repo, &content, config.release.signing_key.as_deref())?;
+    let tag_oid = repo.odb()?.write(
+        ObjectType::Tag,
+        format!("{}{}", content, signature).as_bytes(),
+    )?;
+    repo.reference(
+        &format!("refs/tags/{}", tag_name),
+        tag_oid,
+        false,
+        &format!("Signed release tag {}", tag_name),
+    )?;
+    info!("🔏 Signed tag {}", tag_name);
+
+    Ok(())
+}
+
+/// An ASCII-armored detached signature of `content`, made by `gpg.program` (default `gpg`)
+fn gpg_sign(
+    repo: &Repository,
+    content: &str,
+    key: Option<&str>,
+) -> std::result::Result<String, ReleaseError> {
+    let program = repo
+        .config()?
+        .get_string("gpg.program")
+        .unwrap_or_else(|_| "gpg".to_string());
+    let mut command = Command::new(&program);
+    command.args(["--detach-sign", "--armor"]);
+    if let Some(key) = key {
+        command.args(["--local-user", key]);
+    }
+    let mut child = command
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("Failed to run {} to sign the tag: {}", program, e))?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin
+            .write_all(content.as_bytes())
+            .map_err(|e| format!("Failed to pass the tag to {}: {}", program, e))?;
+    }
+    let output = child
+        .wait_with_output()
+        .map_err(|e| format!("Failed to run {} to sign the tag: {}", program, e))?;
+    if !output.status.success() {
+        return Err(format!(
+            "{} failed to sign the tag: {}",
+            program,
+            String::from_utf8_lossy(&output.stderr).trim()
+        )
+        .into());
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
 pub async fn create_release_commit(
+    base: &Path,
     version: &Version,
     config: &Config,
-) -> std::result::Result<String, Box<dyn std::error::Error>> {
-    let repo = open_repository(".")?;
+    config_path: Option<&Path>,
+    changelog_section: Option<&str>,
+) -> std::result::Result<String, ReleaseError> {
+    let repo = open_repository(base)?;
 
     // Try to find the previous release tag to chain from
     let base_commit_oid = find_previous_release_commit(&repo, config).await?;
@@ -62,7 +217,7 @@ pub async fn create_release_commit(
     let parents = match base_commit_oid {
         Some(oid) => {
             let previous_release_commit = repo.find_commit(oid)?;
-            println!(
+            debug!(
                 "📎 Creating release with two parents: previous release {} and main {}",
                 oid,
                 main_commit.id()
@@ -70,33 +225,54 @@ pub async fn create_release_commit(
             vec![previous_release_commit, main_commit]
         }
         None => {
-            println!("📎 No previous release found, basing on main branch only");
+            debug!("📎 No previous release found, basing on main branch only");
             vec![main_commit]
         }
     };
 
-    // Update files with new version information
-    if let Some(files) = &config.version.files {
-        for file_config in files {
-            update_file_version(file_config, version)?;
-        }
-    }
+    // Update files with new version information, in their declared order
+    let files = config.version.files.as_deref().unwrap_or_default();
+    update_files(base, files, version)?;
 
     // Add all updated files to the index
     let mut index = repo.index()?;
-    if let Some(files) = &config.version.files {
-        for file_config in files {
-            if std::path::Path::new(&file_config.path).exists() {
-                index.add_path(std::path::Path::new(&file_config.path))?;
-            }
+    for file_config in files {
+        if base.join(&file_config.path).exists() {
+            index.add_path(Path::new(&file_config.path))?;
+        }
+    }
+
+    // Hooks may regenerate other tracked files (e.g. lockfiles), so stage those too
+    if files
+        .iter()
+        .any(|file_config| file_config.run_after.is_some())
+    {
+        index.update_all(["*"], None)?;
+    }
+
+    // Prepend this release to the changelog when enabled
+    if let Some(section) = changelog_section {
+        let changelog_path = Path::new(config.changelog.path());
+        update_changelog(&base.join(changelog_path), section)?;
+        index.add_path(changelog_path)?;
+        info!("📝 Added version {} to {:?}", version, changelog_path);
+    }
+
+    // Record the released version in the config file itself when requested
+    if config.version.update_config_version {
+        if let Some(config_path) = config_path.filter(|path| path.exists()) {
+            Config::record_version(config_path, version)?;
+            index.add_path(&path_in_workdir(&repo, config_path)?)?;
+            info!("📝 Recorded version {} in {:?}", version, config_path);
         }
     }
     index.write()?;
     let tree_id = index.write_tree()?;
     let tree = repo.find_tree(tree_id)?;
 
-    let signature = Signature::now("Release Bot", "release@github.com")?;
-    let message = format!("chore: release version {}", version);
+    let signature = release_signature(&repo, config)?;
+    let tag_name = config.version.tag_name(version);
+    let message = config.release.commit_message(version, &tag_name);
 
     // Create commit with multiple parents (merge-like)
     // Detach HEAD so we don't update any branch
@@ -113,50 +289,54 @@ pub async fn create_release_commit(
     )?;
 
     // Create the tag
-    let tag_name = format!(
-        "{}{}{}",
-        config.version.tag_prefix.as_deref().unwrap_or(""),
-        version,
-        config.version.tag_suffix.as_deref().unwrap_or("")
-    );
+    create_tag(&repo, &tag_name, commit_oid, &signature, config)?;
 
-    repo.tag_lightweight(
-        &tag_name,
-        &repo.find_object(commit_oid, Some(ObjectType::Commit))?,
-        false,
-    )?;
+    // Create or update the major version ref (e.g., v0, v1, v2), unless disabled
+    let Some(major_name) = config.release.major_ref_name(version) else {
+        return Ok(commit_oid.to_string());
+    };
+    let major_ref_type = config.release.major_ref_type()?;
+    let major_ref_name = major_ref(&major_name, major_ref_type);
 
-    // Create or update major version branch (e.g., v0, v1, v2)
-    let major_branch_name = format!("v{}", version.major);
-    let branch_ref_name = format!("refs/heads/{}", major_branch_name);
+    if major_ref_type == MajorRefType::Tag {
+        // A moving tag is simply force-updated to the new release commit
+        repo.reference(
+            &major_ref_name,
+            commit_oid,
+            true,
+            &format!("Move {} tag to release {}", major_name, version),
+        )?;
+        info!(
+            "📌 Moved tag {} to point to release {}",
+            major_name, version
+        );
+        return Ok(commit_oid.to_string());
+    }
 
     // Check if the branch already exists
-    match repo.find_reference(&branch_ref_name) {
+    match repo.find_reference(&major_ref_name) {
         Ok(mut existing_ref) => {
             // Branch exists, update it to point to new commit
             existing_ref.set_target(
                 commit_oid,
-                &format!("Update {} to release {}", major_branch_name, version),
+                &format!("Update {} to release {}", major_name, version),
             )?;
-            println!(
+            info!(
                 "📌 Updated branch {} to point to release {}",
-                major_branch_name, version
+                major_name, version
             );
         }
         Err(_) => {
             // Branch doesn't exist, create it
             repo.reference(
-                &branch_ref_name,
+                &major_ref_name,
                 commit_oid,
                 false,
-                &format!(
-                    "Create {} branch for release {}",
-                    major_branch_name, version
-                ),
+                &format!("Create {} branch for release {}", major_name, version),
             )?;
-            println!(
+            info!(
                 "🌿 Created new branch {} for release {}",
-                major_branch_name, version
+                major_name, version
             );
         }
     }
@@ -164,17 +344,290 @@ pub async fn create_release_commit(
     Ok(commit_oid.to_string())
 }
 
+/// Move HEAD back to `branch`, or to `commit` when it was detached, and reset the working
+/// tree so files updated for a release don't leak into the next one
+pub fn restore_head(
+    repo: &Repository,
+    branch: Option<&str>,
+    commit: git2::Oid,
+) -> std::result::Result<(), ReleaseError> {
+    match branch {
+        Some(branch) => repo.set_head(branch)?,
+        None => repo.set_head_detached(commit)?,
+    }
+    repo.checkout_head(Some(git2::build::CheckoutBuilder::new().force()))?;
+    Ok(())
+}
+
+/// Express `path` relative to the repository's working directory, as the index expects
+fn path_in_workdir(repo: &Repository, path: &Path) -> std::result::Result<PathBuf, ReleaseError> {
+    let workdir = repo
+        .workdir()
+        .ok_or("Cannot stage files in a bare repository")?
+        .canonicalize()?;
+    let path = path.canonicalize()?;
+
+    path.strip_prefix(&workdir)
+        .map(Path::to_path_buf)
+        .map_err(|_| format!("{:?} is outside the repository {:?}", path, workdir).into())
+}
+
+/// Full ref name of the moving major version pointer named `name`, e.g. `refs/heads/v1`
+pub fn major_ref(name: &str, ref_type: MajorRefType) -> String {
+    match ref_type {
+        MajorRefType::Branch => format!("refs/heads/{}", name),
+        MajorRefType::Tag => format!("refs/tags/{}", name),
+    }
+}
+
+/// Refspecs pushing the temporary release branch and the major version ref, if any.
+///
+/// A moving major tag is force-pushed since it is moved to each new release; the push itself
+/// still refuses to move it if a concurrent release got there first.
+pub fn release_refspecs(
+    temp_ref: &str,
+    major_ref: Option<&str>,
+    ref_type: MajorRefType,
+) -> Vec<String> {
+    let force = match ref_type {
+        MajorRefType::Branch => "",
+        MajorRefType::Tag => "+",
+    };
+    let mut refspecs = vec![format!("{}:{}", temp_ref, temp_ref)];
+    if let Some(major_ref) = major_ref {
+        refspecs.push(format!("{}{}:{}", force, major_ref, major_ref));
+    }
+    refspecs
+}
+
+/// Build the name of the temporary branch used to push a release commit
+pub fn temporary_branch_name(prefix: &str, commit_sha: &str, unique_id: &str) -> String {
+    format!("{}{}-{}", prefix, &commit_sha[..8], unique_id)
+}
+
+/// How to authenticate against a remote
+#[derive(Debug, PartialEq)]
+enum Credential {
+    /// Keys offered by the running ssh-agent
+    SshAgent { username: String },
+    /// A private key file, optionally protected by a passphrase
+    SshKey {
+        username: String,
+        private_key: PathBuf,
+        passphrase: Option<String>,
+    },
+    /// The GitHub token over HTTPS
+    Token(String),
+    /// Whatever libgit2 finds on its own
+    Default,
+}
+
+/// Whether `url` is reached over SSH: `ssh://host/path` or scp-like `[user@]host:path`
+fn is_ssh_url(url: &str) -> bool {
+    if url.starts_with("ssh://") || url.starts_with("git+ssh://") {
+        return true;
+    }
+    !url.contains("://")
+        && url
+            .split_once(':')
+            .is_some_and(|(host, _)| !host.is_empty() && !host.contains('/'))
+}
+
+/// Pick credentials for `url`: `github_token` for HTTPS remotes, and for SSH remotes the key
+/// in `SSH_PRIVATE_KEY_PATH`, then the agent at `SSH_AUTH_SOCK`, then a default key in `~/.ssh`
+fn select_credential(
+    url: &str,
+    username_from_url: Option<&str>,
+    github_token: Option<&str>,
+    var: impl Fn(&str) -> Option<String>,
+) -> Credential {
+    if !is_ssh_url(url) {
+        return match github_token {
+            Some(token) => Credential::Token(token.to_string()),
+            None => Credential::Default,
+        };
+    }
+
+    let username = username_from_url.unwrap_or("git").to_string();
+    let key = |private_key: PathBuf| Credential::SshKey {
+        username: username.clone(),
+        private_key,
+        passphrase: var("SSH_KEY_PASSPHRASE"),
+    };
+    if let Some(private_key) = var("SSH_PRIVATE_KEY_PATH") {
+        return key(private_key.into());
+    }
+    if var("SSH_AUTH_SOCK").is_some() {
+        return Credential::SshAgent { username };
+    }
+    if let Some(home) = var("HOME") {
+        let ssh_dir = Path::new(&home).join(".ssh");
+        if let Some(private_key) = ["id_ed25519", "id_ecdsa", "id_rsa"]
+            .iter()
+            .map(|name| ssh_dir.join(name))
+            .find(|path| path.is_file())
+        {
+            return key(private_key);
+        }
+    }
+    Credential::SshAgent { username }
+}
+
+/// Credentials for talking to the release remote, chosen by [`select_credential`]
+pub(crate) fn auth_callbacks(github_token: Option<&str>) -> RemoteCallbacks<'_> {
+    let mut callbacks = RemoteCallbacks::new();
+    let mut attempts = 0;
+    callbacks.credentials(move |url, username_from_url, _allowed_types| {
+        // libgit2 asks again after rejected credentials; don't retry the same ones forever
+        attempts += 1;
+        if attempts > 3 {
+            return Err(git2::Error::from_str(&format!(
+                "Authentication to {} failed",
+                url
+            )));
+        }
+
+        let var = |name: &str| env::var(name).ok().filter(|value| !value.is_empty());
+        match select_credential(url, username_from_url, github_token, var) {
+            Credential::SshAgent { username } => Cred::ssh_key_from_agent(&username),
+            Credential::SshKey {
+                username,
+                private_key,
+                passphrase,
+            } => Cred::ssh_key(&username, None, &private_key, passphrase.as_deref()),
+            Credential::Token(token) => Cred::userpass_plaintext("git", &token),
+            Credential::Default => Cred::default(),
+        }
+    });
+    callbacks
+}
+
+/// A git error from talking to a remote, which may echo credentials, with the token redacted
+fn remote_error(context: &str, error: git2::Error, github_token: Option<&str>) -> ReleaseError {
+    let message = format!("{}: {}", context, error.message());
+    ReleaseError::Git(logging::redact(&message, github_token.unwrap_or_default()))
+}
+
+/// Fetch every tag of the `remote_name` remote, so tags missing from the checkout are seen
+pub fn fetch_tags(
+    repo: &Repository,
+    remote_name: &str,
+    github_token: Option<&str>,
+) -> std::result::Result<(), ReleaseError> {
+    let mut remote = repo.find_remote(remote_name)?;
+    let mut fetch_options = FetchOptions::new();
+    fetch_options.remote_callbacks(auth_callbacks(github_token));
+    fetch_options.download_tags(AutotagOption::All);
+
+    remote
+        .fetch(&["refs/tags/*:refs/tags/*"], Some(&mut fetch_options), None)
+        .map_err(|e| {
+            remote_error(
+                &format!("Failed to fetch tags from {}", remote_name),
+                e,
+                github_token,
+            )
+        })?;
+    debug!("🏷️  Fetched tags from {}", remote_name);
+
+    Ok(())
+}
+
+/// Compare-and-set guard for the moving major ref: only move it when the remote still points
+/// at something our release commit descends from, so concurrent releases can't clobber it.
+///
+/// `remote_tip` is what the remote reported for `ref_name` while negotiating the push, which
+/// the remote only updates if the ref still has that value.
+pub fn check_major_ref_update(
+    repo: &Repository,
+    ref_name: &str,
+    remote_tip: git2::Oid,
+    release_commit: git2::Oid,
+) -> std::result::Result<(), ReleaseError> {
+    // The ref doesn't exist on the remote yet
+    if remote_tip.is_zero() {
+        return Ok(());
+    }
+
+    // The remote tip may be an annotated tag; an unknown object was never part of our history
+    let remote_commit = repo
+        .find_object(remote_tip, None)
+        .and_then(|object| object.peel_to_commit())
+        .ok();
+    let is_ancestor = match remote_commit {
+        Some(commit) => {
+            commit.id() == release_commit
+                || repo.graph_descendant_of(release_commit, commit.id())?
+        }
+        None => false,
+    };
+
+    if !is_ancestor {
+        return Err(ReleaseError::Git(format!(
+            "Remote {} moved to {} which is not part of this release's history; \
+             another release may be running. Aborting without updating it.",
+            ref_name, remote_tip
+        )));
+    }
+
+    Ok(())
+}
+
+/// Callbacks for pushing the release: authentication, plus the major ref guard checked
+/// between negotiation and upload, so nothing can move the ref between check and push.
+/// Refs the remote refuses fail the push.
+fn release_push_callbacks<'a>(
+    repo: &'a Repository,
+    major_ref_name: Option<&'a str>,
+    release_commit: git2::Oid,
+    github_token: Option<&'a str>,
+) -> RemoteCallbacks<'a> {
+    let mut callbacks = auth_callbacks(github_token);
+    callbacks.push_negotiation(move |updates| {
+        let Some(major_ref_name) = major_ref_name else {
+            return Ok(());
+        };
+        for update in updates {
+            if update.dst_refname() == Some(major_ref_name) {
+                check_major_ref_update(repo, major_ref_name, update.src(), release_commit)
+                    .map_err(|e| git2::Error::from_str(&e.to_string()))?;
+            }
+        }
+        Ok(())
+    });
+    // The remote reports refs it refused (a stale old oid, a non-fast-forward, branch
+    // protection or an existing tag) here, while the push itself still succeeds
+    callbacks.push_update_reference(|ref_name, status| match status {
+        Some(message) => Err(git2::Error::from_str(&format!(
+            "Remote rejected {}: {}",
+            ref_name, message
+        ))),
+        None => Ok(()),
+    });
+    callbacks
+}
+
 pub async fn push_commit_to_remote(
+    base: &Path,
     commit_sha: &str,
     version: &Version,
-) -> std::result::Result<String, Box<dyn std::error::Error>> {
-    use git2::{Cred, PushOptions, RemoteCallbacks};
+    config: &Config,
+    github_token: Option<&str>,
+) -> std::result::Result<String, ReleaseError> {
+    use git2::PushOptions;
 
-    let repo = open_repository(".")?;
+    let repo = open_repository(base)?;
 
     // Get the commit object
     let commit_oid = git2::Oid::from_str(commit_sha)?;
 
+    let mut remote = repo.find_remote(config.release.remote())?;
+    let major_ref_type = config.release.major_ref_type()?;
+    let major_ref_name = config
+        .release
+        .major_ref_name(version)
+        .map(|name| major_ref(&name, major_ref_type));
+
     // Create a unique temporary ref name using GitHub Actions run ID or random hash
     let unique_id = env::var("GITHUB_RUN_ID").unwrap_or_else(|_| {
         let mut hasher = DefaultHasher::new();
@@ -182,7 +635,8 @@ pub async fn push_commit_to_remote(
         hasher.finish().to_string()
     });
 
-    let branch_name = format!("release-{}-{}", &commit_sha[..8], unique_id);
+    let branch_name =
+        temporary_branch_name(config.release.temp_branch_prefix(), commit_sha, &unique_id);
     let ref_name = format!("refs/heads/{}", branch_name);
 
     repo.reference(
@@ -192,32 +646,30 @@ pub async fn push_commit_to_remote(
         "Create temporary release branch",
     )?;
 
-    // Set up authentication
-    let mut callbacks = RemoteCallbacks::new();
-    callbacks.credentials(|_url, _username_from_url, _allowed_types| {
-        if let Ok(token) = env::var("GITHUB_TOKEN") {
-            Cred::userpass_plaintext("git", &token)
-        } else {
-            Cred::default()
-        }
-    });
-
+    // The push is cancelled if a concurrent release already moved the major ref
     let mut push_options = PushOptions::new();
-    push_options.remote_callbacks(callbacks);
-
-    // Push the temporary branch and major version branch
-    let mut remote = repo.find_remote("origin")?;
-    let major_branch_name = format!("v{}", version.major);
-    let major_branch_ref = format!("refs/heads/{}", major_branch_name);
+    push_options.remote_callbacks(release_push_callbacks(
+        &repo,
+        major_ref_name.as_deref(),
+        commit_oid,
+        github_token,
+    ));
 
-    let refspecs = [
-        format!("{}:{}", ref_name, ref_name), // Temporary branch
-        format!("{}:{}", major_branch_ref, major_branch_ref), // Major version branch
-    ];
-    remote.push(&refspecs, Some(&mut push_options))?;
+    // Push the temporary branch and major version ref, and an annotated tag made here, which
+    // GitHub would otherwise replace with a lightweight one when creating the release
+    let mut refspecs = release_refspecs(&ref_name, major_ref_name.as_deref(), major_ref_type);
+    if config.release.annotated_tags() {
+        let tag_ref = format!("refs/tags/{}", config.version.tag_name(version));
+        refspecs.push(format!("{}:{}", tag_ref, tag_ref));
+    }
+    remote
+        .push(&refspecs, Some(&mut push_options))
+        .map_err(|e| remote_error("Failed to push release commit", e, github_token))?;
 
-    println!("🚀 Pushed release commit to remote branch: {}", branch_name);
-    println!("🌿 Pushed major version branch: {}", major_branch_name);
+    info!("🚀 Pushed release commit to remote branch: {}", branch_name);
+    if let Some(major_ref_name) = &major_ref_name {
+        info!("🌿 Pushed major version ref: {}", major_ref_name);
+    }
 
     // Clean up the temporary ref locally
     let mut reference = repo.find_reference(&ref_name)?;
@@ -227,31 +679,602 @@ pub async fn push_commit_to_remote(
 }
 
 pub async fn delete_remote_branch(
+    base: &Path,
+    remote_name: &str,
     branch_name: &str,
-) -> std::result::Result<(), Box<dyn std::error::Error>> {
-    use git2::{Cred, PushOptions, RemoteCallbacks};
+    github_token: Option<&str>,
+) -> std::result::Result<(), ReleaseError> {
+    use git2::PushOptions;
 
-    let repo = open_repository(".")?;
-
-    // Set up authentication
-    let mut callbacks = RemoteCallbacks::new();
-    callbacks.credentials(|_url, _username_from_url, _allowed_types| {
-        if let Ok(token) = env::var("GITHUB_TOKEN") {
-            Cred::userpass_plaintext("git", &token)
-        } else {
-            Cred::default()
-        }
-    });
+    let repo = open_repository(base)?;
 
     let mut push_options = PushOptions::new();
-    push_options.remote_callbacks(callbacks);
+    push_options.remote_callbacks(auth_callbacks(github_token));
 
     // Delete the remote branch by pushing an empty ref
-    let mut remote = repo.find_remote("origin")?;
+    let mut remote = repo.find_remote(remote_name)?;
     let delete_refspec = format!(":refs/heads/{}", branch_name);
-    remote.push(&[delete_refspec], Some(&mut push_options))?;
+    remote
+        .push(&[delete_refspec], Some(&mut push_options))
+        .map_err(|e| {
+            remote_error(
+                &format!("Failed to delete branch {}", branch_name),
+                e,
+                github_token,
+            )
+        })?;
 
-    println!("🗑️  Deleted temporary release branch: {}", branch_name);
+    info!("🗑️  Deleted temporary release branch: {}", branch_name);
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{MajorBranch, ReleaseConfig};
+    use crate::token::vars;
+
+    fn commit_with_parents(repo: &Repository, message: &str, parents: &[git2::Oid]) -> git2::Oid {
+        let sig = Signature::now("Test User", "test@example.com").unwrap();
+        let tree = repo
+            .find_tree(repo.index().unwrap().write_tree().unwrap())
+            .unwrap();
+        let parents: Vec<git2::Commit> = parents
+            .iter()
+            .map(|oid| repo.find_commit(*oid).unwrap())
+            .collect();
+        let parent_refs: Vec<&git2::Commit> = parents.iter().collect();
+        repo.commit(None, &sig, &sig, message, &tree, &parent_refs)
+            .unwrap()
+    }
+
+    /// A local repository with a bare `origin` whose `refs/heads/v1` points at `remote_tip`
+    fn repo_with_remote_major_branch(
+        temp_dir: &tempfile::TempDir,
+    ) -> (Repository, git2::Oid, git2::Oid) {
+        let remote_path = temp_dir.path().join("origin.git");
+        Repository::init_bare(&remote_path).unwrap();
+        let repo = Repository::init(temp_dir.path().join("local")).unwrap();
+        repo.remote("origin", remote_path.to_str().unwrap())
+            .unwrap();
+
+        let previous_release = commit_with_parents(&repo, "chore: release version 1.0.0", &[]);
+        repo.reference("refs/heads/v1", previous_release, true, "test")
+            .unwrap();
+        repo.find_remote("origin")
+            .unwrap()
+            .push(&["refs/heads/v1:refs/heads/v1"], None)
+            .unwrap();
+
+        let release =
+            commit_with_parents(&repo, "chore: release version 1.1.0", &[previous_release]);
+        (repo, previous_release, release)
+    }
+
+    /// Push the release commit as the major ref `ref_name` the way a release does
+    fn push_major_ref(
+        repo: &Repository,
+        ref_name: &str,
+        ref_type: MajorRefType,
+        release: git2::Oid,
+    ) -> std::result::Result<(), git2::Error> {
+        repo.reference("refs/heads/release-tmp", release, true, "test")?;
+        repo.reference(ref_name, release, true, "test")?;
+        let mut push_options = git2::PushOptions::new();
+        push_options.remote_callbacks(release_push_callbacks(repo, Some(ref_name), release, None));
+        repo.find_remote("origin")?.push(
+            &release_refspecs("refs/heads/release-tmp", Some(ref_name), ref_type),
+            Some(&mut push_options),
+        )
+    }
+
+    fn remote_tip(temp_dir: &tempfile::TempDir, ref_name: &str) -> Option<git2::Oid> {
+        let remote = Repository::open_bare(temp_dir.path().join("origin.git")).unwrap();
+        let tip = remote.find_reference(ref_name).ok()?.target();
+        tip
+    }
+
+    #[test]
+    fn test_major_ref_lease_allows_expected_tip() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let (repo, _, release) = repo_with_remote_major_branch(&temp_dir);
+
+        push_major_ref(&repo, "refs/heads/v1", MajorRefType::Branch, release).unwrap();
+        assert_eq!(remote_tip(&temp_dir, "refs/heads/v1"), Some(release));
+        // A ref that doesn't exist on the remote yet can always be created
+        push_major_ref(&repo, "refs/heads/v2", MajorRefType::Branch, release).unwrap();
+        assert_eq!(remote_tip(&temp_dir, "refs/heads/v2"), Some(release));
+    }
+
+    #[test]
+    fn test_major_ref_lease_aborts_when_remote_moved() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let (repo, previous_release, release) = repo_with_remote_major_branch(&temp_dir);
+
+        // Another run released concurrently and moved the remote branch and tag
+        let concurrent =
+            commit_with_parents(&repo, "chore: release version 1.0.1", &[previous_release]);
+        repo.reference("refs/heads/v1", concurrent, true, "test")
+            .unwrap();
+        repo.reference("refs/tags/v1", concurrent, true, "test")
+            .unwrap();
+        repo.find_remote("origin")
+            .unwrap()
+            .push(
+                &["+refs/heads/v1:refs/heads/v1", "refs/tags/v1:refs/tags/v1"],
+                None,
+            )
+            .unwrap();
+
+        let error = push_major_ref(&repo, "refs/heads/v1", MajorRefType::Branch, release)
+            .unwrap_err()
+            .to_string();
+        assert!(error.contains("Aborting"), "Unexpected error: {}", error);
+        assert_eq!(remote_tip(&temp_dir, "refs/heads/v1"), Some(concurrent));
+
+        // The forced push of a major tag is guarded the same way
+        let error = push_major_ref(&repo, "refs/tags/v1", MajorRefType::Tag, release)
+            .unwrap_err()
+            .to_string();
+        assert!(error.contains("Aborting"), "Unexpected error: {}", error);
+        assert_eq!(remote_tip(&temp_dir, "refs/tags/v1"), Some(concurrent));
+    }
+
+    #[test]
+    fn test_push_fails_when_remote_rejects_a_ref() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let (repo, previous_release, release) = repo_with_remote_major_branch(&temp_dir);
+        // A commit the remote already has, for a concurrent run to move the branch to
+        let concurrent =
+            commit_with_parents(&repo, "chore: release version 1.0.1", &[previous_release]);
+        repo.reference("refs/heads/concurrent", concurrent, true, "test")
+            .unwrap();
+        repo.find_remote("origin")
+            .unwrap()
+            .push(&["refs/heads/concurrent:refs/heads/concurrent"], None)
+            .unwrap();
+
+        // After negotiation passed, the concurrent run moves the major branch and is still
+        // holding its lock when our update arrives, so the remote refuses it
+        let origin = temp_dir.path().join("origin.git");
+        let mut callbacks = release_push_callbacks(&repo, Some("refs/heads/v1"), release, None);
+        callbacks.push_transfer_progress(move |_, _, _| {
+            let lock = origin.join("refs/heads/v1.lock");
+            if lock.exists() {
+                return;
+            }
+            Repository::open_bare(&origin)
+                .unwrap()
+                .reference("refs/heads/v1", concurrent, true, "concurrent release")
+                .unwrap();
+            std::fs::write(&lock, "").unwrap();
+        });
+        repo.reference("refs/heads/v1", release, true, "test")
+            .unwrap();
+        let mut push_options = git2::PushOptions::new();
+        push_options.remote_callbacks(callbacks);
+        let error = repo
+            .find_remote("origin")
+            .unwrap()
+            .push(&["refs/heads/v1:refs/heads/v1"], Some(&mut push_options))
+            .unwrap_err()
+            .to_string();
+
+        assert!(error.contains("Remote rejected refs/heads/v1"), "{}", error);
+        assert_eq!(remote_tip(&temp_dir, "refs/heads/v1"), Some(concurrent));
+    }
+
+    #[test]
+    fn test_release_refspecs_major_branch() {
+        let major = major_ref("v2", MajorRefType::Branch);
+        let refspecs =
+            release_refspecs("refs/heads/release-tmp", Some(&major), MajorRefType::Branch);
+
+        assert_eq!(major, "refs/heads/v2");
+        assert_eq!(refspecs[1], "refs/heads/v2:refs/heads/v2");
+    }
+
+    #[test]
+    fn test_release_refspecs_major_tag_is_forced() {
+        let major = major_ref("v2", MajorRefType::Tag);
+        let refspecs = release_refspecs("refs/heads/release-tmp", Some(&major), MajorRefType::Tag);
+
+        assert_eq!(major, "refs/tags/v2");
+        assert_eq!(refspecs[0], "refs/heads/release-tmp:refs/heads/release-tmp");
+        assert_eq!(refspecs[1], "+refs/tags/v2:refs/tags/v2");
+    }
+
+    #[test]
+    fn test_release_refspecs_without_major_ref() {
+        let refspecs = release_refspecs("refs/heads/release-tmp", None, MajorRefType::Branch);
+        assert_eq!(
+            refspecs,
+            vec!["refs/heads/release-tmp:refs/heads/release-tmp".to_string()]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_major_branch_disabled() {
+        let temp_dir = tempfile::TempDir::new().expect("Failed to create temp directory");
+        let repo = repo_with_initial_commit(&temp_dir);
+
+        let mut config = Config::default();
+        config.version.files = None;
+        config.release.major_branch = Some(MajorBranch::Enabled(false));
+
+        let version = Version::parse("1.0.0").unwrap();
+        create_release_commit(temp_dir.path(), &version, &config, None, None)
+            .await
+            .unwrap();
+
+        assert!(repo.find_reference("refs/tags/v1.0.0").is_ok());
+        assert!(repo.find_reference("refs/heads/v1").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_major_branch_pattern() {
+        let temp_dir = tempfile::TempDir::new().expect("Failed to create temp directory");
+        let repo = repo_with_initial_commit(&temp_dir);
+
+        let mut config = Config::default();
+        config.version.files = None;
+        config.release.major_branch = Some(MajorBranch::Pattern("release/v{major}".to_string()));
+
+        let version = Version::parse("3.1.0").unwrap();
+        let sha = create_release_commit(temp_dir.path(), &version, &config, None, None)
+            .await
+            .unwrap();
+
+        let branch = repo.find_reference("refs/heads/release/v3").unwrap();
+        assert_eq!(branch.target().unwrap().to_string(), sha);
+        assert!(repo.find_reference("refs/heads/v3").is_err());
+    }
+
+    #[test]
+    fn test_temporary_branch_name_default_prefix() {
+        let release = ReleaseConfig::default();
+        let name = temporary_branch_name(
+            release.temp_branch_prefix(),
+            "0123456789abcdef0123456789abcdef01234567",
+            "42",
+        );
+        assert_eq!(name, "release-01234567-42");
+    }
+
+    #[test]
+    fn test_temporary_branch_name_custom_prefix() {
+        let release = ReleaseConfig {
+            temp_branch_prefix: Some("tmp/cra-".to_string()),
+            ..ReleaseConfig::default()
+        };
+        let name = temporary_branch_name(
+            release.temp_branch_prefix(),
+            "0123456789abcdef0123456789abcdef01234567",
+            "42",
+        );
+        assert_eq!(name, "tmp/cra-01234567-42");
+    }
+
+    #[tokio::test]
+    async fn test_previous_release_commit_with_stable_tags_only() {
+        let temp_dir = tempfile::TempDir::new().expect("Failed to create temp directory");
+        let repo = Repository::init(temp_dir.path()).unwrap();
+        let stable = commit_with_parents(&repo, "chore: release version 1.1.4", &[]);
+        let prerelease = commit_with_parents(&repo, "chore: release version 1.2.0-rc.1", &[stable]);
+        for (tag, oid) in [("v1.1.4", stable), ("v1.2.0-rc.1", prerelease)] {
+            repo.tag_lightweight(tag, &repo.find_object(oid, None).unwrap(), false)
+                .unwrap();
+        }
+
+        let mut config = Config::default();
+        assert_eq!(
+            find_previous_release_commit(&repo, &config).await.unwrap(),
+            Some(prerelease)
+        );
+
+        config.version.stable_tags_only = true;
+        assert_eq!(
+            find_previous_release_commit(&repo, &config).await.unwrap(),
+            Some(stable)
+        );
+
+        config.version.prerelease = Some("rc".to_string());
+        assert_eq!(
+            find_previous_release_commit(&repo, &config).await.unwrap(),
+            Some(prerelease)
+        );
+    }
+
+    /// A repository with a single commit on HEAD
+    fn repo_with_initial_commit(temp_dir: &tempfile::TempDir) -> Repository {
+        let repo = Repository::init(temp_dir.path()).unwrap();
+        let sig = Signature::now("Test User", "test@example.com").unwrap();
+        let tree_id = repo.index().unwrap().write_tree().unwrap();
+        repo.commit(
+            Some("HEAD"),
+            &sig,
+            &sig,
+            "feat: initial",
+            &repo.find_tree(tree_id).unwrap(),
+            &[],
+        )
+        .unwrap();
+        repo
+    }
+
+    #[tokio::test]
+    async fn test_release_commit_message_template() {
+        let temp_dir = tempfile::TempDir::new().expect("Failed to create temp directory");
+        let repo = repo_with_initial_commit(&temp_dir);
+
+        let mut config = Config::default();
+        config.version.files = None;
+        config.release.commit_message_template =
+            Some("chore(release): {version} [skip ci]\n\nTag: {tag}".to_string());
+
+        let version = Version::parse("1.2.0").unwrap();
+        let sha = create_release_commit(temp_dir.path(), &version, &config, None, None)
+            .await
+            .unwrap();
+
+        let commit = repo
+            .find_commit(git2::Oid::from_str(&sha).unwrap())
+            .unwrap();
+        assert_eq!(
+            commit.message(),
+            Some("chore(release): 1.2.0 [skip ci]\n\nTag: v1.2.0")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_release_commit_uses_configured_author() {
+        let temp_dir = tempfile::TempDir::new().expect("Failed to create temp directory");
+        let repo = repo_with_initial_commit(&temp_dir);
+
+        let mut config = Config::default();
+        config.version.files = None;
+        config.release.commit_author_name = Some("github-actions[bot]".to_string());
+        config.release.commit_author_email =
+            Some("41898282+github-actions[bot]@users.noreply.github.com".to_string());
+
+        let version = Version::parse("1.0.0").unwrap();
+        let sha = create_release_commit(temp_dir.path(), &version, &config, None, None)
+            .await
+            .unwrap();
+
+        let commit = repo
+            .find_commit(git2::Oid::from_str(&sha).unwrap())
+            .unwrap();
+        for signature in [commit.author(), commit.committer()] {
+            assert_eq!(signature.name(), Some("github-actions[bot]"));
+            assert_eq!(
+                signature.email(),
+                Some("41898282+github-actions[bot]@users.noreply.github.com")
+            );
+        }
+    }
+
+    #[test]
+    fn test_release_signature_defaults() {
+        let temp_dir = tempfile::TempDir::new().expect("Failed to create temp directory");
+        let repo = Repository::init(temp_dir.path()).unwrap();
+
+        let signature = release_signature(&repo, &Config::default()).unwrap();
+        assert_eq!(signature.name(), Some("Release Bot"));
+        assert_eq!(signature.email(), Some("release@github.com"));
+
+        // An identity git can't use falls back to the repository's git config
+        repo.config()
+            .unwrap()
+            .set_str("user.name", "Local Maintainer")
+            .unwrap();
+        repo.config()
+            .unwrap()
+            .set_str("user.email", "maintainer@example.com")
+            .unwrap();
+        let mut config = Config::default();
+        config.release.commit_author_name = Some("Broken <Bot>".to_string());
+        let signature = release_signature(&repo, &config).unwrap();
+        assert_eq!(signature.name(), Some("Local Maintainer"));
+    }
+
+    #[tokio::test]
+    async fn test_annotated_release_tag() {
+        let temp_dir = tempfile::TempDir::new().expect("Failed to create temp directory");
+        let repo = repo_with_initial_commit(&temp_dir);
+
+        let mut config = Config::default();
+        config.version.files = None;
+        config.release.annotated_tags = true;
+
+        let version = Version::parse("1.0.0").unwrap();
+        let sha = create_release_commit(temp_dir.path(), &version, &config, None, None)
+            .await
+            .unwrap();
+
+        let tag = repo
+            .find_reference("refs/tags/v1.0.0")
+            .unwrap()
+            .peel_to_tag()
+            .unwrap();
+        assert_eq!(tag.message(), Some("Release v1.0.0\n"));
+        assert_eq!(tag.tagger().unwrap().name(), Some("Release Bot"));
+        assert_eq!(tag.target_id().to_string(), sha);
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_signed_release_tag() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let temp_dir = tempfile::TempDir::new().expect("Failed to create temp directory");
+        let repo = repo_with_initial_commit(&temp_dir);
+
+        // Stand-in for gpg that records its arguments and prints a fixed signature
+        let gpg = temp_dir.path().join("fake-gpg");
+        let args_file = temp_dir.path().join("gpg-args");
+        std::fs::write(
+            &gpg,
+            format!(
+                "#!/bin/sh\necho \"$@\" > {}\ncat > /dev/null\nprintf -- '-----BEGIN PGP SIGNATURE-----\\n\\nfake\\n-----END PGP SIGNATURE-----\\n'\n",
+                args_file.display()
+            ),
+        )
+        .unwrap();
+        std::fs::set_permissions(&gpg, std::fs::Permissions::from_mode(0o755)).unwrap();
+        repo.config()
+            .unwrap()
+            .set_str("gpg.program", gpg.to_str().unwrap())
+            .unwrap();
+
+        let mut config = Config::default();
+        config.version.files = None;
+        config.release.sign_tags = true;
+        config.release.signing_key = Some("ABCD1234".to_string());
+
+        let version = Version::parse("1.0.0").unwrap();
+        let sha = create_release_commit(temp_dir.path(), &version, &config, None, None)
+            .await
+            .unwrap();
+
+        let tag = repo
+            .find_reference("refs/tags/v1.0.0")
+            .unwrap()
+            .peel_to_tag()
+            .unwrap();
+        assert_eq!(tag.target_id().to_string(), sha);
+        assert_eq!(
+            tag.message(),
+            Some("Release v1.0.0\n-----BEGIN PGP SIGNATURE-----\n\nfake\n-----END PGP SIGNATURE-----\n")
+        );
+        let args = std::fs::read_to_string(&args_file).unwrap();
+        assert!(
+            args.contains("--local-user ABCD1234"),
+            "Unexpected args: {}",
+            args
+        );
+    }
+
+    #[tokio::test]
+    async fn test_push_to_configured_remote() {
+        let temp_dir = tempfile::TempDir::new().expect("Failed to create temp directory");
+        let remote_dir = tempfile::TempDir::new().expect("Failed to create temp directory");
+        let repo = repo_with_initial_commit(&temp_dir);
+        let remote_repo = Repository::init_bare(remote_dir.path()).unwrap();
+        repo.remote("upstream", remote_dir.path().to_str().unwrap())
+            .unwrap();
+        let head = repo.head().unwrap().name().unwrap().to_string();
+        repo.find_remote("upstream")
+            .unwrap()
+            .push(&[format!("{}:{}", head, head)], None)
+            .unwrap();
+
+        let mut config = Config::default();
+        config.version.files = None;
+        config.release.remote = Some("upstream".to_string());
+
+        let version = Version::parse("1.0.0").unwrap();
+        let sha = create_release_commit(temp_dir.path(), &version, &config, None, None)
+            .await
+            .unwrap();
+        let branch_name = push_commit_to_remote(temp_dir.path(), &sha, &version, &config, None)
+            .await
+            .unwrap();
+
+        let pushed = format!("refs/heads/{}", branch_name);
+        assert!(remote_repo.find_reference(&pushed).is_ok());
+        assert!(remote_repo.find_reference("refs/heads/v1").is_ok());
+
+        delete_remote_branch(temp_dir.path(), config.release.remote(), &branch_name, None)
+            .await
+            .unwrap();
+        assert!(remote_repo.find_reference(&pushed).is_err());
+    }
+
+    #[test]
+    fn test_https_remote_uses_token() {
+        let url = "https://github.com/owner/repo.git";
+        assert_eq!(
+            select_credential(url, None, Some("secret"), vars(&[])),
+            Credential::Token("secret".to_string())
+        );
+        assert_eq!(
+            select_credential(url, None, None, vars(&[("SSH_AUTH_SOCK", "/tmp/agent")])),
+            Credential::Default
+        );
+    }
+
+    #[test]
+    fn test_ssh_remote_uses_ssh_credentials() {
+        let url = "git@github.com:owner/repo.git";
+        let env = [("SSH_AUTH_SOCK", "/tmp/agent")];
+        assert_eq!(
+            select_credential(url, Some("git"), Some("secret"), vars(&env)),
+            Credential::SshAgent {
+                username: "git".to_string()
+            }
+        );
+
+        let env = [
+            ("SSH_PRIVATE_KEY_PATH", "/keys/deploy"),
+            ("SSH_KEY_PASSPHRASE", "hunter2"),
+            ("SSH_AUTH_SOCK", "/tmp/agent"),
+        ];
+        assert_eq!(
+            select_credential(
+                "ssh://deploy@git.example.com/repo.git",
+                Some("deploy"),
+                None,
+                vars(&env)
+            ),
+            Credential::SshKey {
+                username: "deploy".to_string(),
+                private_key: PathBuf::from("/keys/deploy"),
+                passphrase: Some("hunter2".to_string()),
+            }
+        );
+    }
+
+    #[test]
+    fn test_ssh_remote_falls_back_to_default_key() {
+        let home = tempfile::TempDir::new().unwrap();
+        std::fs::create_dir(home.path().join(".ssh")).unwrap();
+        std::fs::write(home.path().join(".ssh/id_rsa"), "key").unwrap();
+
+        let env = [("HOME", home.path().to_str().unwrap())];
+        assert_eq!(
+            select_credential("git@github.com:owner/repo.git", None, None, vars(&env)),
+            Credential::SshKey {
+                username: "git".to_string(),
+                private_key: home.path().join(".ssh/id_rsa"),
+                passphrase: None,
+            }
+        );
+    }
+
+    #[test]
+    fn test_is_ssh_url() {
+        assert!(is_ssh_url("git@github.com:owner/repo.git"));
+        assert!(is_ssh_url("ssh://git@github.com/owner/repo.git"));
+        assert!(!is_ssh_url("https://github.com/owner/repo.git"));
+        assert!(!is_ssh_url("/tmp/remote.git"));
+    }
+
+    #[test]
+    fn test_fetch_tags_from_remote() {
+        let temp_dir = tempfile::TempDir::new().expect("Failed to create temp directory");
+        let (repo, previous_release, _) = repo_with_remote_major_branch(&temp_dir);
+        let remote_repo = Repository::open_bare(temp_dir.path().join("origin.git")).unwrap();
+        remote_repo
+            .reference("refs/tags/v1.0.0", previous_release, false, "test")
+            .unwrap();
+        assert!(repo.find_reference("refs/tags/v1.0.0").is_err());
+
+        fetch_tags(&repo, "origin", None).unwrap();
+
+        let tag = repo.find_reference("refs/tags/v1.0.0").unwrap();
+        assert_eq!(tag.target(), Some(previous_release));
+        assert!(fetch_tags(&repo, "upstream", None).is_err());
+    }
+}
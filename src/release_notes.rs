@@ -0,0 +1,714 @@
+use crate::bump_type::BumpType;
+use crate::commit::Commit;
+use crate::config::{Config, FirstReleaseChangelog};
+use crate::contributors::{collect_contributors, render_contributors_section};
+use crate::conventional_commit::{ConventionalCommit, ParseOptions};
+use crate::error::ReleaseError;
+use crate::scm::RepositoryInfo;
+use crate::scm::ScmClient;
+use std::collections::{HashMap, HashSet};
+
+/// Pull request numbers keyed by the SHA of the commit they produced
+pub type PullRequestLinks = HashMap<String, u64>;
+
+/// Web links of the repository being released, for `links = true`
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReleaseLinks {
+    /// e.g. `https://github.com/owner/repo`
+    pub repository_url: String,
+    /// Tag of the previous release; without one there is nothing to compare against
+    pub previous_tag: Option<String>,
+    pub tag: String,
+}
+
+impl ReleaseLinks {
+    pub fn new(
+        server_url: &str,
+        full_name: &str,
+        previous_tag: Option<String>,
+        tag: String,
+    ) -> Self {
+        Self {
+            repository_url: format!("{}/{}", server_url.trim_end_matches('/'), full_name),
+            previous_tag,
+            tag,
+        }
+    }
+
+    /// Links on the server from `GITHUB_SERVER_URL`, falling back to `https://github.com`
+    pub fn from_env(full_name: &str, previous_tag: Option<String>, tag: String) -> Self {
+        let server_url = std::env::var("GITHUB_SERVER_URL")
+            .ok()
+            .filter(|url| !url.is_empty())
+            .unwrap_or_else(|| "https://github.com".to_string());
+        Self::new(&server_url, full_name, previous_tag, tag)
+    }
+
+    pub fn commit_url(&self, sha: &str) -> String {
+        format!("{}/commit/{}", self.repository_url, sha)
+    }
+
+    pub fn pull_request_url(&self, number: u64) -> String {
+        format!("{}/pull/{}", self.repository_url, number)
+    }
+
+    pub fn compare_url(&self) -> Option<String> {
+        self.previous_tag.as_ref().map(|previous_tag| {
+            format!(
+                "{}/compare/{}...{}",
+                self.repository_url, previous_tag, self.tag
+            )
+        })
+    }
+}
+
+/// Look up the pull request behind each commit, for `link_target = "pr"`.
+///
+/// Commits without an associated pull request are left out and keep their commit reference.
+pub async fn resolve_pull_request_links(
+    github_client: &dyn ScmClient,
+    repo_info: &RepositoryInfo,
+    commits: &[Commit],
+) -> std::result::Result<PullRequestLinks, ReleaseError> {
+    let mut links = PullRequestLinks::new();
+
+    for commit in commits {
+        let pull_requests = github_client
+            .get_pull_requests_for_commit(repo_info, &commit.sha)
+            .await?;
+        // Prefer the merged PR when a commit appears in several
+        let pull_request = pull_requests
+            .iter()
+            .find(|pull_request| pull_request.merged_at.is_some())
+            .or_else(|| pull_requests.first());
+        if let Some(pull_request) = pull_request {
+            links.insert(commit.sha.clone(), pull_request.number);
+        }
+    }
+
+    Ok(links)
+}
+
+/// Build the markdown body of a release from the commits in its window
+pub fn build_release_notes(
+    commits: &[Commit],
+    bump_type: &BumpType,
+    config: &Config,
+    pull_requests: &PullRequestLinks,
+    links: Option<&ReleaseLinks>,
+) -> String {
+    let mut sections = Vec::new();
+
+    if *bump_type == BumpType::Major {
+        let upgrade_guide = render_upgrade_guide(commits, &ParseOptions::from_config(config));
+        if !upgrade_guide.is_empty() {
+            sections.push(upgrade_guide);
+        }
+    }
+
+    let options = ParseOptions::from_config(config);
+    let noted: Vec<Commit> = commits
+        .iter()
+        .filter(|commit| !excluded_from_notes(commit, config, &options))
+        .cloned()
+        .collect();
+    let changes = render_changes_with_heading(&noted, config, pull_requests, links, "##");
+    if !changes.is_empty() {
+        sections.push(changes);
+    }
+
+    if config.changelog.contributors {
+        let contributors = render_contributors_section(&collect_contributors(commits));
+        if !contributors.is_empty() {
+            sections.push(contributors);
+        }
+    }
+
+    if let Some(compare_url) = links.and_then(ReleaseLinks::compare_url) {
+        sections.push(format!("**Full Changelog**: {}\n", compare_url));
+    }
+
+    sections.join("\n")
+}
+
+/// Whether `commit` is left out of the release notes: its type is in `exclude_types`, or
+/// without that list, it doesn't bump the version (e.g. `docs`, `chore`). Breaking changes are
+/// always kept.
+fn excluded_from_notes(commit: &Commit, config: &Config, options: &ParseOptions) -> bool {
+    let Ok(parsed) = ConventionalCommit::parse_with(&commit.message, options) else {
+        return false;
+    };
+    if parsed.breaking_change {
+        return false;
+    }
+    match &config.release.exclude_types {
+        Some(exclude_types) => exclude_types.contains(&parsed.commit_type),
+        None => parsed.bump_type_with(options) == BumpType::None,
+    }
+}
+
+/// Build the notes of the first release, whose window is the entire history.
+///
+/// Depending on `first_release_changelog` this is the full notes, a short summary, or nothing.
+pub fn build_first_release_notes(
+    commits: &[Commit],
+    bump_type: &BumpType,
+    config: &Config,
+    pull_requests: &PullRequestLinks,
+    links: Option<&ReleaseLinks>,
+) -> std::result::Result<String, ReleaseError> {
+    Ok(match config.changelog.first_release_changelog()? {
+        FirstReleaseChangelog::Full => {
+            build_release_notes(commits, bump_type, config, pull_requests, links)
+        }
+        FirstReleaseChangelog::Summary => format!(
+            "## 🎉 Initial Release\n\nThis is the first release, built from {} commit(s).\n",
+            commits.len()
+        ),
+        FirstReleaseChangelog::Empty => String::new(),
+    })
+}
+
+/// Section listing every breaking commit, ahead of all others
+const BREAKING_SECTION: &str = "⚠️ Breaking Changes";
+
+/// Render the released commits as changelog entries grouped into one section per type.
+///
+/// Breaking commits of any type are listed first in their own section. Commits whose scope is
+/// listed in `scope_sections` go to that section instead of their type's. Other types without
+/// a section (`chore`, `docs`, ... unless configured in `[changelog.sections]`) and unparseable
+/// messages are left out.
+pub fn render_changes(
+    commits: &[Commit],
+    config: &Config,
+    pull_requests: &PullRequestLinks,
+) -> String {
+    render_changes_with_heading(commits, config, pull_requests, None, "##")
+}
+
+/// `render_changes` with section titles under the given markdown `heading` (e.g. `"###"`),
+/// linking each entry's reference when `links` are given
+pub fn render_changes_with_heading(
+    commits: &[Commit],
+    config: &Config,
+    pull_requests: &PullRequestLinks,
+    links: Option<&ReleaseLinks>,
+    heading: &str,
+) -> String {
+    let options = ParseOptions::from_config(config);
+    let parsed: Vec<(&Commit, ConventionalCommit)> = commits
+        .iter()
+        .filter_map(|commit| {
+            let parsed = ConventionalCommit::parse_with(&commit.message, &options).ok()?;
+            Some((commit, parsed))
+        })
+        .collect();
+
+    // Scope sections come next and claim their commits regardless of type
+    let scope_sections = &config.changelog.scope_sections;
+    let mut scope_titles: Vec<&str> = scope_sections.values().map(String::as_str).collect();
+    scope_titles.sort();
+    scope_titles.dedup();
+    let type_sections = config.changelog.type_sections();
+    let mut titles = vec![BREAKING_SECTION];
+    titles.extend(scope_titles);
+    titles.extend(type_sections.iter().map(|(_, title)| *title));
+
+    let section_for = |parsed: &ConventionalCommit| -> Option<&str> {
+        if parsed.breaking_change {
+            return Some(BREAKING_SECTION);
+        }
+        if let Some(title) = parsed
+            .scope
+            .as_ref()
+            .and_then(|scope| scope_sections.get(scope))
+        {
+            return Some(title);
+        }
+        type_sections
+            .iter()
+            .find(|(commit_type, _)| parsed.commit_type == *commit_type)
+            .map(|(_, title)| *title)
+    };
+
+    let mut sections: Vec<String> = Vec::new();
+    let mut rendered_titles: Vec<&str> = Vec::new();
+    for title in titles {
+        if rendered_titles.contains(&title) {
+            continue;
+        }
+        rendered_titles.push(title);
+
+        // With dedupe on, repeated type+scope+description (e.g. cherry-picks) are listed once
+        let mut seen = HashSet::new();
+        let entries: Vec<String> = parsed
+            .iter()
+            .filter(|(_, parsed)| section_for(parsed) == Some(title))
+            .filter(|(_, parsed)| {
+                !config.changelog.dedupe
+                    || seen.insert((&parsed.commit_type, &parsed.scope, &parsed.description))
+            })
+            .map(|(commit, parsed)| {
+                let pull_request = pull_requests.get(&commit.sha).copied();
+                render_entry(
+                    commit,
+                    parsed,
+                    config.changelog.max_description_len,
+                    pull_request,
+                    links,
+                )
+            })
+            .collect();
+
+        if !entries.is_empty() {
+            sections.push(format!("{} {}\n\n{}", heading, title, entries.concat()));
+        }
+    }
+
+    sections.join("\n")
+}
+
+/// Render a single `- **scope:** description (sha)` changelog line, or `(#123)` when the
+/// commit's pull request is known. With `links` the reference becomes a markdown link.
+fn render_entry(
+    commit: &Commit,
+    parsed: &ConventionalCommit,
+    max_description_len: Option<usize>,
+    pull_request: Option<u64>,
+    links: Option<&ReleaseLinks>,
+) -> String {
+    let description = match max_description_len {
+        Some(max_len) => truncate_description(&parsed.description, max_len),
+        None => parsed.description.clone(),
+    };
+    let short_sha = &commit.sha[..commit.sha.len().min(7)];
+    let reference = match (pull_request, links) {
+        (Some(number), Some(links)) => {
+            format!("[#{}]({})", number, links.pull_request_url(number))
+        }
+        (Some(number), None) => format!("#{}", number),
+        (None, Some(links)) => format!("[{}]({})", short_sha, links.commit_url(&commit.sha)),
+        (None, None) => short_sha.to_string(),
+    };
+
+    match &parsed.scope {
+        Some(scope) => format!("- **{}:** {} ({})\n", scope, description, reference),
+        None => format!("- {} ({})\n", description, reference),
+    }
+}
+
+/// Shorten `description` to at most `max_len` characters, marking the cut with an ellipsis
+pub fn truncate_description(description: &str, max_len: usize) -> String {
+    if description.chars().count() <= max_len {
+        return description.to_string();
+    }
+
+    let truncated: String = description.chars().take(max_len).collect();
+    format!("{}…", truncated.trim_end())
+}
+
+/// Render an "Upgrade Guide" section from the `BREAKING CHANGE:` footers of the commits.
+///
+/// Returns an empty string when no commit carries a breaking-change description.
+pub fn render_upgrade_guide(commits: &[Commit], options: &ParseOptions) -> String {
+    let mut entries = Vec::new();
+    for commit in commits {
+        let Ok(parsed) = ConventionalCommit::parse_with(&commit.message, options) else {
+            continue;
+        };
+        let short_sha = &commit.sha[..commit.sha.len().min(7)];
+        for description in &parsed.breaking_descriptions {
+            entries.push(match &parsed.scope {
+                Some(scope) => format!("- **{}:** {} ({})\n", scope, description, short_sha),
+                None => format!("- {} ({})\n", description, short_sha),
+            });
+        }
+    }
+
+    if entries.is_empty() {
+        return String::new();
+    }
+
+    let mut section = String::from("## ⚠️ Upgrade Guide\n\n");
+    section.push_str("This release contains breaking changes:\n\n");
+    for entry in entries {
+        section.push_str(&entry);
+    }
+    section
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scm::github::GitHubClient;
+
+    fn commit(sha: &str, message: &str) -> Commit {
+        Commit {
+            sha: sha.to_string(),
+            message: message.to_string(),
+            author_name: "Test User".to_string(),
+            author_email: "test@example.com".to_string(),
+        }
+    }
+
+    fn breaking_commits() -> Vec<Commit> {
+        vec![
+            commit(
+                "aaaaaaa1111111",
+                "feat(api)!: new auth\n\nBREAKING CHANGE: tokens must be passed as Bearer",
+            ),
+            commit("bbbbbbb2222222", "fix: small fix"),
+            commit(
+                "ccccccc3333333",
+                "refactor!: drop config v1\n\nBREAKING CHANGE: migrate config files to v2",
+            ),
+        ]
+    }
+
+    #[test]
+    fn test_upgrade_guide_lists_all_breaking_changes() {
+        let notes = build_release_notes(
+            &breaking_commits(),
+            &BumpType::Major,
+            &Config::default(),
+            &PullRequestLinks::new(),
+            None,
+        );
+
+        assert!(notes.starts_with("## ⚠️ Upgrade Guide\n"));
+        assert!(notes.contains("- **api:** tokens must be passed as Bearer (aaaaaaa)\n"));
+        assert!(notes.contains("- migrate config files to v2 (ccccccc)\n"));
+
+        let guide = render_upgrade_guide(&breaking_commits(), &ParseOptions::default());
+        assert!(!guide.contains("small fix"));
+    }
+
+    #[test]
+    fn test_changes_grouped_by_type() {
+        let commits = vec![
+            commit("aaaaaaa1111111", "feat(api): add tokens"),
+            commit("bbbbbbb2222222", "fix: small fix"),
+            commit("ccccccc3333333", "chore: bump deps"),
+        ];
+        let notes = render_changes(&commits, &Config::default(), &PullRequestLinks::new());
+
+        assert_eq!(
+            notes,
+            "## Features\n\n- **api:** add tokens (aaaaaaa)\n\
+             \n## Bug Fixes\n\n- small fix (bbbbbbb)\n"
+        );
+    }
+
+    #[test]
+    fn test_breaking_changes_get_their_own_section() {
+        let commits = vec![
+            commit("aaaaaaa1111111", "fix: small fix"),
+            commit("bbbbbbb2222222", "feat(api)!: require tokens"),
+            commit("ccccccc3333333", "ci: cache builds"),
+            commit(
+                "ddddddd4444444",
+                "refactor: drop config v1\n\nBREAKING CHANGE: migrate to v2",
+            ),
+            commit("eeeeeee5555555", "feat: add export"),
+            commit("fffffff6666666", "not conventional"),
+        ];
+        let notes = render_changes(&commits, &Config::default(), &PullRequestLinks::new());
+
+        assert_eq!(
+            notes,
+            "## ⚠️ Breaking Changes\n\n- **api:** require tokens (bbbbbbb)\n\
+             - drop config v1 (ddddddd)\n\
+             \n## Features\n\n- add export (eeeeeee)\n\
+             \n## Bug Fixes\n\n- small fix (aaaaaaa)\n"
+        );
+    }
+
+    #[test]
+    fn test_scope_sections_take_precedence_over_types() {
+        let commits = vec![
+            commit("aaaaaaa1111111", "fix(security): escape user input"),
+            commit("bbbbbbb2222222", "fix(ui): align buttons"),
+            commit("ccccccc3333333", "chore(security): rotate keys"),
+        ];
+        let mut config = Config::default();
+        config
+            .changelog
+            .scope_sections
+            .insert("security".to_string(), "Security".to_string());
+
+        let notes = render_changes(&commits, &config, &PullRequestLinks::new());
+
+        assert_eq!(
+            notes,
+            "## Security\n\n- **security:** escape user input (aaaaaaa)\n\
+             - **security:** rotate keys (ccccccc)\n\
+             \n## Bug Fixes\n\n- **ui:** align buttons (bbbbbbb)\n"
+        );
+    }
+
+    #[test]
+    fn test_custom_type_sections_and_order() {
+        let commits = vec![
+            commit("aaaaaaa1111111", "feat: add export"),
+            commit("bbbbbbb2222222", "fix: small fix"),
+            commit("ccccccc3333333", "perf: cache lookups"),
+            commit("ddddddd4444444", "docs: explain setup"),
+            commit("eeeeeee5555555", "chore: bump deps"),
+        ];
+        let config: Config = toml::from_str(
+            r#"[version]
+
+[changelog]
+section_order = ["perf", "fix"]
+
+[changelog.sections]
+feat = "🚀 Features"
+fix = "🐛 Fixes"
+perf = "⚡ Performance"
+docs = "📚 Documentation"
+"#,
+        )
+        .unwrap();
+        config.validate().unwrap();
+
+        let notes = render_changes(&commits, &config, &PullRequestLinks::new());
+
+        // Listed types first, then the rest in the built-in order; unmapped chore is left out
+        assert_eq!(
+            notes,
+            "## ⚡ Performance\n\n- cache lookups (ccccccc)\n\
+             \n## 🐛 Fixes\n\n- small fix (bbbbbbb)\n\
+             \n## 🚀 Features\n\n- add export (aaaaaaa)\n\
+             \n## 📚 Documentation\n\n- explain setup (ddddddd)\n"
+        );
+    }
+
+    #[test]
+    fn test_release_notes_exclude_types() {
+        let commits = vec![
+            commit("aaaaaaa1111111", "feat: add export"),
+            commit("bbbbbbb2222222", "docs: explain setup"),
+            commit("ccccccc3333333", "chore!: drop node 16"),
+            commit("ddddddd4444444", "fix: small fix"),
+        ];
+        let mut config = Config::default();
+        config.changelog.sections = Some(HashMap::from([
+            ("feat".to_string(), "Features".to_string()),
+            ("fix".to_string(), "Bug Fixes".to_string()),
+            ("docs".to_string(), "Documentation".to_string()),
+        ]));
+        let notes = |config: &Config| {
+            build_release_notes(
+                &commits,
+                &BumpType::Minor,
+                config,
+                &PullRequestLinks::new(),
+                None,
+            )
+        };
+
+        // By default only non-bumping commits are left out; a breaking chore still bumps
+        let body = notes(&config);
+        assert!(!body.contains("explain setup"), "{}", body);
+        assert!(body.contains("- drop node 16 (ccccccc)"), "{}", body);
+        assert!(body.contains("- add export (aaaaaaa)"), "{}", body);
+
+        config.release.exclude_types = Some(vec!["fix".to_string(), "chore".to_string()]);
+        let body = notes(&config);
+        assert!(
+            body.contains("## Documentation\n\n- explain setup (bbbbbbb)"),
+            "{}",
+            body
+        );
+        assert!(!body.contains("small fix"), "{}", body);
+        // Breaking changes stay even when their type is excluded
+        assert!(
+            body.contains("## ⚠️ Breaking Changes\n\n- drop node 16 (ccccccc)"),
+            "{}",
+            body
+        );
+
+        // The changelog file keeps every type with a section
+        let changelog = render_changes(&commits, &config, &PullRequestLinks::new());
+        assert!(changelog.contains("small fix"), "{}", changelog);
+    }
+
+    #[test]
+    fn test_section_order_needs_a_title() {
+        let mut config = Config::default();
+        config.changelog.section_order = Some(vec!["fix".to_string(), "docs".to_string()]);
+        let error = config.validate().unwrap_err().to_string();
+        assert!(error.contains("\"docs\""), "{}", error);
+    }
+
+    #[tokio::test]
+    async fn test_changes_link_to_pull_requests() {
+        let mut server = mockito::Server::new_async().await;
+        let with_pr = server
+            .mock("GET", "/repos/owner/repo/commits/aaaaaaa1111111/pulls")
+            .with_status(200)
+            .with_body(r#"[{"number": 123, "title": "feat: add tokens", "body": null, "merged_at": "2024-05-01T12:00:00Z"}]"#)
+            .create_async()
+            .await;
+        let without_pr = server
+            .mock("GET", "/repos/owner/repo/commits/bbbbbbb2222222/pulls")
+            .with_status(200)
+            .with_body("[]")
+            .create_async()
+            .await;
+
+        let client = GitHubClient::new("test-token".to_string())
+            .unwrap()
+            .with_base_url(server.url());
+        let repo_info = RepositoryInfo {
+            full_name: "owner/repo".to_string(),
+            default_branch: "main".to_string(),
+        };
+        let commits = vec![
+            commit("aaaaaaa1111111", "feat: add tokens"),
+            commit("bbbbbbb2222222", "fix: direct push"),
+        ];
+
+        let links = resolve_pull_request_links(&client, &repo_info, &commits)
+            .await
+            .unwrap();
+        with_pr.assert_async().await;
+        without_pr.assert_async().await;
+
+        let notes = render_changes(&commits, &Config::default(), &links);
+        assert!(notes.contains("- add tokens (#123)\n"));
+        assert!(notes.contains("- direct push (bbbbbbb)\n"));
+    }
+
+    #[test]
+    fn test_entries_link_to_commits_and_compare() {
+        let commits = vec![
+            commit("aaaaaaa1111111", "feat: add tokens"),
+            commit("bbbbbbb2222222", "fix: direct push"),
+        ];
+        let links = ReleaseLinks::new(
+            "https://github.example.com/",
+            "owner/repo",
+            Some("v1.0.0".to_string()),
+            "v1.1.0".to_string(),
+        );
+        let pull_requests = PullRequestLinks::from([("aaaaaaa1111111".to_string(), 7)]);
+
+        let notes = build_release_notes(
+            &commits,
+            &BumpType::Minor,
+            &Config::default(),
+            &pull_requests,
+            Some(&links),
+        );
+
+        assert!(
+            notes.contains("- add tokens ([#7](https://github.example.com/owner/repo/pull/7))\n")
+        );
+        assert!(notes.contains(
+            "- direct push ([bbbbbbb](https://github.example.com/owner/repo/commit/bbbbbbb2222222))\n"
+        ));
+        assert!(notes.ends_with(
+            "**Full Changelog**: https://github.example.com/owner/repo/compare/v1.0.0...v1.1.0\n"
+        ));
+
+        let first = ReleaseLinks::new(
+            "https://github.com",
+            "owner/repo",
+            None,
+            "v0.1.0".to_string(),
+        );
+        assert_eq!(first.compare_url(), None);
+    }
+
+    #[test]
+    fn test_first_release_summary() {
+        let commits = vec![
+            commit("aaaaaaa1111111", "feat: add tokens"),
+            commit("bbbbbbb2222222", "fix: small fix"),
+        ];
+        let links = PullRequestLinks::new();
+
+        let notes =
+            build_first_release_notes(&commits, &BumpType::Minor, &Config::default(), &links, None)
+                .unwrap();
+        assert_eq!(
+            notes,
+            "## 🎉 Initial Release\n\nThis is the first release, built from 2 commit(s).\n"
+        );
+
+        let mut config = Config::default();
+        config.changelog.first_release_changelog = Some("full".to_string());
+        let notes =
+            build_first_release_notes(&commits, &BumpType::Minor, &config, &links, None).unwrap();
+        assert!(notes.contains("## Features"));
+
+        config.changelog.first_release_changelog = Some("empty".to_string());
+        let notes =
+            build_first_release_notes(&commits, &BumpType::Minor, &config, &links, None).unwrap();
+        assert_eq!(notes, "");
+    }
+
+    #[test]
+    fn test_changes_dedupe_identical_entries() {
+        let commits = vec![
+            commit("aaaaaaa1111111", "fix(api): handle timeouts"),
+            commit("bbbbbbb2222222", "fix(api): handle timeouts"),
+            commit("ccccccc3333333", "fix: handle timeouts"),
+        ];
+        let mut config = Config::default();
+        config.changelog.dedupe = true;
+
+        let notes = render_changes(&commits, &config, &PullRequestLinks::new());
+        assert_eq!(
+            notes,
+            "## Bug Fixes\n\n- **api:** handle timeouts (aaaaaaa)\n- handle timeouts (ccccccc)\n"
+        );
+
+        let notes = render_changes(&commits, &Config::default(), &PullRequestLinks::new());
+        assert!(notes.contains("(bbbbbbb)"));
+    }
+
+    #[test]
+    fn test_changes_truncate_long_descriptions() {
+        let commits = vec![commit(
+            "aaaaaaa1111111",
+            "feat: support an extremely long description that nobody wants to read",
+        )];
+        let mut config = Config::default();
+        config.changelog.max_description_len = Some(20);
+
+        let notes = render_changes(&commits, &config, &PullRequestLinks::new());
+        assert!(notes.contains("- support an extremely… (aaaaaaa)\n"));
+        assert_eq!(truncate_description("short", 20), "short");
+    }
+
+    #[test]
+    fn test_upgrade_guide_lists_every_footer_of_a_commit() {
+        let commits = vec![commit(
+            "ddddddd4444444",
+            "feat!: overhaul\n\nBREAKING CHANGE: first break\nBREAKING CHANGE: second break",
+        )];
+        let guide = render_upgrade_guide(&commits, &ParseOptions::default());
+
+        assert!(guide.contains("- first break (ddddddd)\n- second break (ddddddd)\n"));
+    }
+
+    #[test]
+    fn test_upgrade_guide_only_for_major_releases() {
+        let notes = build_release_notes(
+            &breaking_commits(),
+            &BumpType::Minor,
+            &Config::default(),
+            &PullRequestLinks::new(),
+            None,
+        );
+        assert!(!notes.contains("Upgrade Guide"));
+    }
+
+    #[test]
+    fn test_upgrade_guide_omitted_without_descriptions() {
+        let commits = vec![commit("aaaaaaa1111111", "feat!: breaking without footer")];
+        assert_eq!(render_upgrade_guide(&commits, &ParseOptions::default()), "");
+    }
+}
@@ -1,2 +1,95 @@
 pub mod git;
 pub mod github;
+
+use crate::config::Config;
+use crate::error::ReleaseError;
+use async_trait::async_trait;
+use semver::Version;
+use std::path::Path;
+
+/// A repository on the hosting service
+#[derive(Debug, Clone, PartialEq)]
+pub struct RepositoryInfo {
+    /// `owner/name`
+    pub full_name: String,
+    pub default_branch: String,
+}
+
+/// A tag as listed by the hosting service
+#[derive(Debug, Clone, PartialEq)]
+pub struct Tag {
+    pub name: String,
+    /// The commit the tag points at
+    pub sha: String,
+}
+
+/// A release published on the hosting service
+#[derive(Debug, Clone, PartialEq)]
+pub struct Release {
+    pub id: u64,
+    pub tag_name: String,
+    pub name: Option<String>,
+    pub body: Option<String>,
+    pub draft: bool,
+    pub prerelease: bool,
+    /// Web page of the release
+    pub url: String,
+    /// Where assets are uploaded to, as handed out by the service
+    pub upload_url: String,
+}
+
+/// A pull (or merge) request
+#[derive(Debug, Clone, PartialEq)]
+pub struct PullRequest {
+    pub number: u64,
+    pub title: String,
+    pub body: Option<String>,
+    /// When it was merged, `None` while it is open or when it was closed unmerged
+    pub merged_at: Option<String>,
+}
+
+/// The hosting service a repository's releases are published to.
+///
+/// The pipeline only talks to the service through this trait, so another forge can be
+/// supported by implementing it.
+#[async_trait(?Send)]
+pub trait ScmClient {
+    /// Look up a repository by its `owner/name`
+    async fn get_repository_info(&self, repo: &str) -> Result<RepositoryInfo, ReleaseError>;
+
+    async fn get_tags(&self, repo: &RepositoryInfo) -> Result<Vec<Tag>, ReleaseError>;
+
+    /// The release for `tag_name`, or `None` when there is none
+    async fn get_release_by_tag(
+        &self,
+        repo: &RepositoryInfo,
+        tag_name: &str,
+    ) -> Result<Option<Release>, ReleaseError>;
+
+    /// The repository's releases, most recent first
+    async fn list_releases(&self, repo: &RepositoryInfo) -> Result<Vec<Release>, ReleaseError>;
+
+    /// The pull requests a commit belongs to
+    async fn get_pull_requests_for_commit(
+        &self,
+        repo: &RepositoryInfo,
+        commit_sha: &str,
+    ) -> Result<Vec<PullRequest>, ReleaseError>;
+
+    async fn create_release(
+        &self,
+        repo: &RepositoryInfo,
+        version: &Version,
+        config: &Config,
+        target_commit_sha: &str,
+        release_body: String,
+    ) -> Result<Release, ReleaseError>;
+
+    /// Attach the file at `path` to `release` under `name`
+    async fn upload_release_asset(
+        &self,
+        release: &Release,
+        path: &Path,
+        name: &str,
+    ) -> Result<(), ReleaseError>;
+}
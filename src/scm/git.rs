@@ -1,37 +1,247 @@
-use git2::{Config as GitConfig, Repository};
-use serde::Deserialize;
-use std::error::Error;
-
-#[derive(Debug, Deserialize)]
-pub struct Tag {
-    pub name: String,
-    pub commit: GitCommit,
+use crate::error::ReleaseError;
+use git2::{Config as GitConfig, ConfigLevel, Repository};
+use std::path::Path;
+
+/// Which git config file the repository is added to `safe.directory` in before opening it
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SafeDirectoryScope {
+    Global,
+    System,
+    None,
 }
 
-#[derive(Debug, Deserialize)]
-pub struct GitCommit {
-    pub sha: String,
+impl SafeDirectoryScope {
+    /// Read the scope from `SAFE_DIRECTORY_SCOPE`, defaulting to `global`
+    pub fn from_env() -> Result<Self, ReleaseError> {
+        match std::env::var("SAFE_DIRECTORY_SCOPE") {
+            Ok(value) if !value.trim().is_empty() => Self::parse(&value),
+            _ => Ok(Self::Global),
+        }
+    }
+
+    pub fn parse(value: &str) -> Result<Self, ReleaseError> {
+        match value.trim().to_lowercase().as_str() {
+            "global" => Ok(Self::Global),
+            "system" => Ok(Self::System),
+            "none" => Ok(Self::None),
+            other => Err(ReleaseError::Config(format!(
+                "Invalid safe.directory scope {:?}: expected \"global\", \"system\" or \"none\"",
+                other
+            ))),
+        }
+    }
 }
 
-/// Safely open a git repository with proper safe directory configuration
-pub fn open_repository(path: &str) -> Result<Repository, Box<dyn Error>> {
-    // First, configure git to trust any directory
-    let mut git_config = GitConfig::open_default()?;
-    git_config.set_str("safe.directory", "*")?;
+/// Add the absolute `path` as a `safe.directory` entry at the level of `git_config` selected
+/// by `scope`, unless that level already trusts it (or every directory)
+pub fn trust_directory(
+    git_config: &GitConfig,
+    scope: SafeDirectoryScope,
+    path: &Path,
+) -> Result<(), ReleaseError> {
+    let level = match scope {
+        SafeDirectoryScope::Global => ConfigLevel::Global,
+        SafeDirectoryScope::System => ConfigLevel::System,
+        SafeDirectoryScope::None => return Ok(()),
+    };
+    let path = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+    let path = path.to_string_lossy();
 
-    // Now open the repository
-    let repo =
-        Repository::open(path).map_err(|e| format!("Failed to open git repository: {}", e))?;
+    let add_entry = || -> Result<(), git2::Error> {
+        let mut config = git_config.open_level(level)?;
+        let mut trusted = false;
+        if let Ok(entries) = config.multivar("safe.directory", None) {
+            entries.for_each(|entry| {
+                trusted |= matches!(entry.value(), Some(value) if value == "*" || value == path);
+            })?;
+        }
+        if !trusted {
+            // A pattern matching no existing value appends a new entry
+            config.set_multivar("safe.directory", "^$", &path)?;
+        }
+        Ok(())
+    };
+    add_entry().map_err(|e| {
+        ReleaseError::Git(format!(
+            "Failed to set safe.directory in the {:?} git config: {}",
+            scope, e
+        ))
+    })?;
+
+    Ok(())
+}
+
+/// Open the git repository at `path`. A checkout owned by another user only opens once
+/// [`trust_directory`] has added it to `safe.directory`.
+pub fn open_repository(path: impl AsRef<Path>) -> Result<Repository, ReleaseError> {
+    let repo = Repository::open(path.as_ref())
+        .map_err(|e| ReleaseError::Git(format!("Failed to open git repository: {}", e)))?;
 
     Ok(repo)
 }
 
+/// Extract `owner/name` from a GitHub remote URL.
+///
+/// Handles `https://github.com/owner/name(.git)`, `git@github.com:owner/name(.git)`
+/// and `ssh://git@github.com/owner/name(.git)` forms, on any host.
+pub fn parse_repository_from_url(url: &str) -> Option<String> {
+    let url = url.trim();
+    let path = if let Some((_, rest)) = url.split_once("://") {
+        // https://host/owner/name or ssh://git@host/owner/name
+        rest.split_once('/')?.1
+    } else {
+        // scp-like syntax: git@host:owner/name
+        url.split_once(':')?.1
+    };
+
+    let path = path.trim_end_matches('/');
+    let path = path.strip_suffix(".git").unwrap_or(path);
+    let mut parts = path.rsplitn(3, '/');
+    let name = parts.next().filter(|name| !name.is_empty())?;
+    let owner = parts.next().filter(|owner| !owner.is_empty())?;
+
+    Some(format!("{}/{}", owner, name))
+}
+
+/// Determine `owner/name` from the URL of the given remote
+pub fn repository_from_remote(repo: &Repository, remote_name: &str) -> Option<String> {
+    let remote = repo.find_remote(remote_name).ok()?;
+    parse_repository_from_url(remote.url()?)
+}
+
+/// Fail early with an actionable message when `path` is not a git repository
+pub fn ensure_git_repository(path: &Path) -> Result<(), ReleaseError> {
+    if !path.is_dir() {
+        return Err(ReleaseError::Git(format!(
+            "Working directory {:?} does not exist or is not a directory",
+            path
+        )));
+    }
+
+    open_repository(path).map_err(|e| {
+        ReleaseError::Git(format!(
+            "Working directory {:?} is not a git repository ({}). \
+             Check that the repository is checked out (e.g. with actions/checkout) \
+             and that --working-directory points at its root",
+            path, e
+        ))
+    })?;
+
+    Ok(())
+}
+
+/// A warning when `repo` is a shallow clone, whose history may end before the previous
+/// release so that commits are missed and the bump comes out too small
+pub fn shallow_clone_warning(repo: &Repository) -> Option<String> {
+    repo.is_shallow().then(|| {
+        "The repository is a shallow clone, so commits since the previous release may be \
+         missing from the analysis. Check it out with full history and tags, e.g. \
+         `fetch-depth: 0` and `fetch-tags: true` for actions/checkout"
+            .to_string()
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use git2::{Config as GitConfig, Repository, Signature};
+    use git2::{Config as GitConfig, ConfigLevel, Repository, Signature};
     use tempfile::TempDir;
 
+    #[test]
+    fn test_safe_directory_scope_parse() {
+        assert_eq!(
+            SafeDirectoryScope::parse("global").unwrap(),
+            SafeDirectoryScope::Global
+        );
+        assert_eq!(
+            SafeDirectoryScope::parse("System").unwrap(),
+            SafeDirectoryScope::System
+        );
+        assert_eq!(
+            SafeDirectoryScope::parse("none").unwrap(),
+            SafeDirectoryScope::None
+        );
+        assert!(SafeDirectoryScope::parse("local").is_err());
+    }
+
+    /// Values of `safe.directory` in the config file at `path`
+    fn safe_directories(path: &Path) -> Vec<String> {
+        let config = GitConfig::open(path).unwrap();
+        let mut values = Vec::new();
+        if let Ok(entries) = config.multivar("safe.directory", None) {
+            entries
+                .for_each(|entry| values.push(entry.value().unwrap().to_string()))
+                .unwrap();
+        }
+        values
+    }
+
+    #[test]
+    fn test_trust_directory_writes_requested_level() {
+        let temp_dir = TempDir::new().expect("Failed to create temp directory");
+        let repo_path = temp_dir.path().canonicalize().unwrap();
+        let expected = vec![repo_path.to_string_lossy().to_string()];
+        let global_path = temp_dir.path().join("global.gitconfig");
+        let system_path = temp_dir.path().join("system.gitconfig");
+        std::fs::write(&global_path, "").unwrap();
+        std::fs::write(&system_path, "").unwrap();
+
+        let mut git_config = GitConfig::new().unwrap();
+        git_config
+            .add_file(&global_path, ConfigLevel::Global, false)
+            .unwrap();
+        git_config
+            .add_file(&system_path, ConfigLevel::System, false)
+            .unwrap();
+
+        trust_directory(&git_config, SafeDirectoryScope::System, &repo_path).unwrap();
+        assert_eq!(safe_directories(&system_path), expected);
+        assert!(safe_directories(&global_path).is_empty());
+
+        trust_directory(&git_config, SafeDirectoryScope::Global, &repo_path).unwrap();
+        assert_eq!(safe_directories(&global_path), expected);
+    }
+
+    #[test]
+    fn test_trust_directory_scopes_to_the_path_and_keeps_existing_entries() {
+        let temp_dir = TempDir::new().expect("Failed to create temp directory");
+        let global_path = temp_dir.path().join("global.gitconfig");
+        std::fs::write(&global_path, "[safe]\n\tdirectory = /srv/other\n").unwrap();
+        let repo_path = temp_dir.path().canonicalize().unwrap();
+
+        let mut git_config = GitConfig::new().unwrap();
+        git_config
+            .add_file(&global_path, ConfigLevel::Global, false)
+            .unwrap();
+
+        // Opening the same repository twice adds a single entry and never the wildcard
+        trust_directory(&git_config, SafeDirectoryScope::Global, &repo_path).unwrap();
+        trust_directory(&git_config, SafeDirectoryScope::Global, &repo_path).unwrap();
+
+        assert_eq!(
+            safe_directories(&global_path),
+            vec![
+                "/srv/other".to_string(),
+                repo_path.to_string_lossy().to_string()
+            ]
+        );
+    }
+
+    #[test]
+    fn test_trust_directory_none_writes_nothing() {
+        let temp_dir = TempDir::new().expect("Failed to create temp directory");
+        let global_path = temp_dir.path().join("global.gitconfig");
+        std::fs::write(&global_path, "").unwrap();
+
+        let mut git_config = GitConfig::new().unwrap();
+        git_config
+            .add_file(&global_path, ConfigLevel::Global, false)
+            .unwrap();
+
+        trust_directory(&git_config, SafeDirectoryScope::None, temp_dir.path()).unwrap();
+        assert!(safe_directories(&global_path).is_empty());
+    }
+
     fn create_test_repo() -> TempDir {
         let temp_dir = TempDir::new().expect("Failed to create temp directory");
         let repo = Repository::init(temp_dir.path()).expect("Failed to init repository");
@@ -64,37 +274,71 @@ mod tests {
         );
     }
 
-    #[test]
-    fn test_open_repository_sets_safe_directory() {
-        let temp_dir = create_test_repo();
-        let repo_path = temp_dir.path().to_str().unwrap();
-
-        // Open the repository using our function
-        let _opened_repo = open_repository(repo_path).expect("Failed to open repository");
-
-        // Verify that the global git config now has safe.directory set to *
-        let git_config = GitConfig::open_default().expect("Failed to open git config");
-        let safe_directory = git_config.get_string("safe.directory");
-
-        // Note: This might not always work depending on git version and test environment
-        // but it's a good sanity check when possible
-        if let Ok(value) = safe_directory {
-            assert_eq!(value, "*", "safe.directory should be set to '*'");
-        }
-    }
-
     #[test]
     fn test_open_repository_invalid_path() {
         let result = open_repository("/non/existent/path");
         assert!(result.is_err(), "Should fail for non-existent path");
 
-        let error_msg = result.err().unwrap().to_string();
+        let error = result.err().unwrap();
+        assert!(matches!(error, ReleaseError::Git(_)));
+        let error_msg = error.to_string();
         assert!(
             error_msg.contains("Failed to open git repository"),
             "Error should mention repository opening failure"
         );
     }
 
+    #[test]
+    fn test_parse_repository_from_https_url() {
+        assert_eq!(
+            parse_repository_from_url("https://github.com/NicoKNL/conventional-release-action.git"),
+            Some("NicoKNL/conventional-release-action".to_string())
+        );
+        assert_eq!(
+            parse_repository_from_url("https://github.com/owner/name"),
+            Some("owner/name".to_string())
+        );
+    }
+
+    #[test]
+    fn test_parse_repository_from_ssh_url() {
+        assert_eq!(
+            parse_repository_from_url("git@github.com:owner/name.git"),
+            Some("owner/name".to_string())
+        );
+        assert_eq!(
+            parse_repository_from_url("ssh://git@github.com/owner/name.git"),
+            Some("owner/name".to_string())
+        );
+        assert_eq!(parse_repository_from_url("not-a-url"), None);
+    }
+
+    #[test]
+    fn test_repository_from_remote() {
+        let temp_dir = create_test_repo();
+        let repo = open_repository(temp_dir.path().to_str().unwrap()).unwrap();
+        repo.remote("origin", "git@github.com:owner/name.git")
+            .unwrap();
+
+        assert_eq!(
+            repository_from_remote(&repo, "origin"),
+            Some("owner/name".to_string())
+        );
+        assert_eq!(repository_from_remote(&repo, "upstream"), None);
+    }
+
+    #[test]
+    fn test_ensure_git_repository() {
+        let temp_dir = create_test_repo();
+        assert!(ensure_git_repository(temp_dir.path()).is_ok());
+
+        let plain_dir = TempDir::new().expect("Failed to create temp directory");
+        let error_msg = ensure_git_repository(plain_dir.path())
+            .unwrap_err()
+            .to_string();
+        assert!(error_msg.contains("is not a git repository"));
+    }
+
     #[test]
     fn test_open_repository_current_directory() {
         // This test assumes we're running in a git repository (which we are)
@@ -108,4 +352,25 @@ mod tests {
         // Verify we can perform basic git operations
         assert!(repo.head().is_ok(), "Should be able to access HEAD");
     }
+
+    #[test]
+    fn test_shallow_clone_warning() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo = Repository::init(temp_dir.path()).unwrap();
+        let sig = Signature::now("Test User", "test@example.com").unwrap();
+        let tree = repo
+            .find_tree(repo.index().unwrap().write_tree().unwrap())
+            .unwrap();
+        let oid = repo
+            .commit(Some("HEAD"), &sig, &sig, "feat: initial", &tree, &[])
+            .unwrap();
+        assert_eq!(shallow_clone_warning(&repo), None);
+
+        // A shallow clone records the commits its history was cut off at
+        std::fs::write(repo.path().join("shallow"), format!("{}\n", oid)).unwrap();
+        let repo = Repository::open(temp_dir.path()).unwrap();
+        let warning = shallow_clone_warning(&repo).unwrap();
+        assert!(warning.contains("fetch-depth: 0"), "{}", warning);
+        assert!(warning.contains("fetch-tags: true"), "{}", warning);
+    }
 }
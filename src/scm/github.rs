@@ -1,24 +1,73 @@
 use reqwest::{
-    header::{HeaderMap, HeaderValue, AUTHORIZATION, USER_AGENT},
-    Client,
+    header::{HeaderMap, HeaderValue, AUTHORIZATION, CONTENT_TYPE, LINK, RETRY_AFTER, USER_AGENT},
+    Client, RequestBuilder, Response, StatusCode,
 };
 
 use crate::config::Config;
-use crate::scm::git::Tag;
+use crate::error::ReleaseError;
+use crate::logging;
+use crate::scm::{PullRequest, Release, RepositoryInfo, ScmClient, Tag};
+use async_trait::async_trait;
+use log::warn;
 use semver::Version;
 use serde::{Deserialize, Serialize};
-use std::env;
+use std::path::Path;
+use std::time::Duration;
 
-#[derive(Debug, Deserialize, Serialize)]
-pub struct Release {
-    pub id: u64,
-    pub tag_name: String,
-    pub name: String,
-    pub body: String,
-    pub draft: bool,
-    pub prerelease: bool,
-    pub html_url: String,
-    pub upload_url: String,
+/// Retries of a transiently failing API call unless configured otherwise
+pub const DEFAULT_MAX_RETRIES: u32 = 3;
+/// Delay before the first retry unless configured otherwise, doubled for each further attempt
+pub const DEFAULT_RETRY_DELAY_MS: u64 = 1000;
+/// Longest wait before a retry, whatever the backoff or a `Retry-After` header asks for
+pub const MAX_RETRY_DELAY: Duration = Duration::from_secs(60);
+
+/// A tag as the REST API returns it
+#[derive(Debug, Deserialize)]
+struct GitHubTag {
+    name: String,
+    commit: GitHubTagCommit,
+}
+
+#[derive(Debug, Deserialize)]
+struct GitHubTagCommit {
+    sha: String,
+}
+
+impl From<GitHubTag> for Tag {
+    fn from(tag: GitHubTag) -> Self {
+        Self {
+            name: tag.name,
+            sha: tag.commit.sha,
+        }
+    }
+}
+
+/// A release as the REST API returns it
+#[derive(Debug, Deserialize)]
+struct GitHubRelease {
+    id: u64,
+    tag_name: String,
+    name: Option<String>,
+    body: Option<String>,
+    draft: bool,
+    prerelease: bool,
+    html_url: String,
+    upload_url: String,
+}
+
+impl From<GitHubRelease> for Release {
+    fn from(release: GitHubRelease) -> Self {
+        Self {
+            id: release.id,
+            tag_name: release.tag_name,
+            name: release.name,
+            body: release.body,
+            draft: release.draft,
+            prerelease: release.prerelease,
+            url: release.html_url,
+            upload_url: release.upload_url,
+        }
+    }
 }
 
 #[derive(Debug, Serialize)]
@@ -27,30 +76,182 @@ pub struct CreateReleaseRequest {
     pub name: String,
     pub body: String,
     pub target_commitish: String,
+    pub draft: bool,
+    pub prerelease: bool,
+    pub generate_release_notes: bool,
 }
 
-#[derive(Debug, Deserialize, Serialize)]
-pub struct RepositoryInfo {
-    pub id: u64,
-    pub name: String,
-    pub full_name: String,
-    pub owner: RepositoryOwner,
-    pub default_branch: String,
+impl CreateReleaseRequest {
+    /// Request for the release of `version`; versions with a prerelease identifier are always
+    /// marked as prereleases. With `auto_notes` GitHub's generated notes replace `body`.
+    pub fn new(version: &Version, config: &Config, target_commit_sha: &str, body: String) -> Self {
+        let tag_name = config.version.tag_name(version);
+
+        Self {
+            name: format!("Release {}", tag_name),
+            tag_name,
+            // GitHub would prepend a body to its generated notes
+            body: if config.release.auto_notes {
+                String::new()
+            } else {
+                body
+            },
+            target_commitish: target_commit_sha.to_string(),
+            draft: config.release.draft,
+            prerelease: config.release.prerelease || !version.pre.is_empty(),
+            generate_release_notes: config.release.auto_notes,
+        }
+    }
+}
+
+/// A repository as the REST API returns it
+#[derive(Debug, Deserialize)]
+struct GitHubRepository {
+    full_name: String,
+    default_branch: String,
+}
+
+impl From<GitHubRepository> for RepositoryInfo {
+    fn from(repository: GitHubRepository) -> Self {
+        Self {
+            full_name: repository.full_name,
+            default_branch: repository.default_branch,
+        }
+    }
+}
+
+/// A pull request as the REST API returns it
+#[derive(Debug, Deserialize)]
+struct GitHubPullRequest {
+    number: u64,
+    title: String,
+    body: Option<String>,
+    merged_at: Option<String>,
 }
 
-#[derive(Debug, Deserialize, Serialize)]
-pub struct RepositoryOwner {
-    pub login: String,
+impl From<GitHubPullRequest> for PullRequest {
+    fn from(pull_request: GitHubPullRequest) -> Self {
+        Self {
+            number: pull_request.number,
+            title: pull_request.title,
+            body: pull_request.body,
+            merged_at: pull_request.merged_at,
+        }
+    }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Clone)]
 pub struct GitHubClient {
     client: Client,
+    token: String,
     base_url: String,
+    max_retries: u32,
+    retry_delay: Duration,
+}
+
+// By hand, so the token never shows up in debug output; the HTTP client's own output
+// would include it in the Authorization header
+impl std::fmt::Debug for GitHubClient {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("GitHubClient")
+            .field("token", &"***")
+            .field("base_url", &self.base_url)
+            .field("max_retries", &self.max_retries)
+            .field("retry_delay", &self.retry_delay)
+            .finish_non_exhaustive()
+    }
+}
+
+/// API root from `GITHUB_API_URL`, which Actions sets to the GitHub Enterprise Server API on
+/// those installations, falling back to `https://api.github.com`
+fn api_base_url() -> String {
+    api_base_url_from(std::env::var("GITHUB_API_URL").ok())
+}
+
+/// API root for a `GITHUB_API_URL` value, without its trailing slash
+fn api_base_url_from(github_api_url: Option<String>) -> String {
+    github_api_url
+        .filter(|url| !url.is_empty())
+        .map(|url| url.trim_end_matches('/').to_string())
+        .unwrap_or_else(|| "https://api.github.com".to_string())
+}
+
+/// The `rel="next"` target of a pagination `Link` header, if there is a next page
+fn next_page_url(link_header: &str) -> Option<reqwest::Url> {
+    link_header.split(',').find_map(|link| {
+        let (target, params) = link.split_once(';')?;
+        let is_next = params
+            .split(';')
+            .any(|param| param.trim() == r#"rel="next""#);
+        if !is_next {
+            return None;
+        }
+        let target = target.trim().strip_prefix('<')?.strip_suffix('>')?;
+        reqwest::Url::parse(target).ok()
+    })
+}
+
+/// How long to wait before retry number `attempt + 1`: what `Retry-After` asks for, else
+/// `base_delay` doubled for each retry already made, capped at [`MAX_RETRY_DELAY`]
+fn retry_delay(retry_after: Option<Duration>, base_delay: Duration, attempt: u32) -> Duration {
+    let delay = retry_after.unwrap_or_else(|| {
+        let factor = 2u32.checked_pow(attempt).unwrap_or(u32::MAX);
+        base_delay.saturating_mul(factor)
+    });
+    delay.min(MAX_RETRY_DELAY)
+}
+
+/// Send `request`, retrying transient failures up to `max_retries` times with exponential
+/// backoff from `base_delay` and honoring a `Retry-After` header.
+///
+/// Requests that must not be repeated once processed (`idempotent = false`, e.g. creating a
+/// release) are only retried when the server rejected them outright (429, 503) or they never
+/// reached it.
+pub(crate) async fn retrying_send(
+    request: RequestBuilder,
+    idempotent: bool,
+    max_retries: u32,
+    base_delay: Duration,
+) -> reqwest::Result<Response> {
+    let mut attempt = 0;
+    loop {
+        let Some(this_try) = request.try_clone() else {
+            return request.send().await;
+        };
+        let result = this_try.send().await;
+
+        let retryable = match &result {
+            Ok(response) => match response.status() {
+                StatusCode::TOO_MANY_REQUESTS | StatusCode::SERVICE_UNAVAILABLE => true,
+                StatusCode::BAD_GATEWAY | StatusCode::GATEWAY_TIMEOUT => idempotent,
+                _ => false,
+            },
+            Err(e) => e.is_connect() || (idempotent && e.is_timeout()),
+        };
+        if !retryable || attempt >= max_retries {
+            return result;
+        }
+
+        let retry_after = result
+            .as_ref()
+            .ok()
+            .and_then(|response| response.headers().get(RETRY_AFTER))
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.trim().parse::<u64>().ok())
+            .map(Duration::from_secs);
+        let delay = retry_delay(retry_after, base_delay, attempt);
+
+        attempt += 1;
+        warn!(
+            "⏳ Request failed transiently, retrying in {:?} ({}/{})",
+            delay, attempt, max_retries
+        );
+        tokio::time::sleep(delay).await;
+    }
 }
 
 impl GitHubClient {
-    pub fn new(token: String) -> std::result::Result<Self, Box<dyn std::error::Error>> {
+    pub fn new(token: String) -> std::result::Result<Self, ReleaseError> {
         let mut headers = HeaderMap::new();
         headers.insert(
             AUTHORIZATION,
@@ -69,62 +270,240 @@ impl GitHubClient {
 
         Ok(Self {
             client,
-            base_url: "https://api.github.com".to_string(),
+            token,
+            base_url: api_base_url(),
+            max_retries: DEFAULT_MAX_RETRIES,
+            retry_delay: Duration::from_millis(DEFAULT_RETRY_DELAY_MS),
         })
     }
 
+    /// The error for a non-success `response`, with the token redacted from the body
+    async fn api_error(&self, response: Response) -> ReleaseError {
+        let status = response.status().as_u16();
+        let body = response.text().await.unwrap_or_default();
+        ReleaseError::GitHubApi {
+            status,
+            body: logging::redact(&body, &self.token),
+        }
+    }
+
+    /// Retry transient failures up to `max_retries` times, starting with `retry_delay`
+    pub fn with_retry(mut self, max_retries: u32, retry_delay: Duration) -> Self {
+        self.max_retries = max_retries;
+        self.retry_delay = retry_delay;
+        self
+    }
+
+    /// Apply the `api_max_retries` / `api_retry_delay_ms` settings from `config`
+    pub fn with_retry_config(self, config: &Config) -> Self {
+        let max_retries = config
+            .release
+            .api_max_retries
+            .unwrap_or(DEFAULT_MAX_RETRIES);
+        let retry_delay = config
+            .release
+            .api_retry_delay_ms
+            .unwrap_or(DEFAULT_RETRY_DELAY_MS);
+        self.with_retry(max_retries, Duration::from_millis(retry_delay))
+    }
+
+    /// Send `request` with [`retrying_send`] and this client's retry settings
+    async fn send_with_retry(
+        &self,
+        request: RequestBuilder,
+        idempotent: bool,
+    ) -> reqwest::Result<Response> {
+        retrying_send(request, idempotent, self.max_retries, self.retry_delay).await
+    }
+
+    /// Point the client at a different API host (e.g. a mock server in tests)
+    pub fn with_base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = base_url.into();
+        self
+    }
+
+    /// Perform a cheap authenticated request to verify the token is accepted
+    pub async fn check_authentication(&self) -> std::result::Result<(), ReleaseError> {
+        let url = format!("{}/rate_limit", self.base_url);
+
+        let response = self
+            .send_with_retry(self.client.get(&url), true)
+            .await
+            .map_err(|e| format!("Failed to reach GitHub API: {}", e))?;
+
+        if !response.status().is_success() {
+            return Err(self.api_error(response).await);
+        }
+
+        Ok(())
+    }
+
     pub async fn get_repository_info(
         &self,
-    ) -> std::result::Result<RepositoryInfo, Box<dyn std::error::Error>> {
-        let repo = self.get_repository_from_env()?;
+        repo: &str,
+    ) -> std::result::Result<RepositoryInfo, ReleaseError> {
         let url = format!("{}/repos/{}", self.base_url, repo);
 
         let response = self
-            .client
-            .get(&url)
-            .send()
+            .send_with_retry(self.client.get(&url), true)
             .await
             .map_err(|e| format!("Failed to fetch repository information: {}", e))?;
 
         if !response.status().is_success() {
-            let status = response.status();
-            let text = response.text().await.unwrap_or_default();
-            return Err(format!("GitHub API error {}: {}", status, text).into());
+            return Err(self.api_error(response).await);
         }
 
         let repo_info = response
-            .json::<RepositoryInfo>()
+            .json::<GitHubRepository>()
             .await
             .map_err(|e| format!("Failed to parse repository information: {}", e))?;
 
-        Ok(repo_info)
+        Ok(repo_info.into())
     }
 
     pub async fn get_tags(
         &self,
         repo: &RepositoryInfo,
-    ) -> std::result::Result<Vec<Tag>, Box<dyn std::error::Error>> {
-        let url = format!("{}/repos/{}/tags", self.base_url, repo.full_name);
+    ) -> std::result::Result<Vec<Tag>, ReleaseError> {
+        let mut url = Some(
+            reqwest::Url::parse_with_params(
+                &format!("{}/repos/{}/tags", self.base_url, repo.full_name),
+                &[("per_page", "100")],
+            )
+            .map_err(|e| format!("Invalid tags URL: {}", e))?,
+        );
+
+        // Follow the `Link` header through every page
+        let mut tags = Vec::new();
+        while let Some(page_url) = url {
+            let response = self
+                .send_with_retry(self.client.get(page_url), true)
+                .await
+                .map_err(|e| format!("Failed to fetch repository tags: {}", e))?;
+
+            if !response.status().is_success() {
+                return Err(self.api_error(response).await);
+            }
+
+            url = response
+                .headers()
+                .get(LINK)
+                .and_then(|value| value.to_str().ok())
+                .and_then(next_page_url);
+
+            let page = response
+                .json::<Vec<GitHubTag>>()
+                .await
+                .map_err(|e| format!("Failed to parse repository tags: {}", e))?;
+            tags.extend(page.into_iter().map(Tag::from));
+        }
+
+        Ok(tags)
+    }
+
+    /// The release for `tag_name`, or `None` when there is none
+    pub async fn get_release_by_tag(
+        &self,
+        repo: &RepositoryInfo,
+        tag_name: &str,
+    ) -> std::result::Result<Option<Release>, ReleaseError> {
+        let mut url = reqwest::Url::parse(&format!(
+            "{}/repos/{}/releases/tags",
+            self.base_url, repo.full_name
+        ))
+        .map_err(|e| format!("Invalid release URL: {}", e))?;
+        // A single segment, so tags such as `pkg/v1.0.0` are escaped
+        url.path_segments_mut()
+            .map_err(|_| "Invalid release URL: cannot be a base")?
+            .push(tag_name);
 
         let response = self
-            .client
-            .get(&url)
-            .send()
+            .send_with_retry(self.client.get(url), true)
             .await
-            .map_err(|e| format!("Failed to fetch repository tags: {}", e))?;
+            .map_err(|e| format!("Failed to fetch release {}: {}", tag_name, e))?;
 
+        if response.status() == StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
         if !response.status().is_success() {
-            let status = response.status();
-            let text = response.text().await.unwrap_or_default();
-            return Err(format!("GitHub API error {}: {}", status, text).into());
+            return Err(self.api_error(response).await);
         }
 
-        let tags = response
-            .json::<Vec<Tag>>()
+        let release = response
+            .json::<GitHubRelease>()
             .await
-            .map_err(|e| format!("Failed to parse repository tags: {}", e))?;
+            .map_err(|e| format!("Failed to parse release {}: {}", tag_name, e))?;
 
-        Ok(tags)
+        Ok(Some(release.into()))
+    }
+
+    /// List the repository's releases, most recent first
+    pub async fn list_releases(
+        &self,
+        repo: &RepositoryInfo,
+    ) -> std::result::Result<Vec<Release>, ReleaseError> {
+        let mut url = Some(
+            reqwest::Url::parse_with_params(
+                &format!("{}/repos/{}/releases", self.base_url, repo.full_name),
+                &[("per_page", "100")],
+            )
+            .map_err(|e| format!("Invalid releases URL: {}", e))?,
+        );
+
+        // Follow the `Link` header through every page
+        let mut releases = Vec::new();
+        while let Some(page_url) = url {
+            let response = self
+                .send_with_retry(self.client.get(page_url), true)
+                .await
+                .map_err(|e| format!("Failed to fetch releases: {}", e))?;
+
+            if !response.status().is_success() {
+                return Err(self.api_error(response).await);
+            }
+
+            url = response
+                .headers()
+                .get(LINK)
+                .and_then(|value| value.to_str().ok())
+                .and_then(next_page_url);
+
+            let page = response
+                .json::<Vec<GitHubRelease>>()
+                .await
+                .map_err(|e| format!("Failed to parse releases: {}", e))?;
+            releases.extend(page.into_iter().map(Release::from));
+        }
+
+        Ok(releases)
+    }
+
+    /// List the pull requests associated with a commit (e.g. the PR a squash commit came from)
+    pub async fn get_pull_requests_for_commit(
+        &self,
+        repo: &RepositoryInfo,
+        commit_sha: &str,
+    ) -> std::result::Result<Vec<PullRequest>, ReleaseError> {
+        let url = format!(
+            "{}/repos/{}/commits/{}/pulls",
+            self.base_url, repo.full_name, commit_sha
+        );
+
+        let response = self
+            .send_with_retry(self.client.get(&url), true)
+            .await
+            .map_err(|e| format!("Failed to fetch pull requests for commit: {}", e))?;
+
+        if !response.status().is_success() {
+            return Err(self.api_error(response).await);
+        }
+
+        let pull_requests = response
+            .json::<Vec<GitHubPullRequest>>()
+            .await
+            .map_err(|e| format!("Failed to parse pull requests: {}", e))?;
+
+        Ok(pull_requests.into_iter().map(PullRequest::from).collect())
     }
 
     pub async fn create_release(
@@ -133,50 +512,504 @@ impl GitHubClient {
         version: &Version,
         config: &Config,
         target_commit_sha: &str,
-    ) -> std::result::Result<Release, Box<dyn std::error::Error>> {
-        let tag_name = format!(
-            "{}{}{}",
-            config.version.tag_prefix.as_deref().unwrap_or(""),
-            version,
-            config.version.tag_suffix.as_deref().unwrap_or("")
-        );
-
-        let release_name = format!("Release {}", tag_name);
-        let release_body = String::new(); // Empty body, let GitHub auto-generate if needed
-
-        let request = CreateReleaseRequest {
-            tag_name: tag_name.clone(),
-            name: release_name,
-            body: release_body,
-            target_commitish: target_commit_sha.to_string(),
-        };
+        release_body: String,
+    ) -> std::result::Result<Release, ReleaseError> {
+        let request = CreateReleaseRequest::new(version, config, target_commit_sha, release_body);
 
         let url = format!("{}/repos/{}/releases", self.base_url, repo.full_name);
 
         let response = self
-            .client
-            .post(&url)
-            .json(&request)
-            .send()
+            .send_with_retry(self.client.post(&url).json(&request), false)
             .await
             .map_err(|e| format!("Failed to create release: {}", e))?;
 
         if !response.status().is_success() {
-            let status = response.status();
-            let text = response.text().await.unwrap_or_default();
-            return Err(format!("GitHub API error {}: {}", status, text).into());
+            return Err(self.api_error(response).await);
         }
 
         let release = response
-            .json::<Release>()
+            .json::<GitHubRelease>()
             .await
             .map_err(|e| format!("Failed to parse release response: {}", e))?;
 
-        Ok(release)
+        Ok(release.into())
+    }
+
+    /// Upload a file to a release's `upload_url` under the given asset name
+    pub async fn upload_release_asset(
+        &self,
+        release: &Release,
+        path: &Path,
+        name: &str,
+    ) -> std::result::Result<(), ReleaseError> {
+        // upload_url is a URI template ending in `{?name,label}`
+        let base = release
+            .upload_url
+            .split('{')
+            .next()
+            .unwrap_or(&release.upload_url);
+        let url = reqwest::Url::parse_with_params(base, &[("name", name)])
+            .map_err(|e| format!("Invalid release upload URL {}: {}", base, e))?;
+
+        let content = tokio::fs::read(path)
+            .await
+            .map_err(|e| format!("Failed to read asset {:?}: {}", path, e))?;
+
+        let request = self
+            .client
+            .post(url)
+            .header(CONTENT_TYPE, "application/octet-stream")
+            .body(content);
+        let response = self
+            .send_with_retry(request, false)
+            .await
+            .map_err(|e| format!("Failed to upload release asset {}: {}", name, e))?;
+
+        if !response.status().is_success() {
+            return Err(self.api_error(response).await);
+        }
+
+        Ok(())
+    }
+}
+
+#[async_trait(?Send)]
+impl ScmClient for GitHubClient {
+    async fn get_repository_info(&self, repo: &str) -> Result<RepositoryInfo, ReleaseError> {
+        GitHubClient::get_repository_info(self, repo).await
+    }
+
+    async fn get_tags(&self, repo: &RepositoryInfo) -> Result<Vec<Tag>, ReleaseError> {
+        GitHubClient::get_tags(self, repo).await
     }
 
-    fn get_repository_from_env(&self) -> std::result::Result<String, Box<dyn std::error::Error>> {
-        env::var("GITHUB_REPOSITORY")
-            .map_err(|_| "GITHUB_REPOSITORY environment variable is required".into())
+    async fn get_release_by_tag(
+        &self,
+        repo: &RepositoryInfo,
+        tag_name: &str,
+    ) -> Result<Option<Release>, ReleaseError> {
+        GitHubClient::get_release_by_tag(self, repo, tag_name).await
+    }
+
+    async fn list_releases(&self, repo: &RepositoryInfo) -> Result<Vec<Release>, ReleaseError> {
+        GitHubClient::list_releases(self, repo).await
+    }
+
+    async fn get_pull_requests_for_commit(
+        &self,
+        repo: &RepositoryInfo,
+        commit_sha: &str,
+    ) -> Result<Vec<PullRequest>, ReleaseError> {
+        GitHubClient::get_pull_requests_for_commit(self, repo, commit_sha).await
+    }
+
+    async fn create_release(
+        &self,
+        repo: &RepositoryInfo,
+        version: &Version,
+        config: &Config,
+        target_commit_sha: &str,
+        release_body: String,
+    ) -> Result<Release, ReleaseError> {
+        GitHubClient::create_release(self, repo, version, config, target_commit_sha, release_body)
+            .await
+    }
+
+    async fn upload_release_asset(
+        &self,
+        release: &Release,
+        path: &Path,
+        name: &str,
+    ) -> Result<(), ReleaseError> {
+        GitHubClient::upload_release_asset(self, release, path, name).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_api_url_from_environment() {
+        assert_eq!(
+            api_base_url_from(Some("https://github.example.com/api/v3/".to_string())),
+            "https://github.example.com/api/v3"
+        );
+        assert_eq!(
+            api_base_url_from(Some(String::new())),
+            "https://api.github.com"
+        );
+        assert_eq!(api_base_url_from(None), "https://api.github.com");
+    }
+
+    #[test]
+    fn test_release_request_draft_and_prerelease() {
+        let mut config = Config::default();
+        let stable = Version::parse("1.2.0").unwrap();
+        let candidate = Version::parse("1.2.0-rc.1").unwrap();
+        let json = |version: &Version, config: &Config| {
+            serde_json::to_value(CreateReleaseRequest::new(
+                version,
+                config,
+                "abc",
+                String::new(),
+            ))
+            .unwrap()
+        };
+
+        let request = json(&stable, &config);
+        assert_eq!(request["tag_name"], "v1.2.0");
+        assert_eq!(request["draft"], false);
+        assert_eq!(request["prerelease"], false);
+        assert_eq!(request["generate_release_notes"], false);
+
+        let request = json(&candidate, &config);
+        assert_eq!(request["draft"], false);
+        assert_eq!(request["prerelease"], true);
+
+        config.release.draft = true;
+        config.release.prerelease = true;
+        let request = json(&stable, &config);
+        assert_eq!(request["draft"], true);
+        assert_eq!(request["prerelease"], true);
+    }
+
+    #[test]
+    fn test_release_request_with_generated_notes() {
+        let mut config = Config::default();
+        config.release.auto_notes = true;
+        let version = Version::parse("1.2.0").unwrap();
+
+        // Notes built locally are dropped so GitHub doesn't prepend them to its own
+        let request = serde_json::to_value(CreateReleaseRequest::new(
+            &version,
+            &config,
+            "abc",
+            "## Features\n\n- add exports (abc1234)\n".to_string(),
+        ))
+        .unwrap();
+        assert_eq!(request["generate_release_notes"], true);
+        assert_eq!(request["body"], "");
+    }
+
+    fn test_repo_info() -> RepositoryInfo {
+        RepositoryInfo {
+            full_name: "owner/repo".to_string(),
+            default_branch: "main".to_string(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_get_release_by_tag_existing() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("GET", "/repos/owner/repo/releases/tags/pkg%2Fv1.0.0")
+            .with_status(200)
+            .with_body(
+                r#"{"id": 7, "tag_name": "pkg/v1.0.0", "name": "Release pkg/v1.0.0",
+                    "body": "", "draft": false, "prerelease": false,
+                    "html_url": "https://github.com/owner/repo/releases/tag/pkg/v1.0.0",
+                    "upload_url": "https://uploads.github.com/repos/owner/repo/releases/7/assets{?name,label}"}"#,
+            )
+            .create_async()
+            .await;
+
+        let client = GitHubClient::new("test-token".to_string())
+            .unwrap()
+            .with_base_url(server.url());
+        let release = client
+            .get_release_by_tag(&test_repo_info(), "pkg/v1.0.0")
+            .await
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(release.id, 7);
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_get_release_by_tag_not_found() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("GET", "/repos/owner/repo/releases/tags/v2.0.0")
+            .with_status(404)
+            .with_body(r#"{"message": "Not Found"}"#)
+            .create_async()
+            .await;
+
+        let client = GitHubClient::new("test-token".to_string())
+            .unwrap()
+            .with_base_url(server.url());
+        let release = client
+            .get_release_by_tag(&test_repo_info(), "v2.0.0")
+            .await
+            .unwrap();
+
+        assert!(release.is_none());
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_api_errors_carry_status_and_body() {
+        let mut server = mockito::Server::new_async().await;
+        server
+            .mock("GET", "/repos/owner/missing")
+            .with_status(404)
+            .with_body(r#"{"message": "Not Found"}"#)
+            .create_async()
+            .await;
+
+        let client = GitHubClient::new("test-token".to_string())
+            .unwrap()
+            .with_base_url(server.url());
+        let error = client
+            .get_repository_info("owner/missing")
+            .await
+            .unwrap_err();
+
+        match error {
+            ReleaseError::GitHubApi { status, body } => {
+                assert_eq!(status, 404);
+                assert!(body.contains("Not Found"));
+            }
+            other => panic!("Unexpected error: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_debug_output_hides_the_token() {
+        let client = GitHubClient::new("ghp_secret123".to_string()).unwrap();
+        let debug = format!("{:?}", client);
+        assert!(!debug.contains("ghp_secret123"), "{}", debug);
+        assert!(debug.contains("***"), "{}", debug);
+    }
+
+    #[test]
+    fn test_retry_delay_is_capped() {
+        let delay = Duration::from_millis(1000);
+        assert_eq!(retry_delay(None, delay, 0), delay);
+        assert_eq!(retry_delay(None, delay, 3), Duration::from_secs(8));
+        // Large attempt counts and delays saturate instead of overflowing
+        assert_eq!(retry_delay(None, delay, 40), MAX_RETRY_DELAY);
+        assert_eq!(retry_delay(None, Duration::MAX, 3), MAX_RETRY_DELAY);
+        // Retry-After is honored up to the cap
+        let retry_after = Some(Duration::from_secs(5));
+        assert_eq!(retry_delay(retry_after, delay, 3), Duration::from_secs(5));
+        let retry_after = Some(Duration::from_secs(86_400));
+        assert_eq!(retry_delay(retry_after, delay, 0), MAX_RETRY_DELAY);
+    }
+
+    #[tokio::test]
+    async fn test_api_errors_redact_the_token() {
+        let mut server = mockito::Server::new_async().await;
+        server
+            .mock("GET", "/repos/owner/repo")
+            .with_status(401)
+            .with_body(r#"{"message": "Bad credentials: ghp_secret123"}"#)
+            .create_async()
+            .await;
+
+        let client = GitHubClient::new("ghp_secret123".to_string())
+            .unwrap()
+            .with_base_url(server.url());
+        let error = client.get_repository_info("owner/repo").await.unwrap_err();
+
+        let message = error.to_string();
+        assert!(!message.contains("ghp_secret123"));
+        assert!(message.contains("Bad credentials: ***"));
+    }
+
+    #[tokio::test]
+    async fn test_get_tags_follows_pagination() {
+        let mut server = mockito::Server::new_async().await;
+        let first_page = server
+            .mock("GET", "/repos/owner/repo/tags")
+            .match_query(mockito::Matcher::UrlEncoded(
+                "per_page".to_string(),
+                "100".to_string(),
+            ))
+            .with_status(200)
+            .with_header(
+                "link",
+                &format!(
+                    r#"<{0}/repositories/1/tags?per_page=100&page=2>; rel="next", <{0}/repositories/1/tags?per_page=100&page=2>; rel="last""#,
+                    server.url()
+                ),
+            )
+            .with_body(
+                r#"[{"name": "v1.1.0", "commit": {"sha": "b", "url": "u"}},
+                    {"name": "v1.0.1", "commit": {"sha": "a", "url": "u"}}]"#,
+            )
+            .create_async()
+            .await;
+        let second_page = server
+            .mock("GET", "/repositories/1/tags")
+            .match_query(mockito::Matcher::UrlEncoded(
+                "page".to_string(),
+                "2".to_string(),
+            ))
+            .with_status(200)
+            .with_header(
+                "link",
+                &format!(
+                    r#"<{}/repositories/1/tags?per_page=100&page=1>; rel="prev""#,
+                    server.url()
+                ),
+            )
+            .with_body(r#"[{"name": "v1.0.0", "commit": {"sha": "c", "url": "u"}}]"#)
+            .create_async()
+            .await;
+
+        let client = GitHubClient::new("test-token".to_string())
+            .unwrap()
+            .with_base_url(server.url());
+        let repo_info = test_repo_info();
+
+        let tags = client.get_tags(&repo_info).await.unwrap();
+        let names: Vec<&str> = tags.iter().map(|tag| tag.name.as_str()).collect();
+
+        assert_eq!(names, vec!["v1.1.0", "v1.0.1", "v1.0.0"]);
+        assert_eq!(tags[0].sha, "b");
+        first_page.assert_async().await;
+        second_page.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_list_releases_follows_pagination() {
+        let mut server = mockito::Server::new_async().await;
+        let release = |id: u64, tag_name: &str| {
+            serde_json::json!({
+                "id": id, "tag_name": tag_name, "name": tag_name, "body": null,
+                "draft": false, "prerelease": false, "html_url": "h", "upload_url": "u"
+            })
+        };
+        let first_page = server
+            .mock("GET", "/repos/owner/repo/releases")
+            .match_query(mockito::Matcher::UrlEncoded(
+                "per_page".to_string(),
+                "100".to_string(),
+            ))
+            .with_status(200)
+            .with_header(
+                "link",
+                &format!(
+                    r#"<{0}/repositories/1/releases?per_page=100&page=2>; rel="next", <{0}/repositories/1/releases?per_page=100&page=2>; rel="last""#,
+                    server.url()
+                ),
+            )
+            .with_body(serde_json::json!([release(2, "v1.1.0")]).to_string())
+            .create_async()
+            .await;
+        let second_page = server
+            .mock("GET", "/repositories/1/releases")
+            .match_query(mockito::Matcher::UrlEncoded(
+                "page".to_string(),
+                "2".to_string(),
+            ))
+            .with_status(200)
+            .with_body(serde_json::json!([release(1, "v1.0.0")]).to_string())
+            .create_async()
+            .await;
+
+        let client = GitHubClient::new("test-token".to_string())
+            .unwrap()
+            .with_base_url(server.url());
+        let repo_info = test_repo_info();
+
+        let releases = client.list_releases(&repo_info).await.unwrap();
+        let tags: Vec<&str> = releases
+            .iter()
+            .map(|release| release.tag_name.as_str())
+            .collect();
+
+        assert_eq!(tags, vec!["v1.1.0", "v1.0.0"]);
+        first_page.assert_async().await;
+        second_page.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_retries_transient_failures() {
+        let mut server = mockito::Server::new_async().await;
+        let unavailable = server
+            .mock("GET", "/repos/owner/repo")
+            .with_status(503)
+            .expect(2)
+            .create_async()
+            .await;
+        let available = server
+            .mock("GET", "/repos/owner/repo")
+            .with_status(200)
+            .with_body(
+                r#"{"id": 1, "name": "repo", "full_name": "owner/repo",
+                    "owner": {"login": "owner"}, "default_branch": "main"}"#,
+            )
+            .create_async()
+            .await;
+
+        let client = GitHubClient::new("test-token".to_string())
+            .unwrap()
+            .with_base_url(server.url())
+            .with_retry(3, Duration::from_millis(1));
+        let repo_info = client.get_repository_info("owner/repo").await.unwrap();
+
+        assert_eq!(repo_info.full_name, "owner/repo");
+        unavailable.assert_async().await;
+        available.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_gives_up_after_max_retries() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("GET", "/rate_limit")
+            .with_status(429)
+            .with_header("retry-after", "0")
+            .expect(3)
+            .create_async()
+            .await;
+
+        let client = GitHubClient::new("test-token".to_string())
+            .unwrap()
+            .with_base_url(server.url())
+            .with_retry(2, Duration::from_millis(1));
+        let error = client.check_authentication().await.unwrap_err();
+
+        assert!(
+            error.to_string().contains("429"),
+            "Unexpected error: {}",
+            error
+        );
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_does_not_repeat_processed_release_creation() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("POST", "/repos/owner/repo/releases")
+            .with_status(502)
+            .expect(1)
+            .create_async()
+            .await;
+
+        let client = GitHubClient::new("test-token".to_string())
+            .unwrap()
+            .with_base_url(server.url())
+            .with_retry(3, Duration::from_millis(1));
+        let repo_info = test_repo_info();
+        let version = Version::parse("1.0.0").unwrap();
+
+        let result = client
+            .create_release(
+                &repo_info,
+                &version,
+                &Config::default(),
+                "abc",
+                String::new(),
+            )
+            .await;
+
+        assert!(result.is_err());
+        mock.assert_async().await;
     }
 }
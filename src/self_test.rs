@@ -0,0 +1,254 @@
+use crate::config::Config;
+use crate::error::ReleaseError;
+use crate::release::auth_callbacks;
+use crate::scm::git::open_repository;
+use crate::scm::github::GitHubClient;
+use git2::Direction;
+use log::{error, info};
+use std::path::Path;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum CheckStatus {
+    Passed,
+    Failed(String),
+    Skipped(String),
+}
+
+#[derive(Debug, Clone)]
+pub struct CheckResult {
+    pub name: String,
+    pub status: CheckStatus,
+}
+
+#[derive(Debug, Default)]
+pub struct SelfTestReport {
+    pub checks: Vec<CheckResult>,
+}
+
+impl SelfTestReport {
+    pub fn passed(&self) -> bool {
+        !self
+            .checks
+            .iter()
+            .any(|check| matches!(check.status, CheckStatus::Failed(_)))
+    }
+
+    fn record(&mut self, name: &str, status: CheckStatus) {
+        match &status {
+            CheckStatus::Passed => info!("✅ {}", name),
+            CheckStatus::Failed(reason) => error!("❌ {}: {}", name, reason),
+            CheckStatus::Skipped(reason) => info!("⏭️  {}: skipped ({})", name, reason),
+        }
+        self.checks.push(CheckResult {
+            name: name.to_string(),
+            status,
+        });
+    }
+}
+
+/// Verify the environment is ready for a release without performing one.
+///
+/// `api_client` is used for the authentication check; when `None`, a client is
+/// built from `github_token`. `remote_name` is the remote releases are pushed to.
+pub async fn run_self_test(
+    config_file: &Path,
+    working_directory: &Path,
+    remote_name: &str,
+    github_token: Option<String>,
+    api_client: Option<GitHubClient>,
+) -> SelfTestReport {
+    let mut report = SelfTestReport::default();
+
+    info!("🩺 Running self-test");
+
+    // Configuration loads and validates
+    let config_status = match Config::load(config_file).and_then(|config| config.validate()) {
+        Ok(()) => CheckStatus::Passed,
+        Err(e) => CheckStatus::Failed(e.to_string()),
+    };
+    report.record("Configuration loads and validates", config_status);
+
+    // Git repository opens
+    let repo = match open_repository(working_directory) {
+        Ok(repo) => {
+            report.record("Git repository opens", CheckStatus::Passed);
+            Some(repo)
+        }
+        Err(e) => {
+            report.record("Git repository opens", CheckStatus::Failed(e.to_string()));
+            None
+        }
+    };
+
+    // GITHUB_TOKEN is present and authenticates
+    match &github_token {
+        Some(token) => {
+            let client = match api_client {
+                Some(client) => Ok(client),
+                None => GitHubClient::new(token.clone()),
+            };
+            let status = match client {
+                Ok(client) => match client.check_authentication().await {
+                    Ok(()) => CheckStatus::Passed,
+                    Err(e) => CheckStatus::Failed(e.to_string()),
+                },
+                Err(e) => CheckStatus::Failed(e.to_string()),
+            };
+            report.record("GITHUB_TOKEN authenticates", status);
+        }
+        None => report.record(
+            "GITHUB_TOKEN authenticates",
+            CheckStatus::Failed("Neither GITHUB_TOKEN nor GITHUB_TOKEN_FILE is set".to_string()),
+        ),
+    }
+
+    // Remote is reachable
+    let remote_status = match &repo {
+        Some(repo) => match check_remote(repo, remote_name, github_token.as_deref()) {
+            Ok(()) => CheckStatus::Passed,
+            Err(e) => CheckStatus::Failed(e.to_string()),
+        },
+        None => CheckStatus::Skipped("git repository unavailable".to_string()),
+    };
+    report.record(
+        &format!("Remote '{}' is reachable", remote_name),
+        remote_status,
+    );
+
+    report
+}
+
+fn check_remote(
+    repo: &git2::Repository,
+    remote_name: &str,
+    github_token: Option<&str>,
+) -> std::result::Result<(), ReleaseError> {
+    let mut remote = repo
+        .find_remote(remote_name)
+        .map_err(|e| format!("Failed to find remote '{}': {}", remote_name, e))?;
+
+    // The same credentials, with the same attempt limit, as the release push
+    let mut connection = remote
+        .connect_auth(Direction::Fetch, Some(auth_callbacks(github_token)), None)
+        .map_err(|e| format!("Failed to connect to remote '{}': {}", remote_name, e))?;
+
+    if !connection.connected() {
+        return Err(format!("Remote '{}' closed the connection", remote_name).into());
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use git2::{Repository, Signature};
+    use tempfile::TempDir;
+
+    fn create_repo_with_remote() -> (TempDir, TempDir) {
+        let remote_dir = TempDir::new().expect("Failed to create temp directory");
+        Repository::init_bare(remote_dir.path()).expect("Failed to init bare repository");
+
+        let temp_dir = TempDir::new().expect("Failed to create temp directory");
+        let repo = Repository::init(temp_dir.path()).expect("Failed to init repository");
+
+        let sig = Signature::now("Test User", "test@example.com").unwrap();
+        let tree_id = {
+            let mut index = repo.index().unwrap();
+            index.write_tree().unwrap()
+        };
+        let tree = repo.find_tree(tree_id).unwrap();
+        repo.commit(Some("HEAD"), &sig, &sig, "feat: initial", &tree, &[])
+            .unwrap();
+        repo.remote("origin", remote_dir.path().to_str().unwrap())
+            .unwrap();
+
+        std::fs::write(
+            temp_dir.path().join(".release-config.toml"),
+            "[version]\ninitial_version = \"0.1.0\"\ntag_prefix = \"v\"\n",
+        )
+        .unwrap();
+
+        (temp_dir, remote_dir)
+    }
+
+    #[tokio::test]
+    async fn test_self_test_passes_with_mocked_api() {
+        let (temp_dir, _remote_dir) = create_repo_with_remote();
+
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("GET", "/rate_limit")
+            .with_status(200)
+            .with_body("{}")
+            .create_async()
+            .await;
+
+        let client = GitHubClient::new("test-token".to_string())
+            .unwrap()
+            .with_base_url(server.url());
+
+        let report = run_self_test(
+            &temp_dir.path().join(".release-config.toml"),
+            temp_dir.path(),
+            "origin",
+            Some("test-token".to_string()),
+            Some(client),
+        )
+        .await;
+
+        mock.assert_async().await;
+        assert!(report.passed(), "Report: {:?}", report);
+        assert_eq!(report.checks.len(), 4);
+        assert!(report
+            .checks
+            .iter()
+            .all(|check| check.status == CheckStatus::Passed));
+    }
+
+    #[tokio::test]
+    async fn test_self_test_fails_without_token() {
+        let (temp_dir, _remote_dir) = create_repo_with_remote();
+
+        let report = run_self_test(
+            &temp_dir.path().join(".release-config.toml"),
+            temp_dir.path(),
+            "origin",
+            None,
+            None,
+        )
+        .await;
+
+        assert!(!report.passed());
+        let token_check = report
+            .checks
+            .iter()
+            .find(|check| check.name == "GITHUB_TOKEN authenticates")
+            .unwrap();
+        assert!(matches!(token_check.status, CheckStatus::Failed(_)));
+    }
+
+    #[tokio::test]
+    async fn test_self_test_checks_the_configured_remote() {
+        let (temp_dir, _remote_dir) = create_repo_with_remote();
+
+        let report = run_self_test(
+            &temp_dir.path().join(".release-config.toml"),
+            temp_dir.path(),
+            "upstream",
+            None,
+            None,
+        )
+        .await;
+
+        let remote_check = report
+            .checks
+            .iter()
+            .find(|check| check.name == "Remote 'upstream' is reachable")
+            .unwrap();
+        match &remote_check.status {
+            CheckStatus::Failed(reason) => assert!(reason.contains("'upstream'"), "{}", reason),
+            other => panic!("Unexpected status: {:?}", other),
+        }
+    }
+}
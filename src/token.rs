@@ -0,0 +1,116 @@
+use crate::error::ReleaseError;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// The GitHub token, read from `token_file` (or the file in `GITHUB_TOKEN_FILE`) when one is
+/// given, else from `GITHUB_TOKEN`
+pub fn github_token(
+    token_file: Option<&Path>,
+    var: impl Fn(&str) -> Option<String>,
+) -> std::result::Result<Option<String>, ReleaseError> {
+    let token_file = token_file.map(Path::to_path_buf).or_else(|| {
+        var("GITHUB_TOKEN_FILE")
+            .filter(|path| !path.is_empty())
+            .map(PathBuf::from)
+    });
+    if let Some(path) = token_file {
+        return read_token_file(&path).map(Some);
+    }
+    Ok(var("GITHUB_TOKEN")
+        .map(|token| token.trim().to_string())
+        .filter(|token| !token.is_empty()))
+}
+
+/// Read a token from `path`, as mounted for Docker secrets, without surrounding whitespace
+fn read_token_file(path: &Path) -> std::result::Result<String, ReleaseError> {
+    let token = fs::read_to_string(path).map_err(|e| {
+        ReleaseError::Config(format!(
+            "Failed to read GitHub token from {:?}: {}",
+            path, e
+        ))
+    })?;
+    let token = token.trim();
+    if token.is_empty() {
+        return Err(ReleaseError::Config(format!(
+            "GitHub token file {:?} is empty",
+            path
+        )));
+    }
+    Ok(token.to_string())
+}
+
+/// Environment lookups answered from `pairs` instead of the process environment, for tests
+#[cfg(test)]
+pub(crate) fn vars(pairs: &[(&str, &str)]) -> impl Fn(&str) -> Option<String> {
+    let pairs: Vec<(String, String)> = pairs
+        .iter()
+        .map(|(name, value)| (name.to_string(), value.to_string()))
+        .collect();
+    move |name| {
+        pairs
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_token_read_from_file() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("github_token");
+        fs::write(&path, "  ghp_from_file\n").unwrap();
+
+        let token = github_token(Some(&path), vars(&[])).unwrap();
+        assert_eq!(token.as_deref(), Some("ghp_from_file"));
+
+        let path_var = path.to_str().unwrap();
+        let token = github_token(None, vars(&[("GITHUB_TOKEN_FILE", path_var)])).unwrap();
+        assert_eq!(token.as_deref(), Some("ghp_from_file"));
+    }
+
+    #[test]
+    fn test_token_file_takes_precedence_over_env() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("github_token");
+        fs::write(&path, "ghp_from_file").unwrap();
+        let path_var = path.to_str().unwrap();
+
+        let env = vars(&[
+            ("GITHUB_TOKEN", "ghp_from_env"),
+            ("GITHUB_TOKEN_FILE", path_var),
+        ]);
+        assert_eq!(
+            github_token(None, env).unwrap().as_deref(),
+            Some("ghp_from_file")
+        );
+
+        let env = vars(&[("GITHUB_TOKEN", "ghp_from_env")]);
+        assert_eq!(
+            github_token(None, &env).unwrap().as_deref(),
+            Some("ghp_from_env")
+        );
+        assert_eq!(
+            github_token(Some(&path), &env).unwrap().as_deref(),
+            Some("ghp_from_file")
+        );
+    }
+
+    #[test]
+    fn test_unreadable_or_empty_token_file() {
+        let temp_dir = TempDir::new().unwrap();
+        let missing = temp_dir.path().join("missing");
+        assert!(matches!(
+            github_token(Some(&missing), vars(&[])),
+            Err(ReleaseError::Config(_))
+        ));
+
+        let empty = temp_dir.path().join("empty");
+        fs::write(&empty, "\n").unwrap();
+        assert!(github_token(Some(&empty), vars(&[])).is_err());
+    }
+}
@@ -1,41 +1,91 @@
-use crate::conventional_commit::ConventionalCommit;
+use crate::config::Config;
+use crate::conventional_commit::{ConventionalCommit, ParseOptions};
+use crate::error::ReleaseError;
+use log::{error, info};
 use std::env;
-use std::error::Error;
 
-pub async fn validate_pr_title(event_path: &str) -> Result<(), Box<dyn Error>> {
+/// Parse a PR title and make sure its type is in `allowed_types` and its scope (if any) is in
+/// `allowed_scopes`, for whichever of them is set
+pub fn check_pr_title(
+    title: &str,
+    options: &ParseOptions,
+    allowed_scopes: Option<&[String]>,
+) -> Result<ConventionalCommit, String> {
+    let commit = ConventionalCommit::parse_with(title, options)?;
+    if !options.is_known_type(&commit.commit_type) {
+        return Err(format!(
+            "Commit type {:?} is not allowed",
+            commit.commit_type
+        ));
+    }
+    if let (Some(scope), Some(allowed)) = (&commit.scope, allowed_scopes) {
+        if !allowed.contains(scope) {
+            return Err(format!(
+                "Scope {:?} is not allowed; allowed scopes: {}",
+                scope,
+                allowed.join(", ")
+            ));
+        }
+    }
+    Ok(commit)
+}
+
+/// The verdict on a PR title
+#[derive(Debug)]
+pub enum ValidationOutcome {
+    Valid(ConventionalCommit),
+    Invalid { title: String, error: String },
+}
+
+impl ValidationOutcome {
+    pub fn is_valid(&self) -> bool {
+        matches!(self, ValidationOutcome::Valid(_))
+    }
+}
+
+/// Check the title of the PR in the event at `event_path`, printing guidance when it is invalid.
+/// Only an unreadable event is an error; an invalid title is a [`ValidationOutcome::Invalid`]
+pub async fn validate_pr_title(
+    event_path: &str,
+    config: &Config,
+) -> Result<ValidationOutcome, ReleaseError> {
     let event_data = std::fs::read_to_string(event_path)?;
-    let event: serde_json::Value = serde_json::from_str(&event_data)?;
+    let event: serde_json::Value = serde_json::from_str(&event_data)
+        .map_err(|e| format!("Failed to parse event {}: {}", event_path, e))?;
 
     let pr_title = event["pull_request"]["title"]
         .as_str()
         .ok_or("Could not extract PR title from event")?;
 
-    println!("🔍 Validating PR title: {}", pr_title);
+    info!("🔍 Validating PR title: {}", pr_title);
 
     // Use ConventionalCommit parser for validation
-    match ConventionalCommit::parse(pr_title) {
+    let options = ParseOptions::from_config(config);
+    match check_pr_title(pr_title, &options, config.commits.allowed_scopes.as_deref()) {
         Ok(commit) => {
-            println!("✅ PR title follows conventional commit format");
-            println!("   Type: {}", commit.commit_type);
+            info!("✅ PR title follows conventional commit format");
+            info!("   Type: {}", commit.commit_type);
             if let Some(scope) = &commit.scope {
-                println!("   Scope: {}", scope);
+                info!("   Scope: {}", scope);
             }
-            println!("   Description: {}", commit.description);
+            info!("   Description: {}", commit.description);
             if commit.breaking_change {
-                println!("   ⚠️ Breaking change detected");
+                info!("   ⚠️ Breaking change detected");
             }
+            Ok(ValidationOutcome::Valid(commit))
         }
         Err(error) => {
-            eprintln!("❌ PR title does not follow conventional commit format");
-            eprintln!("   Error: {}", error);
-            eprintln!("Expected format: type(scope): description");
-            eprintln!("Valid types: feat, fix, docs, style, refactor, perf, test, chore, build, ci, revert, security");
-            eprintln!("Example: feat(auth): add user login functionality");
-            std::process::exit(1);
+            error!("❌ PR title does not follow conventional commit format");
+            error!("   Error: {}", error);
+            error!("Expected format: type(scope): description");
+            error!("Valid types: {}", options.known_types().join(", "));
+            error!("Example: feat(auth): add user login functionality");
+            Ok(ValidationOutcome::Invalid {
+                title: pr_title.to_string(),
+                error,
+            })
         }
     }
-
-    Ok(())
 }
 
 pub fn should_validate_pr() -> bool {
@@ -45,3 +95,113 @@ pub fn should_validate_pr() -> bool {
         false
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+    use tempfile::TempDir;
+
+    fn options_with_alias() -> ParseOptions {
+        ParseOptions {
+            type_aliases: HashMap::from([("feature".to_string(), "feat".to_string())]),
+            ..ParseOptions::default()
+        }
+    }
+
+    #[test]
+    fn test_check_pr_title_accepts_aliased_type() {
+        let commit = check_pr_title("feature(web): x", &options_with_alias(), None).unwrap();
+        assert_eq!(commit.commit_type, "feat");
+    }
+
+    #[test]
+    fn test_check_pr_title_accepts_any_type_without_allowlist() {
+        let commit = check_pr_title("fet: x", &ParseOptions::default(), None).unwrap();
+        assert_eq!(commit.commit_type, "fet");
+        assert!(check_pr_title("bogus(web): x", &options_with_alias(), None).is_ok());
+    }
+
+    /// Validate `title` as the title of the PR in a `pull_request` event
+    async fn validate_title(title: &str) -> Result<ValidationOutcome, ReleaseError> {
+        let temp_dir = TempDir::new().expect("Failed to create temp directory");
+        let event_path = temp_dir.path().join("event.json");
+        let event = serde_json::json!({ "pull_request": { "title": title } });
+        std::fs::write(&event_path, event.to_string()).unwrap();
+
+        validate_pr_title(event_path.to_str().unwrap(), &Config::default()).await
+    }
+
+    #[tokio::test]
+    async fn test_validate_valid_pr_title() {
+        match validate_title("feat(api)!: add tokens").await.unwrap() {
+            ValidationOutcome::Valid(commit) => {
+                assert_eq!(commit.commit_type, "feat");
+                assert_eq!(commit.scope.as_deref(), Some("api"));
+                assert!(commit.breaking_change);
+            }
+            other => panic!("Unexpected outcome: {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_validate_invalid_pr_title() {
+        let outcome = validate_title("Update stuff").await.unwrap();
+
+        assert!(!outcome.is_valid());
+        match outcome {
+            ValidationOutcome::Invalid { title, error } => {
+                assert_eq!(title, "Update stuff");
+                assert!(!error.is_empty());
+            }
+            other => panic!("Unexpected outcome: {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_unreadable_event_is_an_error() {
+        let result = validate_pr_title("/non/existent/event.json", &Config::default()).await;
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_check_pr_title_allowed_types() {
+        let options = ParseOptions {
+            allowed_types: Some(vec!["feat".to_string(), "fix".to_string()]),
+            ..options_with_alias()
+        };
+
+        let error = check_pr_title("fet: x", &options, None).unwrap_err();
+        assert!(error.contains("fet"), "Unexpected error: {}", error);
+        assert!(check_pr_title("chore: x", &options, None).is_err());
+        assert!(check_pr_title("bogus: x", &options, None).is_err());
+        assert!(check_pr_title("fix: x", &options, None).is_ok());
+        // Aliases resolve before the check
+        assert!(check_pr_title("feature: x", &options, None).is_ok());
+        assert_eq!(options.known_types(), vec!["feat", "fix", "feature"]);
+
+        // The allowlist replaces the built-in types, so it can also admit new ones
+        let options = ParseOptions {
+            allowed_types: Some(vec!["fet".to_string()]),
+            ..ParseOptions::default()
+        };
+        assert!(check_pr_title("fet: x", &options, None).is_ok());
+        assert!(check_pr_title("feat: x", &options, None).is_err());
+    }
+
+    #[test]
+    fn test_check_pr_title_allowed_scopes() {
+        let allowed = vec!["api".to_string(), "web".to_string()];
+        let options = ParseOptions::default();
+
+        let commit = check_pr_title("fix(api): x", &options, Some(&allowed)).unwrap();
+        assert_eq!(commit.scope.as_deref(), Some("api"));
+        assert!(check_pr_title("fix: x", &options, Some(&allowed)).is_ok());
+
+        let error = check_pr_title("fix(cli): x", &options, Some(&allowed)).unwrap_err();
+        assert!(error.contains("\"cli\""), "Unexpected error: {}", error);
+        assert!(error.contains("api, web"), "Unexpected error: {}", error);
+
+        assert!(check_pr_title("fix(cli): x", &options, None).is_ok());
+    }
+}
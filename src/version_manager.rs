@@ -1,22 +1,81 @@
+use crate::error::ReleaseError;
 use semver::Version;
+use std::cell::OnceCell;
+use std::cmp::Ordering;
 
 use crate::bump_type::BumpType;
-use crate::config::Config;
-use crate::scm::github::{GitHubClient, RepositoryInfo};
+use crate::config::{Config, TagSource, Versioning};
+use crate::scm::RepositoryInfo;
+use crate::scm::ScmClient;
 
 pub struct VersionManager<'a> {
     config: &'a Config,
-    repo_info: &'a RepositoryInfo,
+    scm_client: Option<&'a dyn ScmClient>,
+    repo_info: Option<&'a RepositoryInfo>,
+    repo: Option<&'a git2::Repository>,
+    current_version: Option<Version>,
+    release_date: Option<chrono::NaiveDate>,
+    /// Tag names read from GitHub while looking up the current version
+    tag_names: OnceCell<Vec<String>>,
 }
 
 impl<'a> VersionManager<'a> {
-    pub fn new(config: &'a Config, repo_info: &'a RepositoryInfo) -> Self {
-        Self { config, repo_info }
+    pub fn new(
+        config: &'a Config,
+        scm_client: &'a dyn ScmClient,
+        repo_info: &'a RepositoryInfo,
+    ) -> Self {
+        Self {
+            config,
+            scm_client: Some(scm_client),
+            repo_info: Some(repo_info),
+            repo: None,
+            current_version: None,
+            release_date: None,
+            tag_names: OnceCell::new(),
+        }
     }
 
-    pub async fn get_current_version(
-        &self,
-    ) -> std::result::Result<Version, Box<dyn std::error::Error>> {
+    /// A manager that works from the local repository only, without GitHub access
+    pub fn offline(config: &'a Config) -> Self {
+        Self {
+            config,
+            scm_client: None,
+            repo_info: None,
+            repo: None,
+            current_version: None,
+            release_date: None,
+            tag_names: OnceCell::new(),
+        }
+    }
+
+    /// Use `version` as the current version instead of looking it up from tags
+    pub fn with_current_version(mut self, version: Version) -> Self {
+        self.current_version = Some(version);
+        self
+    }
+
+    /// Date CalVer versions are computed for, instead of today (UTC)
+    pub fn with_release_date(mut self, date: chrono::NaiveDate) -> Self {
+        self.release_date = Some(date);
+        self
+    }
+
+    /// The checked-out repository, whose tags are read with `tag_source = "local"`
+    pub fn with_repository(mut self, repo: &'a git2::Repository) -> Self {
+        self.repo = Some(repo);
+        self
+    }
+
+    pub async fn get_current_version(&self) -> std::result::Result<Version, ReleaseError> {
+        if let Some(version) = &self.current_version {
+            return Ok(version.clone());
+        }
+        if let (Ok(TagSource::Local), Some(repo)) = (self.config.version.tag_source(), self.repo) {
+            if let Some(version) = self.latest_local_version(repo)? {
+                return Ok(version);
+            }
+        }
         self.get_version_from_git_tags().await
     }
 
@@ -24,77 +83,662 @@ impl<'a> VersionManager<'a> {
         &self,
         current: &Version,
         bump_type: &BumpType,
-    ) -> std::result::Result<Version, Box<dyn std::error::Error>> {
-        let mut new_version = current.clone();
-
-        match bump_type {
-            BumpType::Major => {
-                new_version.major += 1;
-                new_version.minor = 0;
-                new_version.patch = 0;
-            }
-            BumpType::Minor => {
-                new_version.minor += 1;
-                new_version.patch = 0;
+    ) -> std::result::Result<Version, ReleaseError> {
+        // While in 0.x every change shifts down a position: the minor is the "major"
+        let bump_type = match bump_type {
+            BumpType::Major if current.major == 0 && self.config.version.respect_zerover => {
+                &BumpType::Minor
             }
-            BumpType::Patch => {
-                new_version.patch += 1;
+            BumpType::Minor if current.major == 0 && self.config.version.respect_zerover => {
+                &BumpType::Patch
             }
-            BumpType::None => {
-                // No version bump needed
-                return Ok(current.clone());
+            other => other,
+        };
+
+        if *bump_type == BumpType::None {
+            // No version bump needed
+            return Ok(current.clone());
+        }
+
+        let base = Version::new(current.major, current.minor, current.patch);
+        let versioning = self
+            .config
+            .version
+            .versioning()
+            .map_err(ReleaseError::Config)?;
+        let new_base = if versioning == Versioning::CalVer {
+            // The date decides the version; the bump only decides whether to release
+            let date = self
+                .release_date
+                .unwrap_or_else(|| chrono::Utc::now().date_naive());
+            next_calver(current, date)
+        } else if current.pre.is_empty() {
+            bump_version(&base, bump_type)
+        } else {
+            // A prerelease already carries its bump: `1.2.0-rc.1` covers anything up to a minor
+            let covered = match bump_type {
+                BumpType::Major => base.minor == 0 && base.patch == 0,
+                BumpType::Minor => base.patch == 0,
+                _ => true,
+            };
+            if covered {
+                base.clone()
+            } else {
+                bump_version(&base, bump_type)
             }
+        };
+
+        let Some(channel) = self.config.version.prerelease.as_deref() else {
+            // Off a channel the final version is released, promoting any prerelease
+            return Ok(new_base);
+        };
+
+        // Continue after the highest number tagged on this base and channel, counting the
+        // current version too, so switching channels never reuses an existing tag
+        let current_tag = self.config.version.tag_name(current);
+        let tag_names = self.known_tag_names()?;
+        self.next_prerelease(
+            &new_base,
+            channel,
+            tag_names
+                .iter()
+                .map(String::as_str)
+                .chain(std::iter::once(current_tag.as_str())),
+        )
+    }
+
+    /// The tag names read from GitHub, else those of the local repository, if there is one
+    fn known_tag_names(&self) -> std::result::Result<Vec<String>, ReleaseError> {
+        if let Some(tag_names) = self.tag_names.get() {
+            return Ok(tag_names.clone());
         }
+        let Some(repo) = self.repo else {
+            return Ok(Vec::new());
+        };
+        Ok(repo
+            .tag_names(None)?
+            .iter()
+            .flatten()
+            .map(str::to_string)
+            .collect())
+    }
 
-        Ok(new_version)
+    async fn get_version_from_git_tags(&self) -> std::result::Result<Version, ReleaseError> {
+        let (Some(scm_client), Some(repo_info)) = (self.scm_client, self.repo_info) else {
+            return Err("Repository information is required to list tags from GitHub".into());
+        };
+        let tags = scm_client.get_tags(repo_info).await?;
+        let _ = self
+            .tag_names
+            .set(tags.iter().map(|tag| tag.name.clone()).collect());
+
+        match self.select_latest_version(tags.iter().map(|tag| tag.name.as_str())) {
+            Some(version) => Ok(version),
+            None => self.initial_version(),
+        }
     }
 
-    async fn get_version_from_git_tags(
+    /// Determine the current version from the tags in a local repository
+    pub fn get_version_from_local_tags(
         &self,
-    ) -> std::result::Result<Version, Box<dyn std::error::Error>> {
-        let github_client = GitHubClient::new(
-            std::env::var("GITHUB_TOKEN")
-                .map_err(|_| "GITHUB_TOKEN environment variable is required")?,
-        )?;
+        repo: &git2::Repository,
+    ) -> std::result::Result<Version, ReleaseError> {
+        if let Some(version) = &self.current_version {
+            return Ok(version.clone());
+        }
 
-        let tags = github_client.get_tags(self.repo_info).await?;
+        match self.latest_local_version(repo)? {
+            Some(version) => Ok(version),
+            None => self.initial_version(),
+        }
+    }
 
-        let tag_prefix = self.config.version.tag_prefix.as_deref().unwrap_or("");
-        let tag_suffix = self.config.version.tag_suffix.as_deref().unwrap_or("");
+    /// The highest version among the tags of `repo`, if any tag carries one
+    fn latest_local_version(
+        &self,
+        repo: &git2::Repository,
+    ) -> std::result::Result<Option<Version>, ReleaseError> {
+        let tag_names = repo.tag_names(None)?;
+        Ok(self.select_latest_version(tag_names.iter().flatten()))
+    }
 
-        let mut versions = Vec::new();
+    fn initial_version(&self) -> std::result::Result<Version, ReleaseError> {
+        // No valid version tags found, use initial version
+        let initial = self
+            .config
+            .version
+            .initial_version
+            .as_deref()
+            .unwrap_or("0.1.0");
+        Version::parse(initial).map_err(|e| {
+            ReleaseError::InvalidVersion(format!("Invalid initial version {}: {}", initial, e))
+        })
+    }
 
-        for tag in tags {
-            let tag_name = &tag.name;
+    /// Pick the highest version among tag names matching the configured prefix/suffix
+    pub fn select_latest_version<'t>(
+        &self,
+        tag_names: impl Iterator<Item = &'t str>,
+    ) -> Option<Version> {
+        let prerelease_order = self
+            .config
+            .version
+            .prerelease_order
+            .as_deref()
+            .unwrap_or_default();
 
-            // Remove prefix and suffix
-            let mut version_str = tag_name.as_str();
-            if !tag_prefix.is_empty() && tag_name.starts_with(tag_prefix) {
-                version_str = &tag_name[tag_prefix.len()..];
-            }
-            if !tag_suffix.is_empty() && version_str.ends_with(tag_suffix) {
-                version_str = &version_str[..version_str.len() - tag_suffix.len()];
-            }
+        let stable_only = self.config.version.ignores_prerelease_tags();
+        let mut versions: Vec<Version> = tag_names
+            .filter_map(|tag_name| self.version_from_tag(tag_name))
+            .filter(|version| !stable_only || version.pre.is_empty())
+            .collect();
+
+        // Return the highest version
+        versions.sort_by(|a, b| compare_versions(a, b, prerelease_order));
+        versions.into_iter().last()
+    }
+
+    /// The next `<base>-<channel>.N` version, continuing after the highest `N` already tagged.
+    ///
+    /// Starts at `N = 1` when no tag with this base version and channel exists yet.
+    pub fn next_prerelease<'t>(
+        &self,
+        base: &Version,
+        channel: &str,
+        tag_names: impl Iterator<Item = &'t str>,
+    ) -> std::result::Result<Version, ReleaseError> {
+        let highest = tag_names
+            .filter_map(|tag_name| self.version_from_tag(tag_name))
+            .filter(|version| {
+                (version.major, version.minor, version.patch)
+                    == (base.major, base.minor, base.patch)
+            })
+            .filter_map(|version| {
+                let (label, number) = version.pre.as_str().split_once('.')?;
+                if label != channel {
+                    return None;
+                }
+                number.parse::<u64>().ok()
+            })
+            .max()
+            .unwrap_or(0);
+
+        let mut next = Version::new(base.major, base.minor, base.patch);
+        next.pre =
+            semver::Prerelease::new(&format!("{}.{}", channel, highest + 1)).map_err(|e| {
+                ReleaseError::InvalidVersion(format!(
+                    "Invalid prerelease channel {:?}: {}",
+                    channel, e
+                ))
+            })?;
+        Ok(next)
+    }
+
+    /// Parse the version out of a tag name carrying the configured prefix/separator/suffix
+    fn version_from_tag(&self, tag_name: &str) -> Option<Version> {
+        Version::parse(self.config.version.version_part(tag_name)).ok()
+    }
+}
+
+/// Increment the part of a stable `version` selected by `bump_type`
+fn bump_version(version: &Version, bump_type: &BumpType) -> Version {
+    match bump_type {
+        BumpType::Major => Version::new(version.major + 1, 0, 0),
+        BumpType::Minor => Version::new(version.major, version.minor + 1, 0),
+        BumpType::Patch => Version::new(version.major, version.minor, version.patch + 1),
+        BumpType::None => version.clone(),
+    }
+}
+
+/// The `YYYY.MM.PATCH` version following `current` for a release on `date`: the patch counts
+/// up within a month and restarts at 0 in the next one. A prerelease of this month's next
+/// version is covered already.
+fn next_calver(current: &Version, date: chrono::NaiveDate) -> Version {
+    use chrono::Datelike;
+
+    let (year, month) = (date.year() as u64, u64::from(date.month()));
+    if (current.major, current.minor) != (year, month) {
+        return Version::new(year, month, 0);
+    }
+    if current.pre.is_empty() {
+        Version::new(year, month, current.patch + 1)
+    } else {
+        Version::new(year, month, current.patch)
+    }
+}
+
+/// Compare two versions, ordering prerelease labels of the same base version
+/// by their position in `prerelease_order` (e.g. `nightly < beta < rc`).
+///
+/// Versions with different bases, stable versions, and labels that aren't in
+/// the list fall back to semver precedence.
+pub fn compare_versions(a: &Version, b: &Version, prerelease_order: &[String]) -> Ordering {
+    let same_base = (a.major, a.minor, a.patch) == (b.major, b.minor, b.patch);
+    if !same_base || a.pre.is_empty() || b.pre.is_empty() {
+        return a.cmp(b);
+    }
+
+    let rank = |version: &Version| {
+        let label = version.pre.as_str().split('.').next().unwrap_or_default();
+        prerelease_order.iter().position(|known| known == label)
+    };
+
+    match (rank(a), rank(b)) {
+        (Some(rank_a), Some(rank_b)) if rank_a != rank_b => rank_a.cmp(&rank_b),
+        _ => a.cmp(b),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scm::github::GitHubClient;
+
+    fn create_repo_info() -> RepositoryInfo {
+        RepositoryInfo {
+            full_name: "owner/repo".to_string(),
+            default_branch: "main".to_string(),
+        }
+    }
+
+    fn channel_order() -> Vec<String> {
+        vec!["nightly".to_string(), "beta".to_string(), "rc".to_string()]
+    }
+
+    #[test]
+    fn test_compare_versions_uses_custom_prerelease_order() {
+        let nightly = Version::parse("1.2.0-nightly.5").unwrap();
+        let beta = Version::parse("1.2.0-beta.1").unwrap();
+        let rc = Version::parse("1.2.0-rc.1").unwrap();
+        let stable = Version::parse("1.2.0").unwrap();
+
+        assert_eq!(
+            compare_versions(&nightly, &beta, &channel_order()),
+            Ordering::Less
+        );
+        assert_eq!(
+            compare_versions(&beta, &rc, &channel_order()),
+            Ordering::Less
+        );
+        assert_eq!(
+            compare_versions(&rc, &stable, &channel_order()),
+            Ordering::Less
+        );
+        // Without an order, semver compares labels lexically
+        assert_eq!(compare_versions(&nightly, &beta, &[]), Ordering::Greater);
+    }
+
+    #[tokio::test]
+    async fn test_supplied_current_version_skips_tag_lookup() {
+        let config = Config::default();
+        let repo_info = create_repo_info();
+        let client = GitHubClient::new("test-token".to_string()).unwrap();
+        // Without a token or network access the tags API call would fail
+        let manager = VersionManager::new(&config, &client, &repo_info)
+            .with_current_version(Version::parse("2.7.1").unwrap());
+
+        let current = manager.get_current_version().await.unwrap();
+        assert_eq!(current, Version::parse("2.7.1").unwrap());
+
+        let next = manager
+            .calculate_new_version(&current, &BumpType::Minor)
+            .unwrap();
+        assert_eq!(next, Version::parse("2.8.0").unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_tags_are_listed_through_the_supplied_client() {
+        let mut server = mockito::Server::new_async().await;
+        // The client authenticates with its own token; nothing reads GITHUB_TOKEN
+        let mock = server
+            .mock("GET", "/repos/owner/repo/tags")
+            .match_query(mockito::Matcher::Any)
+            .match_header("authorization", "Bearer client-token")
+            .with_status(200)
+            .with_body(r#"[{"name": "v1.4.2", "commit": {"sha": "a", "url": "u"}}]"#)
+            .create_async()
+            .await;
+
+        let config = Config::default();
+        let repo_info = create_repo_info();
+        let client = GitHubClient::new("client-token".to_string())
+            .unwrap()
+            .with_base_url(server.url());
+        let manager = VersionManager::new(&config, &client, &repo_info);
+
+        let current = manager.get_current_version().await.unwrap();
+        assert_eq!(current, Version::parse("1.4.2").unwrap());
+        mock.assert_async().await;
+    }
 
-            if let Ok(version) = Version::parse(version_str) {
-                versions.push(version);
+    #[tokio::test]
+    async fn test_next_prerelease_number_from_github_tags() {
+        let mut server = mockito::Server::new_async().await;
+        server
+            .mock("GET", "/repos/owner/repo/tags")
+            .match_query(mockito::Matcher::Any)
+            .with_status(200)
+            .with_body(
+                r#"[{"name": "v1.4.0-rc.2", "commit": {"sha": "b", "url": "u"}},
+                    {"name": "v1.4.0-rc.1", "commit": {"sha": "a", "url": "u"}}]"#,
+            )
+            .create_async()
+            .await;
+
+        let mut config = Config::default();
+        config.version.prerelease = Some("rc".to_string());
+        let repo_info = create_repo_info();
+        let client = GitHubClient::new("test-token".to_string())
+            .unwrap()
+            .with_base_url(server.url());
+        let manager = VersionManager::new(&config, &client, &repo_info);
+
+        manager.get_current_version().await.unwrap();
+        // The tags read from GitHub decide the number, not only the version bumped from
+        let next = manager
+            .calculate_new_version(&Version::parse("1.3.0").unwrap(), &BumpType::Minor)
+            .unwrap();
+        assert_eq!(next, Version::parse("1.4.0-rc.3").unwrap());
+    }
+
+    /// A repository with one commit carrying each of `tags`
+    fn repo_with_tags(tags: &[&str]) -> (tempfile::TempDir, git2::Repository) {
+        let temp_dir = tempfile::TempDir::new().expect("Failed to create temp directory");
+        let repo = git2::Repository::init(temp_dir.path()).expect("Failed to init repository");
+        {
+            let sig = git2::Signature::now("Test User", "test@example.com").unwrap();
+            let tree = repo
+                .find_tree(repo.index().unwrap().write_tree().unwrap())
+                .unwrap();
+            let oid = repo
+                .commit(Some("HEAD"), &sig, &sig, "feat: initial", &tree, &[])
+                .unwrap();
+            let commit = repo.find_object(oid, None).unwrap();
+            for tag in tags {
+                repo.tag_lightweight(tag, &commit, false).unwrap();
             }
         }
+        (temp_dir, repo)
+    }
+
+    #[tokio::test]
+    async fn test_local_tag_source_skips_the_api() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("GET", "/repos/owner/repo/tags")
+            .match_query(mockito::Matcher::Any)
+            .expect(0)
+            .create_async()
+            .await;
+        let (_temp_dir, repo) = repo_with_tags(&["v1.2.0", "v1.10.0", "v1.9.3", "other-2.0.0"]);
+
+        let mut config = Config::default();
+        config.version.tag_source = Some("local".to_string());
+        let repo_info = create_repo_info();
+        let client = GitHubClient::new("test-token".to_string())
+            .unwrap()
+            .with_base_url(server.url());
+        let manager = VersionManager::new(&config, &client, &repo_info).with_repository(&repo);
+
+        let current = manager.get_current_version().await.unwrap();
+        assert_eq!(current, Version::parse("1.10.0").unwrap());
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_local_tag_source_falls_back_to_the_api() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("GET", "/repos/owner/repo/tags")
+            .match_query(mockito::Matcher::Any)
+            .with_status(200)
+            .with_body(r#"[{"name": "v3.1.0", "commit": {"sha": "a", "url": "u"}}]"#)
+            .create_async()
+            .await;
+        // e.g. a checkout that fetched no tags
+        let (_temp_dir, repo) = repo_with_tags(&["nightly"]);
+
+        let mut config = Config::default();
+        config.version.tag_source = Some("local".to_string());
+        let repo_info = create_repo_info();
+        let client = GitHubClient::new("test-token".to_string())
+            .unwrap()
+            .with_base_url(server.url());
+        let manager = VersionManager::new(&config, &client, &repo_info).with_repository(&repo);
+
+        let current = manager.get_current_version().await.unwrap();
+        assert_eq!(current, Version::parse("3.1.0").unwrap());
+        mock.assert_async().await;
+    }
+
+    #[test]
+    fn test_calculate_new_version_before_1_0() {
+        let config = Config::default();
+        let manager = VersionManager::offline(&config);
+        let current = Version::parse("0.4.2").unwrap();
+        let bump = |bump_type| manager.calculate_new_version(&current, &bump_type).unwrap();
+
+        assert_eq!(bump(BumpType::Major), Version::parse("0.5.0").unwrap());
+        assert_eq!(bump(BumpType::Minor), Version::parse("0.4.3").unwrap());
+        assert_eq!(bump(BumpType::Patch), Version::parse("0.4.3").unwrap());
+        assert_eq!(bump(BumpType::None), current);
+    }
+
+    #[test]
+    fn test_calculate_new_version_zerover_opt_out() {
+        let mut config = Config::default();
+        config.version.respect_zerover = false;
+        let manager = VersionManager::offline(&config);
+        let current = Version::parse("0.4.2").unwrap();
+        let bump = |bump_type| manager.calculate_new_version(&current, &bump_type).unwrap();
+
+        assert_eq!(bump(BumpType::Major), Version::parse("1.0.0").unwrap());
+        assert_eq!(bump(BumpType::Minor), Version::parse("0.5.0").unwrap());
+        assert_eq!(bump(BumpType::Patch), Version::parse("0.4.3").unwrap());
+    }
+
+    #[test]
+    fn test_calculate_new_version_on_prerelease_channel() {
+        let mut config = Config::default();
+        config.version.prerelease = Some("rc".to_string());
+        let manager = VersionManager::offline(&config);
+        let next = |current: &str, bump_type| {
+            manager
+                .calculate_new_version(&Version::parse(current).unwrap(), &bump_type)
+                .unwrap()
+                .to_string()
+        };
+
+        assert_eq!(next("1.1.4", BumpType::Minor), "1.2.0-rc.1");
+        assert_eq!(next("1.2.0-rc.1", BumpType::Patch), "1.2.0-rc.2");
+        assert_eq!(next("1.2.0-rc.1", BumpType::Minor), "1.2.0-rc.2");
+        // A larger bump than the prerelease carries moves to a new base
+        assert_eq!(next("1.2.0-rc.2", BumpType::Major), "2.0.0-rc.1");
+        // Switching channels restarts the numbering
+        assert_eq!(next("1.2.0-beta.3", BumpType::Patch), "1.2.0-rc.1");
+    }
+
+    #[test]
+    fn test_prerelease_number_continues_after_existing_tags() {
+        let (_temp_dir, repo) = repo_with_tags(&["v1.2.0-rc.1", "v1.2.0-beta.1", "v1.2.0-beta.2"]);
+        let mut config = Config::default();
+        config.version.prerelease = Some("rc".to_string());
+        let manager = VersionManager::offline(&config).with_repository(&repo);
+
+        // Back on rc after beta, rc.1 is already taken
+        let next = manager
+            .calculate_new_version(&Version::parse("1.2.0-beta.2").unwrap(), &BumpType::Patch)
+            .unwrap();
+        assert_eq!(next, Version::parse("1.2.0-rc.2").unwrap());
+    }
+
+    #[test]
+    fn test_calculate_new_version_calver() {
+        let mut config = Config::default();
+        config.version.versioning = Some("calver".to_string());
+        let next = |current: &str, date: (i32, u32, u32), bump_type| {
+            VersionManager::offline(&config)
+                .with_release_date(chrono::NaiveDate::from_ymd_opt(date.0, date.1, date.2).unwrap())
+                .calculate_new_version(&Version::parse(current).unwrap(), &bump_type)
+                .unwrap()
+                .to_string()
+        };
+
+        // First release in a month, whatever the bump
+        assert_eq!(next("0.1.0", (2024, 10, 3), BumpType::Major), "2024.10.0");
+        assert_eq!(
+            next("2024.9.4", (2024, 10, 3), BumpType::Patch),
+            "2024.10.0"
+        );
+        // Another release in the same month
+        assert_eq!(
+            next("2024.10.0", (2024, 10, 21), BumpType::Minor),
+            "2024.10.1"
+        );
+        // The month and the year roll over
+        assert_eq!(
+            next("2024.10.1", (2024, 11, 1), BumpType::Patch),
+            "2024.11.0"
+        );
+        assert_eq!(next("2024.12.7", (2025, 1, 2), BumpType::Patch), "2025.1.0");
+        // No bump, no release
+        assert_eq!(
+            next("2024.10.1", (2024, 11, 1), BumpType::None),
+            "2024.10.1"
+        );
+    }
+
+    #[test]
+    fn test_calculate_new_version_promotes_prerelease() {
+        let config = Config::default();
+        let manager = VersionManager::offline(&config);
+        let current = Version::parse("1.2.0-rc.2").unwrap();
+
+        let promoted = manager
+            .calculate_new_version(&current, &BumpType::Patch)
+            .unwrap();
+        assert_eq!(promoted, Version::parse("1.2.0").unwrap());
+
+        let promoted = manager
+            .calculate_new_version(&current, &BumpType::Major)
+            .unwrap();
+        assert_eq!(promoted, Version::parse("2.0.0").unwrap());
+    }
+
+    #[test]
+    fn test_select_latest_version_orders_prerelease_numbers() {
+        let config = Config::default();
+        let manager = VersionManager::offline(&config);
+
+        let tags = ["v1.1.0", "v1.2.0-rc.2", "v1.2.0-rc.10", "v1.2.0-rc.9"];
+        let latest = manager.select_latest_version(tags.into_iter()).unwrap();
+        assert_eq!(latest, Version::parse("1.2.0-rc.10").unwrap());
+
+        let tags = ["v1.2.0-rc.10", "v1.2.0"];
+        let latest = manager.select_latest_version(tags.into_iter()).unwrap();
+        assert_eq!(latest, Version::parse("1.2.0").unwrap());
+    }
+
+    #[test]
+    fn test_next_prerelease_continues_after_existing_tags() {
+        let config = Config::default();
+        let manager = VersionManager::offline(&config);
+        let base = Version::parse("1.4.0").unwrap();
+        let tags = [
+            "v1.3.0",
+            "v1.4.0-rc.1",
+            "v1.4.0-rc.2",
+            "v1.4.0-beta.7",
+            "v1.5.0-rc.9",
+        ];
+
+        let next = manager
+            .next_prerelease(&base, "rc", tags.into_iter())
+            .unwrap();
+        assert_eq!(next, Version::parse("1.4.0-rc.3").unwrap());
+
+        let first = manager
+            .next_prerelease(&base, "alpha", tags.into_iter())
+            .unwrap();
+        assert_eq!(first, Version::parse("1.4.0-alpha.1").unwrap());
+    }
+
+    #[test]
+    fn test_select_latest_version_with_custom_prerelease_order() {
+        let mut config = Config::default();
+        config.version.prerelease_order = Some(channel_order());
+        let repo_info = create_repo_info();
+        let client = GitHubClient::new("test-token".to_string()).unwrap();
+        let manager = VersionManager::new(&config, &client, &repo_info);
 
-        if versions.is_empty() {
-            // No valid version tags found, use initial version
-            let initial = self
-                .config
-                .version
-                .initial_version
-                .as_deref()
-                .unwrap_or("0.1.0");
-            return Version::parse(initial)
-                .map_err(|e| format!("Invalid initial version {}: {}", initial, e).into());
+        let tags = ["v1.1.0", "v1.2.0-nightly.3", "v1.2.0-rc.1", "v1.2.0-beta.2"];
+        let latest = manager.select_latest_version(tags.into_iter()).unwrap();
+
+        assert_eq!(latest, Version::parse("1.2.0-rc.1").unwrap());
+    }
+
+    #[test]
+    fn test_get_version_from_local_tags() {
+        let temp_dir = tempfile::TempDir::new().expect("Failed to create temp directory");
+        let repo = git2::Repository::init(temp_dir.path()).expect("Failed to init repository");
+        let config = Config::default();
+        let manager = VersionManager::offline(&config);
+
+        let sig = git2::Signature::now("Test User", "test@example.com").unwrap();
+        let tree_id = repo.index().unwrap().write_tree().unwrap();
+        let tree = repo.find_tree(tree_id).unwrap();
+        let oid = repo
+            .commit(Some("HEAD"), &sig, &sig, "feat: initial", &tree, &[])
+            .unwrap();
+
+        // Without tags the initial version is used
+        assert_eq!(
+            manager.get_version_from_local_tags(&repo).unwrap(),
+            Version::parse("0.1.0").unwrap()
+        );
+
+        let commit = repo.find_object(oid, None).unwrap();
+        for tag in ["v1.0.0", "v1.10.0", "v1.9.3", "not-a-version"] {
+            repo.tag_lightweight(tag, &commit, false).unwrap();
         }
 
-        // Return the highest version
-        versions.sort();
-        Ok(versions.into_iter().last().unwrap())
+        assert_eq!(
+            manager.get_version_from_local_tags(&repo).unwrap(),
+            Version::parse("1.10.0").unwrap()
+        );
+    }
+
+    #[test]
+    fn test_select_latest_version_default_ordering() {
+        let config = Config::default();
+        let repo_info = create_repo_info();
+        let client = GitHubClient::new("test-token".to_string()).unwrap();
+        let manager = VersionManager::new(&config, &client, &repo_info);
+
+        let tags = ["v1.2.0-beta.2", "v1.2.0-nightly.3"];
+        let latest = manager.select_latest_version(tags.into_iter()).unwrap();
+
+        assert_eq!(latest, Version::parse("1.2.0-nightly.3").unwrap());
+    }
+
+    #[test]
+    fn test_stable_tags_only() {
+        let tags = ["v1.1.4", "v1.2.0-rc.2", "v1.2.0-rc.1"];
+        let mut config = Config::default();
+        let latest = |config: &Config| {
+            VersionManager::offline(config)
+                .select_latest_version(tags.into_iter())
+                .unwrap()
+        };
+
+        // By default the newest prerelease is current, so it gets promoted next
+        assert_eq!(latest(&config), Version::parse("1.2.0-rc.2").unwrap());
+
+        config.version.stable_tags_only = true;
+        assert_eq!(latest(&config), Version::parse("1.1.4").unwrap());
+
+        // A prerelease flow keeps seeing its own tags
+        config.version.prerelease = Some("rc".to_string());
+        assert_eq!(latest(&config), Version::parse("1.2.0-rc.2").unwrap());
     }
 }
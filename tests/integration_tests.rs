@@ -9,7 +9,7 @@ fn test_docker_git_ownership() {
 
     // Initialize git repo
     let init_output = Command::new("git")
-        .args(&["init"])
+        .args(["init"])
         .current_dir(temp_dir.path())
         .output()
         .expect("Failed to run git init");
@@ -21,32 +21,32 @@ fn test_docker_git_ownership() {
         .expect("Failed to write test file");
 
     Command::new("git")
-        .args(&["add", "."])
+        .args(["add", "."])
         .current_dir(temp_dir.path())
         .output()
         .expect("Failed to run git add");
 
     Command::new("git")
-        .args(&["config", "user.email", "test@example.com"])
+        .args(["config", "user.email", "test@example.com"])
         .current_dir(temp_dir.path())
         .output()
         .expect("Failed to set git email");
 
     Command::new("git")
-        .args(&["config", "user.name", "Test User"])
+        .args(["config", "user.name", "Test User"])
         .current_dir(temp_dir.path())
         .output()
         .expect("Failed to set git name");
 
     Command::new("git")
-        .args(&["commit", "-m", "feat: test commit"])
+        .args(["commit", "-m", "feat: test commit"])
         .current_dir(temp_dir.path())
         .output()
         .expect("Failed to run git commit");
 
     // Build the Docker image
     let build_output = Command::new("docker")
-        .args(&["build", "-t", "conventional-release-test", "."])
+        .args(["build", "-t", "conventional-release-test", "."])
         .output()
         .expect("Failed to run docker build");
 
@@ -58,7 +58,7 @@ fn test_docker_git_ownership() {
 
     // Run the Docker container with the test repository mounted
     let run_output = Command::new("docker")
-        .args(&[
+        .args([
             "run",
             "--rm",
             "-v",
@@ -118,3 +118,74 @@ fn test_git_safe_directory_config() {
         "Should be able to access HEAD after opening repository"
     );
 }
+
+#[test]
+fn test_quiet_no_release_run_prints_nothing() {
+    let temp_dir = TempDir::new().expect("Failed to create temp directory");
+    let repo = git2::Repository::init(temp_dir.path()).expect("Failed to init repository");
+    let sig = git2::Signature::now("Test User", "test@example.com").unwrap();
+    let tree = repo
+        .find_tree(repo.index().unwrap().write_tree().unwrap())
+        .unwrap();
+    repo.commit(Some("HEAD"), &sig, &sig, "chore: initial", &tree, &[])
+        .unwrap();
+    let config_file = temp_dir.path().join(".release-config.toml");
+    std::fs::write(&config_file, "[version]\ntag_prefix = \"v\"\n").unwrap();
+
+    let mut server = mockito::Server::new();
+    let _repository = server
+        .mock("GET", "/repos/owner/repo")
+        .with_body(
+            r#"{"id": 1, "name": "repo", "full_name": "owner/repo",
+                "owner": {"login": "owner"}, "default_branch": "main"}"#,
+        )
+        .create();
+    let _releases = server
+        .mock("GET", "/repos/owner/repo/releases")
+        .match_query(mockito::Matcher::Any)
+        .with_body("[]")
+        .create();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_conventional-release-action"))
+        .args(["--quiet", "--repository", "owner/repo"])
+        .args(["--current-version", "1.0.0"])
+        .arg("--config-file")
+        .arg(&config_file)
+        .arg("--working-directory")
+        .arg(temp_dir.path())
+        .env("GITHUB_API_URL", server.url())
+        .env("GITHUB_TOKEN", "test-token")
+        .env("SAFE_DIRECTORY_SCOPE", "none")
+        .env_remove("GITHUB_ACTIONS")
+        .env_remove("GITHUB_EVENT_NAME")
+        .env_remove("ACTION_ENABLED")
+        .output()
+        .expect("Failed to run the action");
+
+    assert!(
+        output.status.success(),
+        "Run failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "");
+}
+
+#[test]
+fn test_fatal_error_is_reported_on_stderr() {
+    let temp_dir = TempDir::new().expect("Failed to create temp directory");
+    let config_file = temp_dir.path().join(".release-config.toml");
+    std::fs::write(&config_file, "[version]\ninitial_version = \"1.0\"\n").unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_conventional-release-action"))
+        .arg("--config-file")
+        .arg(&config_file)
+        .env_remove("GITHUB_ACTIONS")
+        .env_remove("ACTION_ENABLED")
+        .output()
+        .expect("Failed to run the action");
+
+    assert_eq!(output.status.code(), Some(2));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("Invalid initial_version"), "{}", stderr);
+    assert!(!String::from_utf8_lossy(&output.stdout).contains("Invalid initial_version"));
+}
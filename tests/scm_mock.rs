@@ -0,0 +1,572 @@
+use async_trait::async_trait;
+use conventional_release_action::cli::Args;
+use conventional_release_action::config::{Config, FileUpdateConfig, PackageConfig};
+use conventional_release_action::error::ReleaseError;
+use conventional_release_action::output::{NoReleaseReason, Outcome};
+use conventional_release_action::scm::git::SafeDirectoryScope;
+use conventional_release_action::scm::{PullRequest, Release, RepositoryInfo, ScmClient, Tag};
+use conventional_release_action::ReleaseApplication;
+use git2::{Repository, Signature};
+use semver::Version;
+use std::cell::RefCell;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+use tempfile::TempDir;
+
+/// An in-memory forge: serves fixed tags and records the releases created on it
+#[derive(Default)]
+struct MockScm {
+    tags: Vec<(String, String)>,
+    created: Rc<RefCell<Vec<(String, String)>>>,
+    /// Reject every asset upload, as a flaky upload endpoint would
+    fail_uploads: bool,
+}
+
+fn release(tag_name: &str, body: &str) -> Release {
+    Release {
+        id: 1,
+        tag_name: tag_name.to_string(),
+        name: Some(tag_name.to_string()),
+        body: Some(body.to_string()),
+        draft: false,
+        prerelease: false,
+        url: format!("https://example.com/owner/repo/releases/tag/{}", tag_name),
+        upload_url: "https://example.com/uploads{?name,label}".to_string(),
+    }
+}
+
+#[async_trait(?Send)]
+impl ScmClient for MockScm {
+    async fn get_repository_info(&self, repo: &str) -> Result<RepositoryInfo, ReleaseError> {
+        Ok(RepositoryInfo {
+            full_name: repo.to_string(),
+            default_branch: "main".to_string(),
+        })
+    }
+
+    async fn get_tags(&self, _repo: &RepositoryInfo) -> Result<Vec<Tag>, ReleaseError> {
+        Ok(self
+            .tags
+            .iter()
+            .map(|(name, sha)| Tag {
+                name: name.clone(),
+                sha: sha.clone(),
+            })
+            .collect())
+    }
+
+    async fn get_release_by_tag(
+        &self,
+        _repo: &RepositoryInfo,
+        _tag_name: &str,
+    ) -> Result<Option<Release>, ReleaseError> {
+        Ok(None)
+    }
+
+    async fn list_releases(&self, _repo: &RepositoryInfo) -> Result<Vec<Release>, ReleaseError> {
+        Ok(Vec::new())
+    }
+
+    async fn get_pull_requests_for_commit(
+        &self,
+        _repo: &RepositoryInfo,
+        _commit_sha: &str,
+    ) -> Result<Vec<PullRequest>, ReleaseError> {
+        Ok(Vec::new())
+    }
+
+    async fn create_release(
+        &self,
+        _repo: &RepositoryInfo,
+        version: &Version,
+        config: &Config,
+        _target_commit_sha: &str,
+        release_body: String,
+    ) -> Result<Release, ReleaseError> {
+        let tag_name = config.version.tag_name(version);
+        self.created
+            .borrow_mut()
+            .push((tag_name.clone(), release_body.clone()));
+        Ok(release(&tag_name, &release_body))
+    }
+
+    async fn upload_release_asset(
+        &self,
+        _release: &Release,
+        _path: &Path,
+        name: &str,
+    ) -> Result<(), ReleaseError> {
+        if self.fail_uploads {
+            return Err(format!("Failed to upload release asset {}", name).into());
+        }
+        Ok(())
+    }
+}
+
+fn commit(repo: &Repository, message: &str) -> git2::Oid {
+    let sig = Signature::now("Test User", "test@example.com").unwrap();
+    let tree = repo
+        .find_tree(repo.index().unwrap().write_tree().unwrap())
+        .unwrap();
+    let parent = repo.head().ok().and_then(|head| head.peel_to_commit().ok());
+    let parents: Vec<&git2::Commit> = parent.iter().collect();
+    repo.commit(Some("HEAD"), &sig, &sig, message, &tree, &parents)
+        .unwrap()
+}
+
+fn args(working_directory: PathBuf) -> Args {
+    Args {
+        config_file: working_directory.join(".release-config.toml"),
+        dry_run: false,
+        working_directory,
+        self_test: false,
+        validate_config: false,
+        since: None,
+        since_tag: None,
+        repository: Some("owner/repo".to_string()),
+        remote: None,
+        token_file: None,
+        message_file: None,
+        explain: false,
+        current_version: None,
+        verbose: false,
+        quiet: false,
+        safe_directory_scope: Some(SafeDirectoryScope::None),
+    }
+}
+
+/// A working repository released as v1.0.0 plus `message`, with a bare `origin` next to it.
+///
+/// Returns the working and remote paths and the released commit.
+fn repository_with_release(root: &Path, message: &str) -> (PathBuf, PathBuf, git2::Oid) {
+    let remote_path = root.join("remote.git");
+    Repository::init_bare(&remote_path).unwrap();
+
+    let work_path = root.join("work");
+    let repo = Repository::init(&work_path).unwrap();
+    repo.remote("origin", remote_path.to_str().unwrap())
+        .unwrap();
+    let released = commit(&repo, "feat: initial");
+    repo.tag_lightweight("v1.0.0", &repo.find_object(released, None).unwrap(), false)
+        .unwrap();
+    commit(&repo, message);
+    // Publish the main line so the remote isn't empty
+    let head = repo.head().unwrap().name().unwrap().to_string();
+    repo.find_remote("origin")
+        .unwrap()
+        .push(&[format!("{}:{}", head, head)], None)
+        .unwrap();
+
+    (work_path, remote_path, released)
+}
+
+#[tokio::test]
+async fn test_run_releases_through_scm_client() {
+    let temp_dir = TempDir::new().expect("Failed to create temp directory");
+    let (work_path, remote_path, released) =
+        repository_with_release(temp_dir.path(), "feat: add exports");
+
+    let scm = MockScm {
+        tags: vec![("v1.0.0".to_string(), released.to_string())],
+        ..MockScm::default()
+    };
+    let created = scm.created.clone();
+    let app = ReleaseApplication::new(args(work_path), Config::default()).with_scm_client(scm);
+
+    let output = app.run().await.unwrap();
+
+    assert_eq!(output.outcome, Outcome::Released);
+    assert_eq!(output.version.as_deref(), Some("1.1.0"));
+    assert_eq!(output.tag.as_deref(), Some("v1.1.0"));
+    assert_eq!(output.previous_version.as_deref(), Some("1.0.0"));
+    assert_eq!(output.bump_type.as_deref(), Some("minor"));
+
+    let created = created.borrow();
+    assert_eq!(created.len(), 1);
+    assert_eq!(created[0].0, "v1.1.0");
+    assert!(
+        created[0].1.contains("add exports"),
+        "Unexpected notes: {}",
+        created[0].1
+    );
+
+    // The release commit and the major version branch reached the remote
+    let remote = Repository::open_bare(&remote_path).unwrap();
+    assert!(remote.find_reference("refs/heads/v1").is_ok());
+}
+
+#[tokio::test]
+async fn test_annotated_release_tag_is_pushed() {
+    let temp_dir = TempDir::new().expect("Failed to create temp directory");
+    let (work_path, remote_path, released) = repository_with_release(temp_dir.path(), "fix: patch");
+
+    let scm = MockScm {
+        tags: vec![("v1.0.0".to_string(), released.to_string())],
+        ..MockScm::default()
+    };
+    let mut config = Config::default();
+    config.release.annotated_tags = true;
+    let app = ReleaseApplication::new(args(work_path), config).with_scm_client(scm);
+
+    let output = app.run().await.unwrap();
+    assert_eq!(output.tag.as_deref(), Some("v1.0.1"));
+
+    let remote = Repository::open_bare(&remote_path).unwrap();
+    let tag = remote
+        .find_reference("refs/tags/v1.0.1")
+        .unwrap()
+        .peel_to_tag()
+        .unwrap();
+    assert_eq!(tag.message(), Some("Release v1.0.1\n"));
+}
+
+#[test]
+fn test_concurrent_releases_in_separate_directories() {
+    let releases = [("fix: patch one", "1.0.1"), ("feat: feature two", "1.1.0")]
+        .into_iter()
+        .map(|(message, expected)| {
+            std::thread::spawn(move || {
+                let temp_dir = TempDir::new().expect("Failed to create temp directory");
+                let (work_path, _, released) = repository_with_release(temp_dir.path(), message);
+                let scm = MockScm {
+                    tags: vec![("v1.0.0".to_string(), released.to_string())],
+                    ..MockScm::default()
+                };
+                let app = ReleaseApplication::new(args(work_path.clone()), Config::default())
+                    .with_scm_client(scm);
+
+                let output = tokio::runtime::Builder::new_current_thread()
+                    .enable_all()
+                    .build()
+                    .unwrap()
+                    .block_on(app.run())
+                    .unwrap();
+
+                assert_eq!(output.version.as_deref(), Some(expected));
+                // Each release commit landed in its own repository
+                let repo = Repository::open(&work_path).unwrap();
+                assert!(repo.revparse_single(&format!("v{}", expected)).is_ok());
+            })
+        })
+        .collect::<Vec<_>>();
+
+    for release in releases {
+        release.join().unwrap();
+    }
+}
+
+#[tokio::test]
+async fn test_dry_run_leaves_repository_untouched() {
+    let temp_dir = TempDir::new().expect("Failed to create temp directory");
+    let (work_path, remote_path, released) =
+        repository_with_release(temp_dir.path(), "feat: add exports");
+    std::fs::write(work_path.join("VERSION"), "1.0.0\n").unwrap();
+
+    let scm = MockScm {
+        tags: vec![("v1.0.0".to_string(), released.to_string())],
+        ..MockScm::default()
+    };
+    let created = scm.created.clone();
+    let mut config = Config::default();
+    config.changelog.enabled = true;
+    config.version.files = Some(vec![FileUpdateConfig {
+        path: "VERSION".to_string(),
+        marker: "1.0.0".to_string(),
+        template: None,
+        run_after: None,
+        regex: false,
+        json_pointer: None,
+        require_match: false,
+    }]);
+    let mut args = args(work_path.clone());
+    args.dry_run = true;
+    let head = Repository::open(&work_path)
+        .unwrap()
+        .head()
+        .unwrap()
+        .target();
+
+    let output = ReleaseApplication::new(args, config)
+        .with_scm_client(scm)
+        .run()
+        .await
+        .unwrap();
+
+    assert_eq!(output.version.as_deref(), Some("1.1.0"));
+    assert!(!output.released);
+    assert_eq!(output.reason, Some(NoReleaseReason::DryRun));
+    assert!(created.borrow().is_empty());
+    assert_eq!(
+        std::fs::read_to_string(work_path.join("VERSION")).unwrap(),
+        "1.0.0\n"
+    );
+    assert!(!work_path.join("CHANGELOG.md").exists());
+    let repo = Repository::open(&work_path).unwrap();
+    assert_eq!(repo.head().unwrap().target(), head);
+    assert!(repo.find_reference("refs/tags/v1.1.0").is_err());
+    let remote = Repository::open_bare(&remote_path).unwrap();
+    assert!(remote.find_reference("refs/heads/v1").is_err());
+}
+
+#[tokio::test]
+async fn test_no_release_reasons() {
+    for (message, reason) in [
+        ("chore: tidy up", NoReleaseReason::NoReleasableCommits),
+        ("feat: hidden [skip release]", NoReleaseReason::SkipMarker),
+    ] {
+        let temp_dir = TempDir::new().expect("Failed to create temp directory");
+        let (work_path, _, released) = repository_with_release(temp_dir.path(), message);
+        let scm = MockScm {
+            tags: vec![("v1.0.0".to_string(), released.to_string())],
+            ..MockScm::default()
+        };
+
+        let output = ReleaseApplication::new(args(work_path), Config::default())
+            .with_scm_client(scm)
+            .run()
+            .await
+            .unwrap();
+
+        assert_eq!(output.outcome, Outcome::NoRelease, "{}", message);
+        assert_eq!(output.reason, Some(reason), "{}", message);
+        assert_eq!(output.bump_type.as_deref(), Some("none"), "{}", message);
+    }
+}
+
+#[tokio::test]
+async fn test_waits_for_minimum_releasable_commits() {
+    for (minimum, outcome) in [(3, Outcome::NoRelease), (2, Outcome::Released)] {
+        let temp_dir = TempDir::new().expect("Failed to create temp directory");
+        let (work_path, _, released) = repository_with_release(temp_dir.path(), "feat: first");
+        let repo = Repository::open(&work_path).unwrap();
+        commit(&repo, "docs: update readme");
+        commit(&repo, "fix: a bug");
+        let scm = MockScm {
+            tags: vec![("v1.0.0".to_string(), released.to_string())],
+            ..MockScm::default()
+        };
+        let created = scm.created.clone();
+        let mut config = Config::default();
+        config.release.min_releasable_commits = Some(minimum);
+
+        let output = ReleaseApplication::new(args(work_path), config)
+            .with_scm_client(scm)
+            .run()
+            .await
+            .unwrap();
+
+        assert_eq!(output.outcome, outcome, "minimum {}", minimum);
+        if outcome == Outcome::NoRelease {
+            assert_eq!(output.reason, Some(NoReleaseReason::BelowMinimumCommits));
+            assert_eq!(output.bump_type.as_deref(), Some("none"));
+            assert!(created.borrow().is_empty());
+        } else {
+            assert_eq!(output.version.as_deref(), Some("1.1.0"));
+        }
+    }
+}
+
+#[tokio::test]
+async fn test_missing_asset_fails_before_publishing() {
+    let temp_dir = TempDir::new().expect("Failed to create temp directory");
+    let (work_path, remote_path, released) =
+        repository_with_release(temp_dir.path(), "feat: add exports");
+    let head = Repository::open(&work_path)
+        .unwrap()
+        .head()
+        .unwrap()
+        .target();
+    let scm = MockScm {
+        tags: vec![("v1.0.0".to_string(), released.to_string())],
+        ..MockScm::default()
+    };
+    let created = scm.created.clone();
+    let mut config = Config::default();
+    config.release.assets = Some(vec!["dist/*.zip".to_string()]);
+
+    let error = ReleaseApplication::new(args(work_path.clone()), config)
+        .with_scm_client(scm)
+        .run()
+        .await
+        .err()
+        .unwrap();
+
+    assert!(error.to_string().contains("matched no files"), "{}", error);
+    assert!(created.borrow().is_empty());
+    // Neither a release commit nor a tag was made
+    let repo = Repository::open(&work_path).unwrap();
+    assert_eq!(repo.head().unwrap().target(), head);
+    let remote = Repository::open_bare(&remote_path).unwrap();
+    assert!(remote.find_reference("refs/tags/v1.1.0").is_err());
+}
+
+#[tokio::test]
+async fn test_temporary_branch_is_deleted_when_publishing_fails() {
+    let temp_dir = TempDir::new().expect("Failed to create temp directory");
+    let (work_path, remote_path, released) =
+        repository_with_release(temp_dir.path(), "feat: add exports");
+    std::fs::create_dir(work_path.join("dist")).unwrap();
+    std::fs::write(work_path.join("dist/app.zip"), "app").unwrap();
+    let scm = MockScm {
+        tags: vec![("v1.0.0".to_string(), released.to_string())],
+        fail_uploads: true,
+        ..MockScm::default()
+    };
+    let mut config = Config::default();
+    config.release.assets = Some(vec!["dist/*.zip".to_string()]);
+
+    let error = ReleaseApplication::new(args(work_path), config)
+        .with_scm_client(scm)
+        .run()
+        .await
+        .err()
+        .unwrap();
+
+    assert!(error.to_string().contains("app.zip"), "{}", error);
+    let remote = Repository::open_bare(&remote_path).unwrap();
+    let branches: Vec<String> = remote
+        .branches(Some(git2::BranchType::Local))
+        .unwrap()
+        .map(|branch| branch.unwrap().0.name().unwrap().unwrap().to_string())
+        .collect();
+    assert!(
+        !branches.iter().any(|name| name.starts_with("release-")),
+        "{:?}",
+        branches
+    );
+}
+
+#[tokio::test]
+async fn test_feature_after_skipped_commit_releases() {
+    let temp_dir = TempDir::new().expect("Failed to create temp directory");
+    let (work_path, _, released) =
+        repository_with_release(temp_dir.path(), "feat: hidden [skip release]");
+    commit(&Repository::open(&work_path).unwrap(), "feat: add exports");
+    let scm = MockScm {
+        tags: vec![("v1.0.0".to_string(), released.to_string())],
+        ..MockScm::default()
+    };
+    let created = scm.created.clone();
+
+    let output = ReleaseApplication::new(args(work_path), Config::default())
+        .with_scm_client(scm)
+        .run()
+        .await
+        .unwrap();
+
+    assert_eq!(output.outcome, Outcome::Released);
+    assert_eq!(output.version.as_deref(), Some("1.1.0"));
+    let created = created.borrow();
+    assert!(created[0].1.contains("add exports"), "{}", created[0].1);
+    assert!(!created[0].1.contains("hidden"), "{}", created[0].1);
+}
+
+#[tokio::test]
+async fn test_package_dry_run_reports_dry_run() {
+    let temp_dir = TempDir::new().expect("Failed to create temp directory");
+    let (work_path, _, _) = repository_with_release(temp_dir.path(), "chore: tidy up");
+    let repo = Repository::open(&work_path).unwrap();
+    std::fs::create_dir(work_path.join("core")).unwrap();
+    std::fs::write(work_path.join("core/lib.rs"), "").unwrap();
+    let mut index = repo.index().unwrap();
+    index.add_path(Path::new("core/lib.rs")).unwrap();
+    index.write().unwrap();
+    commit(&repo, "feat: core exports");
+
+    let config = Config {
+        packages: ["core", "cli"]
+            .into_iter()
+            .map(|name| PackageConfig {
+                name: name.to_string(),
+                path: name.to_string(),
+                tag_prefix: None,
+                initial_version: None,
+                files: None,
+            })
+            .collect(),
+        ..Config::default()
+    };
+    let mut args = args(work_path);
+    args.dry_run = true;
+
+    let output = ReleaseApplication::new(args, config)
+        .with_scm_client(MockScm::default())
+        .run()
+        .await
+        .unwrap();
+
+    assert_eq!(output.outcome, Outcome::Informational);
+    assert_eq!(output.reason, Some(NoReleaseReason::DryRun));
+    assert_eq!(output.releases[0].reason, Some(NoReleaseReason::DryRun));
+    assert_eq!(
+        output.releases[1].reason,
+        Some(NoReleaseReason::NoReleasableCommits)
+    );
+}
+
+#[tokio::test]
+async fn test_message_file_reports_previous_version_and_bump() {
+    let temp_dir = TempDir::new().expect("Failed to create temp directory");
+    let (work_path, _, _) = repository_with_release(temp_dir.path(), "chore: tidy up");
+    std::fs::write(work_path.join("COMMIT_EDITMSG"), "feat: add exports\n").unwrap();
+    let mut args = args(work_path);
+    args.message_file = Some(PathBuf::from("COMMIT_EDITMSG"));
+
+    let output = ReleaseApplication::new(args, Config::default())
+        .with_scm_client(MockScm::default())
+        .run()
+        .await
+        .unwrap();
+
+    assert_eq!(output.version.as_deref(), Some("1.1.0"));
+    assert_eq!(output.previous_version.as_deref(), Some("1.0.0"));
+    assert_eq!(output.bump_type.as_deref(), Some("minor"));
+}
+
+#[tokio::test]
+async fn test_shallow_clone_can_fail_the_run() {
+    let temp_dir = TempDir::new().expect("Failed to create temp directory");
+    let (work_path, _, released) = repository_with_release(temp_dir.path(), "feat: add exports");
+    std::fs::write(work_path.join(".git/shallow"), format!("{}\n", released)).unwrap();
+
+    let mut config = Config::default();
+    config.release.fail_on_shallow_clone = true;
+    let error = ReleaseApplication::new(args(work_path), config)
+        .with_scm_client(MockScm::default())
+        .run()
+        .await
+        .err()
+        .unwrap();
+
+    assert!(matches!(error, ReleaseError::Git(_)));
+    assert!(error.to_string().contains("fetch-depth: 0"), "{}", error);
+}
+
+#[tokio::test]
+async fn test_local_tag_source_fetches_missing_tags() {
+    let temp_dir = TempDir::new().expect("Failed to create temp directory");
+    let (work_path, _, _) = repository_with_release(temp_dir.path(), "fix: patch");
+    // The tag only exists on the remote, as in a checkout made without tags
+    let repo = Repository::open(&work_path).unwrap();
+    repo.find_remote("origin")
+        .unwrap()
+        .push(&["refs/tags/v1.0.0:refs/tags/v1.0.0"], None)
+        .unwrap();
+    repo.tag_delete("v1.0.0").unwrap();
+
+    let mut config = Config::default();
+    config.version.tag_source = Some("local".to_string());
+    config.version.fetch_tags = Some(true);
+    let mut args = args(work_path.clone());
+    args.dry_run = true;
+    // The SCM client lists no tags, so only the fetched one can be found
+    let output = ReleaseApplication::new(args, config)
+        .with_scm_client(MockScm::default())
+        .run()
+        .await
+        .unwrap();
+
+    assert_eq!(output.previous_version.as_deref(), Some("1.0.0"));
+    assert_eq!(output.version.as_deref(), Some("1.0.1"));
+    assert!(repo.find_reference("refs/tags/v1.0.0").is_ok());
+}